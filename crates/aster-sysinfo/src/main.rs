@@ -7,7 +7,7 @@
 use clap::Parser;
 use env_logger::Env;
 use itertools::Itertools;
-use log::{debug, error, info};
+use log::{debug, error, info, warn};
 use regex::Regex;
 use std::cmp::PartialEq;
 use std::collections::HashMap;
@@ -18,15 +18,21 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use std::process::{Command, exit};
 use std::thread::sleep;
-use std::time::{Duration, Instant};
-use sysinfo::{Components, DiskKind, Disks, Networks, System};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sysinfo::{Components, DiskKind, Disks, LoadAvg, Networks, System};
 use tempfile::Builder;
 
+/// `System::load_average()` is not available on Windows and always returns zeros.
+const LOAD_AVERAGE_SUPPORTED: bool = !cfg!(target_os = "windows");
+
 /// Proof of concept sensor value collection for the asterctl screen control tool.
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
     /// Output sensor file.
+    ///
+    /// Supported placeholders, expanded before each write: `{hostname}` system host name,
+    /// `{timestamp}` Unix timestamp in seconds.
     #[arg(short, long)]
     out: Option<PathBuf>,
 
@@ -49,6 +55,31 @@ struct Args {
     #[arg(long)]
     disk_refresh: Option<u16>,
 
+    /// Only include storage devices with a device name matching the RegEx, e.g. `^nvme0n1$`.
+    ///
+    /// Can be specified multiple times. All devices are included if not specified.
+    #[arg(long)]
+    disk_include: Vec<Regex>,
+
+    /// Exclude storage devices with a device name matching the RegEx, e.g. `^sdc$`.
+    ///
+    /// Can be specified multiple times. Takes priority over `--disk-include`.
+    #[arg(long)]
+    disk_exclude: Vec<Regex>,
+
+    /// Temperature component label mapping file.
+    ///
+    /// Each line contains a RegEx matching the component label and the sensor label to use,
+    /// separated by the last `:`. Example: `k10temp Tctl: temperature_cpu`
+    #[arg(long)]
+    temperature_mapping: Option<PathBuf>,
+
+    /// Print the raw temperature component labels with the assigned sensor labels and exit.
+    ///
+    /// Helps to build a `--temperature-mapping` file.
+    #[arg(long)]
+    list_components: bool,
+
     /// Retrieve drive temperature if `disk-update` option is enabled.
     ///
     /// Requires smartctl and password-less sudo!
@@ -66,20 +97,36 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(not(target_os = "linux"))]
     let use_smartctl = false;
 
-    if let Some(out_file) = &args.out
-        && let Some(parent) = out_file.parent()
-    {
-        fs::create_dir_all(parent)?;
+    let host_name = System::host_name().unwrap_or_else(|| "localhost".to_string());
+    if let Some(out_file) = &args.out {
+        // fail early on an invalid output path template
+        expand_out_path(out_file, &host_name, unix_timestamp())?;
     }
     let mut sensors = HashMap::with_capacity(64);
     let mut sysinfo_source = SysinfoSource::new();
+    if let Some(mapping_file) = &args.temperature_mapping {
+        info!("Loading temperature mapping file {mapping_file:?}");
+        sysinfo_source.set_temperature_mapping(read_temperature_mapping(mapping_file)?);
+    }
+
+    if args.list_components {
+        sysinfo_source.refresh();
+        for line in sysinfo_source.component_list() {
+            println!("{line}");
+        }
+        return Ok(());
+    }
 
     let refresh = Duration::from_secs(args.refresh.unwrap_or_default() as u64);
 
     let disk_refresh = Duration::from_secs(args.disk_refresh.unwrap_or_default() as u64);
     let mut disk_refresh_time = Instant::now();
+    let disk_filter = DiskFilter {
+        include: args.disk_include,
+        exclude: args.disk_exclude,
+    };
     if !disk_refresh.is_zero() {
-        update_linux_storage_sensors(&mut sensors, use_smartctl)?;
+        update_linux_storage_sensors(&mut sensors, use_smartctl, &disk_filter)?;
     }
 
     if !refresh.is_zero() {
@@ -97,12 +144,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         if !disk_refresh.is_zero() && disk_refresh_time.elapsed() > disk_refresh {
             debug!("Refreshing individual disks");
-            update_linux_storage_sensors(&mut sensors, use_smartctl)?;
+            update_linux_storage_sensors(&mut sensors, use_smartctl, &disk_filter)?;
             disk_refresh_time = Instant::now();
         }
 
         if let Some(out_file) = &args.out {
-            write_sensor_file(out_file, args.temp_dir.as_deref(), &sensors)?;
+            let out_file = expand_out_path(out_file, &host_name, unix_timestamp())?;
+            write_sensor_file(&out_file, args.temp_dir.as_deref(), &sensors)?;
         }
 
         if args.console {
@@ -126,6 +174,52 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Current Unix timestamp in seconds for the `{timestamp}` output path placeholder.
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Expand the placeholders of the output sensor file path.
+///
+/// Supported placeholders:
+/// - `{hostname}`: system host name.
+/// - `{timestamp}`: Unix timestamp in seconds.
+///
+/// returns: the expanded path, or an error for an unknown or unclosed placeholder.
+fn expand_out_path(template: &Path, host_name: &str, timestamp: u64) -> Result<PathBuf, String> {
+    let Some(mut rest) = template.to_str() else {
+        // placeholders are only supported in UTF-8 paths
+        return Ok(template.to_path_buf());
+    };
+
+    let mut path = String::with_capacity(rest.len());
+    while let Some(start) = rest.find('{') {
+        path.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| {
+            format!(
+                "Unclosed placeholder in output path {template:?}: {}",
+                &rest[start..]
+            )
+        })? + start;
+        match &rest[start + 1..end] {
+            "hostname" => path.push_str(host_name),
+            "timestamp" => path.push_str(&timestamp.to_string()),
+            name => {
+                return Err(format!(
+                    "Unknown placeholder {{{name}}} in output path {template:?}. Supported placeholders: {{hostname}}, {{timestamp}}"
+                ));
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    path.push_str(rest);
+
+    Ok(PathBuf::from(path))
+}
+
 fn write_sensor_file(
     out_file: &Path,
     temp_dir: Option<&Path>,
@@ -135,6 +229,9 @@ fn write_sensor_file(
         error!("Output cannot be a directory: {}", out_file.display());
         exit(1);
     }
+    if let Some(parent) = out_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
 
     // make sure our sensor file can be read by everyone
     let all_read_perm = fs::Permissions::from_mode(0o664);
@@ -172,6 +269,7 @@ pub struct SysinfoSource {
     networks: Networks,
     last_refresh: Option<Instant>,
     refresh_duration: Option<Duration>,
+    temperature_mapping: Vec<(Regex, String)>,
 }
 
 impl Default for SysinfoSource {
@@ -189,9 +287,28 @@ impl SysinfoSource {
             networks: Networks::new(),
             last_refresh: None,
             refresh_duration: None,
+            temperature_mapping: Vec::new(),
         }
     }
 
+    /// Set a custom temperature component label mapping.
+    ///
+    /// The first matching RegEx defines the sensor label of a temperature component. The built-in
+    /// label guessing is used for all components without a matching entry.
+    pub fn set_temperature_mapping(&mut self, mapping: Vec<(Regex, String)>) {
+        self.temperature_mapping = mapping;
+    }
+
+    /// List all temperature components as `<component label>: <sensor label> (<temperature>)`
+    /// lines, sorted by the component label.
+    pub fn component_list(&self) -> Vec<String> {
+        let components = self
+            .components
+            .iter()
+            .map(|component| (component.label(), component.temperature()));
+        format_component_list(components, &self.temperature_mapping)
+    }
+
     pub fn refresh(&mut self) {
         self.sys.refresh_all();
         debug!("Refreshing disks, components, networks");
@@ -230,14 +347,7 @@ impl SysinfoSource {
             format!("{:.2}", self.sys.global_cpu_usage()),
         );
 
-        let load_avg = System::load_average();
-        add_sensor(sensors, "load_avg_one", format!("{:.2}", load_avg.one));
-        add_sensor(sensors, "load_avg_five", format!("{:.2}", load_avg.five));
-        add_sensor(
-            sensors,
-            "load_avg_fifteen",
-            format!("{:.2}", load_avg.fifteen),
-        );
+        add_load_average_sensors(sensors, System::load_average(), LOAD_AVERAGE_SUPPORTED);
 
         // RAM and swap information:
         add_sensor(sensors, "mem_free_bytes", self.sys.free_memory());
@@ -272,38 +382,8 @@ impl SysinfoSource {
 
         // System information:
         let up_secs = System::uptime();
-        let up_days = up_secs / 86400;
-        let up_hours = (up_secs - (up_days * 86400)) / 3600;
-        let up_mins = (up_secs - (up_days * 86400) - (up_hours * 3600)) / 60;
         add_sensor(sensors, "system_uptime_sec", up_secs);
-        /*
-        Time to look into ftl for i18n
-        The coreutils project did a lot of work that could be used:
-        https://github.com/uutils/coreutils/blob/main/src/uucore/src/lib/mods/locale.rs
-        Then this would be the easy way to format the time, just uses a lot of setup code:
-
-        uptime-format = { $days ->
-            [0] { $time }
-            [one] { $days } day, { $time }
-           *[other] { $days } days { $time }
-        }
-
-        translate!(
-            "uptime-format",
-            "days" => up_days,
-            "time" => format!("{up_hours:02}:{up_mins:02}")
-        )
-         */
-        let day_string = match up_days {
-            0 => "",
-            1 => "1 day, ",
-            n => &format!("{n} days "),
-        };
-        add_sensor(
-            sensors,
-            "system_uptime",
-            format!("{day_string}{up_hours:02}:{up_mins:02}"),
-        );
+        add_sensor(sensors, "system_uptime", format_uptime(up_secs));
 
         if let Some(name) = System::name() {
             add_sensor(sensors, "system_name", name);
@@ -383,23 +463,7 @@ impl SysinfoSource {
         // Components temperature:
         for component in &self.components {
             if let Some(temperature) = component.temperature() {
-                let label;
-                if component.label().contains("spd5118") {
-                    label = "temperature_memory".to_string();
-                } else if component.label().contains("amdgpu") {
-                    label = "temperature_gpu".to_string();
-                } else if component.label().contains("Tctl") {
-                    label = "temperature_cpu".to_string();
-                } else if component.label().contains("Composite")
-                    && !component.label().contains("nvme")
-                {
-                    // just a guess...
-                    label = "temperature_motherboard".to_string();
-                } else {
-                    label = format!("temperature_{}", component.label().replace(' ', "_"));
-                    // println!("label={}, type_id={:?}, id={:?}, {component:?}",
-                    //          component.label(), component.type_id(), component.id());
-                }
+                let label = temperature_label(component.label(), &self.temperature_mapping);
 
                 add_sensor(sensors, format!("{label}#unit"), "°C");
                 add_sensor(sensors, label, format!("{temperature:.1}"));
@@ -433,16 +497,18 @@ impl SysinfoSource {
 
             if let Some(refresh) = self.refresh_duration {
                 let interval = refresh.as_millis() as u64;
-                if interval > 0 {
+                if let Some(received) = (1000 * data.received()).checked_div(interval) {
                     add_sensor(
                         sensors,
                         format!("network_{interface_name}_download_speed"),
-                        format!("{}/s", format_bytes(1000 * data.received() / interval)),
+                        format!("{}/s", format_bytes(received)),
                     );
+                }
+                if let Some(transmitted) = (1000 * data.transmitted()).checked_div(interval) {
                     add_sensor(
                         sensors,
                         format!("network_{interface_name}_upload_speed"),
-                        format!("{}/s", format_bytes(1000 * data.transmitted() / interval)),
+                        format!("{}/s", format_bytes(transmitted)),
                     );
                 }
             }
@@ -473,6 +539,135 @@ impl SysinfoSource {
     }
 }
 
+/// Get the sensor label of a temperature component.
+///
+/// The custom mapping takes precedence over the built-in label guessing. Unmatched components are
+/// labeled with `temperature_<component label>`.
+fn temperature_label(component_label: &str, mapping: &[(Regex, String)]) -> String {
+    if let Some((_, label)) = mapping.iter().find(|(re, _)| re.is_match(component_label)) {
+        return label.clone();
+    }
+
+    if component_label.contains("spd5118") {
+        "temperature_memory".to_string()
+    } else if component_label.contains("amdgpu") {
+        "temperature_gpu".to_string()
+    } else if component_label.contains("Tctl") {
+        "temperature_cpu".to_string()
+    } else if component_label.contains("Composite") && !component_label.contains("nvme") {
+        // just a guess...
+        "temperature_motherboard".to_string()
+    } else {
+        format!("temperature_{}", component_label.replace(' ', "_"))
+    }
+}
+
+/// Format temperature components as `<component label>: <sensor label> (<temperature>)` lines,
+/// sorted by the component label.
+fn format_component_list<'a>(
+    components: impl Iterator<Item = (&'a str, Option<f32>)>,
+    mapping: &[(Regex, String)],
+) -> Vec<String> {
+    components
+        .sorted_by(|a, b| a.0.cmp(b.0))
+        .map(|(component_label, temperature)| {
+            let label = temperature_label(component_label, mapping);
+            match temperature {
+                Some(temperature) => format!("{component_label}: {label} ({temperature:.1} °C)"),
+                None => format!("{component_label}: {label} (no temperature)"),
+            }
+        })
+        .collect()
+}
+
+/// Read the temperature component label mapping file.
+///
+/// - Empty lines and lines starting with # are skipped
+/// - RegEx and sensor label must be separated by the last `:` in the line
+/// - Invalid RegEx expressions are skipped
+fn read_temperature_mapping(
+    path: &Path,
+) -> Result<Vec<(Regex, String)>, Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(path)?;
+    Ok(parse_temperature_mapping(&content))
+}
+
+fn parse_temperature_mapping(content: &str) -> Vec<(Regex, String)> {
+    let mut mapping = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((pattern, label)) = line.rsplit_once(':') else {
+            warn!("Skipping invalid entry in temperature mapping file: {line}");
+            continue;
+        };
+        match Regex::new(pattern.trim()) {
+            Ok(re) => mapping.push((re, label.trim().to_string())),
+            Err(e) => warn!("Skipping invalid RegEx in temperature mapping file: {line}: {e}"),
+        }
+    }
+
+    mapping
+}
+
+/// Add the load average sensors if supported by the operating system.
+///
+/// Unsupported load average values are not emitted, to avoid showing a misleading `0.00` load.
+fn add_load_average_sensors(
+    sensors: &mut HashMap<String, String>,
+    load_avg: LoadAvg,
+    supported: bool,
+) {
+    if !supported {
+        return;
+    }
+
+    add_sensor(sensors, "load_avg_one", format!("{:.2}", load_avg.one));
+    add_sensor(sensors, "load_avg_five", format!("{:.2}", load_avg.five));
+    add_sensor(
+        sensors,
+        "load_avg_fifteen",
+        format!("{:.2}", load_avg.fifteen),
+    );
+}
+
+/// Format the system uptime in seconds as `[n day(s)] HH:MM`.
+///
+/// `System::uptime()` returns the time since boot in seconds on all supported operating systems.
+fn format_uptime(up_secs: u64) -> String {
+    let up_days = up_secs / 86400;
+    let up_hours = (up_secs - (up_days * 86400)) / 3600;
+    let up_mins = (up_secs - (up_days * 86400) - (up_hours * 3600)) / 60;
+    /*
+    Time to look into ftl for i18n
+    The coreutils project did a lot of work that could be used:
+    https://github.com/uutils/coreutils/blob/main/src/uucore/src/lib/mods/locale.rs
+    Then this would be the easy way to format the time, just uses a lot of setup code:
+
+    uptime-format = { $days ->
+        [0] { $time }
+        [one] { $days } day, { $time }
+       *[other] { $days } days { $time }
+    }
+
+    translate!(
+        "uptime-format",
+        "days" => up_days,
+        "time" => format!("{up_hours:02}:{up_mins:02}")
+    )
+     */
+    let day_string = match up_days {
+        0 => "",
+        1 => "1 day, ",
+        n => &format!("{n} days "),
+    };
+
+    format!("{day_string}{up_hours:02}:{up_mins:02}")
+}
+
 fn add_sensor(
     sensors: &mut HashMap<String, String>,
     label: impl Into<String>,
@@ -484,9 +679,10 @@ fn add_sensor(
 fn update_linux_storage_sensors(
     sensors: &mut HashMap<String, String>,
     use_smartctl: bool,
+    filter: &DiskFilter,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Note: AOOSTAR-X only considered spinning Rust. Too bad if you're using SSDs in the HD bays...
-    if let Ok(hdd_devices) = get_storage_devices(StorageDevice::HddOrSsd) {
+    if let Ok(hdd_devices) = get_storage_devices(StorageDevice::HddOrSsd, filter) {
         debug!("HDD devices : {:?}", hdd_devices);
         for (idx, device) in hdd_devices.iter().enumerate() {
             let usage = get_disk_usage(device)?;
@@ -527,7 +723,7 @@ fn update_linux_storage_sensors(
     }
 
     // AOOSTAR-X: ssd == nvme
-    if let Ok(nvme_devices) = get_storage_devices(StorageDevice::Nvme) {
+    if let Ok(nvme_devices) = get_storage_devices(StorageDevice::Nvme, filter) {
         debug!("NVME devices: {:?}", nvme_devices);
         for (idx, device) in nvme_devices.iter().enumerate() {
             let usage = get_disk_usage(device)?;
@@ -597,19 +793,48 @@ pub enum StorageDevice {
 
 pub type DiskResult = Result<Vec<DiskInfo>, Box<dyn std::error::Error>>;
 
+/// Storage device name filter with include and exclude RegEx patterns.
+#[derive(Debug, Default)]
+pub struct DiskFilter {
+    /// Only include matching device names. All devices are included if empty.
+    pub include: Vec<Regex>,
+    /// Exclude matching device names. Takes priority over `include`.
+    pub exclude: Vec<Regex>,
+}
+
+impl DiskFilter {
+    /// Check if the storage device with the given name passes the filter.
+    pub fn matches(&self, device: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|re| re.is_match(device)))
+            && !self.exclude.iter().any(|re| re.is_match(device))
+    }
+}
+
 /// Get storage devices of the given type: NVME, SSD or HD
 ///
 /// Storage devices are identified from /sys/block attributes.
-/// Removable devices are excluded.
+/// Removable devices and devices not passing the device name `filter` are excluded.
 ///
 /// # Arguments
 ///
 /// * `kind`: type of storage device
+/// * `filter`: device name filter
 ///
 /// returns: sorted list of found device names (`sd*` and `nvme*`)
-pub fn get_storage_devices(kind: StorageDevice) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+pub fn get_storage_devices(
+    kind: StorageDevice,
+    filter: &DiskFilter,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    find_storage_devices(Path::new("/sys/block"), kind, filter)
+}
+
+/// Get storage devices of the given type from the `sys_block` directory, see [get_storage_devices].
+fn find_storage_devices(
+    sys_block: &Path,
+    kind: StorageDevice,
+    filter: &DiskFilter,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     let mut devices = Vec::new();
-    let sys_block = Path::new("/sys/block");
 
     if !sys_block.exists() {
         info!("No storage device found");
@@ -631,6 +856,11 @@ pub fn get_storage_devices(kind: StorageDevice) -> Result<Vec<String>, Box<dyn s
             continue;
         }
 
+        if !filter.matches(&dev_str) {
+            debug!("Skipping filtered storage device {dev_str}");
+            continue;
+        }
+
         match kind {
             StorageDevice::All => {}
             StorageDevice::Hdd | StorageDevice::Ssd | StorageDevice::HddOrSsd => {
@@ -822,6 +1052,121 @@ pub fn format_bytes(bytes: u64) -> String {
 mod tests {
     use super::*;
 
+    /// Create a fake `/sys/block` device entry.
+    fn add_block_device(sys_block: &Path, name: &str, rotational: bool, removable: bool) {
+        let device = sys_block.join(name);
+        fs::create_dir_all(device.join("queue")).unwrap();
+        fs::write(
+            device.join("queue/rotational"),
+            format!("{}\n", rotational as u8),
+        )
+        .unwrap();
+        fs::write(device.join("removable"), format!("{}\n", removable as u8)).unwrap();
+    }
+
+    fn fake_sys_block() -> tempfile::TempDir {
+        let sys_block = tempfile::tempdir().unwrap();
+        add_block_device(sys_block.path(), "sda", true, false);
+        add_block_device(sys_block.path(), "sdb", false, false);
+        add_block_device(sys_block.path(), "sdc", true, false);
+        add_block_device(sys_block.path(), "sdd", false, true);
+        add_block_device(sys_block.path(), "nvme0n1", false, false);
+        add_block_device(sys_block.path(), "loop0", false, false);
+        sys_block
+    }
+
+    fn disk_filter(include: &[&str], exclude: &[&str]) -> DiskFilter {
+        DiskFilter {
+            include: include.iter().map(|re| Regex::new(re).unwrap()).collect(),
+            exclude: exclude.iter().map(|re| Regex::new(re).unwrap()).collect(),
+        }
+    }
+
+    #[test]
+    fn storage_devices_without_filter_exclude_removable() {
+        let sys_block = fake_sys_block();
+        let filter = DiskFilter::default();
+
+        let devices =
+            find_storage_devices(sys_block.path(), StorageDevice::HddOrSsd, &filter).unwrap();
+        assert_eq!(devices, ["sda", "sdb", "sdc"]);
+        let devices = find_storage_devices(sys_block.path(), StorageDevice::Nvme, &filter).unwrap();
+        assert_eq!(devices, ["nvme0n1"]);
+    }
+
+    #[test]
+    fn storage_devices_blocklist_excludes_device() {
+        let sys_block = fake_sys_block();
+        let filter = disk_filter(&[], &["^sdc$"]);
+
+        let devices =
+            find_storage_devices(sys_block.path(), StorageDevice::HddOrSsd, &filter).unwrap();
+        assert_eq!(devices, ["sda", "sdb"]);
+    }
+
+    #[test]
+    fn storage_devices_allowlist_restricts_to_named_devices() {
+        let sys_block = fake_sys_block();
+        let filter = disk_filter(&["^sdb$", "^sdd$"], &[]);
+
+        let devices =
+            find_storage_devices(sys_block.path(), StorageDevice::HddOrSsd, &filter).unwrap();
+        // removable devices stay excluded
+        assert_eq!(devices, ["sdb"]);
+        let devices = find_storage_devices(sys_block.path(), StorageDevice::Nvme, &filter).unwrap();
+        assert!(devices.is_empty());
+    }
+
+    #[test]
+    fn storage_devices_blocklist_takes_priority_over_allowlist() {
+        let filter = disk_filter(&["^sd"], &["^sdc$"]);
+
+        assert!(filter.matches("sda"));
+        assert!(!filter.matches("sdc"));
+        assert!(!filter.matches("nvme0n1"));
+    }
+
+    #[test]
+    fn expand_out_path_placeholders() {
+        let path = expand_out_path(
+            Path::new("/tmp/sensors/{hostname}-{timestamp}.txt"),
+            "nas",
+            1700000000,
+        );
+        assert_eq!(path, Ok(PathBuf::from("/tmp/sensors/nas-1700000000.txt")));
+
+        let path = expand_out_path(Path::new("/tmp/sensors.txt"), "nas", 0);
+        assert_eq!(path, Ok(PathBuf::from("/tmp/sensors.txt")));
+    }
+
+    #[test]
+    fn expand_out_path_invalid_placeholders() {
+        let error = expand_out_path(Path::new("/tmp/{host}.txt"), "nas", 0).unwrap_err();
+        assert!(error.contains("Unknown placeholder {host}"), "{error}");
+
+        let error = expand_out_path(Path::new("/tmp/{hostname.txt"), "nas", 0).unwrap_err();
+        assert!(error.contains("Unclosed placeholder"), "{error}");
+    }
+
+    #[test]
+    fn sensor_file_with_hostname_is_written_atomically() {
+        let dir = tempfile::tempdir().unwrap();
+        let temp_dir = dir.path().join("tmp");
+        let template = dir.path().join("{hostname}/sensors.txt");
+        let sensors = HashMap::from([("cpu_temperature".to_string(), "42".to_string())]);
+
+        let out_file = expand_out_path(&template, "nas", 0).unwrap();
+        write_sensor_file(&out_file, Some(&temp_dir), &sensors).unwrap();
+
+        assert_eq!(out_file, dir.path().join("nas/sensors.txt"));
+        assert_eq!(
+            fs::read_to_string(&out_file).unwrap(),
+            "cpu_temperature: 42\n"
+        );
+        // the temp file has been renamed to the output file
+        assert_eq!(fs::read_dir(&temp_dir).unwrap().count(), 0);
+    }
+
     #[test]
     fn test_format_bytes() {
         assert_eq!(format_bytes(0), "0 B");
@@ -829,4 +1174,145 @@ mod tests {
         assert_eq!(format_bytes(1048576), "1.00 MB");
         assert_eq!(format_bytes(1073741824), "1.00 GB");
     }
+
+    #[test]
+    fn load_average_sensors_are_added_if_supported() {
+        let mut sensors = HashMap::new();
+        let load_avg = LoadAvg {
+            one: 1.5,
+            five: 0.75,
+            fifteen: 0.125,
+        };
+        add_load_average_sensors(&mut sensors, load_avg, true);
+
+        assert_eq!(
+            sensors.get("load_avg_one").map(String::as_str),
+            Some("1.50")
+        );
+        assert_eq!(
+            sensors.get("load_avg_five").map(String::as_str),
+            Some("0.75")
+        );
+        assert_eq!(
+            sensors.get("load_avg_fifteen").map(String::as_str),
+            Some("0.12")
+        );
+    }
+
+    #[test]
+    fn load_average_sensors_are_skipped_if_unsupported() {
+        let mut sensors = HashMap::new();
+        add_load_average_sensors(&mut sensors, LoadAvg::default(), false);
+
+        assert!(sensors.is_empty());
+    }
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(0), "00:00");
+        assert_eq!(format_uptime(59), "00:00");
+        assert_eq!(format_uptime(3 * 3600 + 7 * 60), "03:07");
+        assert_eq!(format_uptime(86400 + 3600 + 60), "1 day, 01:01");
+        assert_eq!(
+            format_uptime(3 * 86400 + 23 * 3600 + 59 * 60),
+            "3 days 23:59"
+        );
+    }
+
+    #[test]
+    fn temperature_label_without_mapping_uses_builtin_labels() {
+        assert_eq!(temperature_label("spd5118 0", &[]), "temperature_memory");
+        assert_eq!(temperature_label("amdgpu edge", &[]), "temperature_gpu");
+        assert_eq!(temperature_label("k10temp Tctl", &[]), "temperature_cpu");
+        assert_eq!(
+            temperature_label("Composite", &[]),
+            "temperature_motherboard"
+        );
+        assert_eq!(
+            temperature_label("nvme Composite", &[]),
+            "temperature_nvme_Composite"
+        );
+        assert_eq!(
+            temperature_label("acpitz temp1", &[]),
+            "temperature_acpitz_temp1"
+        );
+    }
+
+    #[test]
+    fn temperature_label_with_mapping() {
+        let mapping = parse_temperature_mapping(
+            r"
+            # comment
+            ^Composite$: temperature_nvme
+            coretemp Package id \d+: temperature_cpu
+            acpitz: temperature_motherboard
+            invalid entry
+            ",
+        );
+        assert_eq!(mapping.len(), 3);
+
+        assert_eq!(temperature_label("Composite", &mapping), "temperature_nvme");
+        assert_eq!(
+            temperature_label("coretemp Package id 0", &mapping),
+            "temperature_cpu"
+        );
+        assert_eq!(
+            temperature_label("acpitz temp1", &mapping),
+            "temperature_motherboard"
+        );
+        // unmatched components keep the built-in labels
+        assert_eq!(
+            temperature_label("amdgpu edge", &mapping),
+            "temperature_gpu"
+        );
+        assert_eq!(
+            temperature_label("nct6798 SYSTIN", &mapping),
+            "temperature_nct6798_SYSTIN"
+        );
+    }
+
+    #[test]
+    fn pinned_mapping_overrides_builtin_heuristic() {
+        let mapping = parse_temperature_mapping(
+            r"
+            ^k10temp Tctl$: temperature_cpu_control
+            ^nvme Composite Samsung SSD 990 PRO 2TB temp1$: temperature_nvme
+            ^Composite$: temperature_ssd
+            ",
+        );
+
+        assert_eq!(
+            temperature_label("k10temp Tctl", &mapping),
+            "temperature_cpu_control"
+        );
+        assert_eq!(temperature_label("Composite", &mapping), "temperature_ssd");
+        assert_eq!(
+            temperature_label("nvme Composite Samsung SSD 990 PRO 2TB temp1", &mapping),
+            "temperature_nvme"
+        );
+        // pinned labels must match the full component label
+        assert_eq!(
+            temperature_label("k10temp Tctl2", &mapping),
+            "temperature_cpu"
+        );
+    }
+
+    #[test]
+    fn component_list_shows_raw_and_assigned_labels() {
+        let mapping = parse_temperature_mapping("^acpitz temp1$: temperature_motherboard");
+        let components = [
+            ("k10temp Tctl", Some(45.25)),
+            ("acpitz temp1", Some(30.0)),
+            ("nct6798 SYSTIN", None),
+        ];
+
+        assert_eq!(
+            format_component_list(components.into_iter(), &mapping),
+            [
+                "acpitz temp1: temperature_motherboard (30.0 °C)",
+                "k10temp Tctl: temperature_cpu (45.2 °C)",
+                "nct6798 SYSTIN: temperature_nct6798_SYSTIN (no temperature)",
+            ]
+        );
+    }
 }
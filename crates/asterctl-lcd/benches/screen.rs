@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2025 Markus Zehnder
+
+//! Benchmarks of the RGB 565 frame conversion and sending frames to a [FakeSerialPort].
+
+use asterctl_lcd::{AooScreenBuilder, DISPLAY_SIZE, FakeSerialPort, ToRgb565};
+use criterion::{Criterion, criterion_group, criterion_main};
+use image::{Rgb, RgbImage};
+use std::hint::black_box;
+use std::sync::{Arc, Mutex};
+
+/// Deterministic full frame with a color gradient.
+fn gradient_frame() -> RgbImage {
+    let (width, height) = DISPLAY_SIZE;
+    RgbImage::from_fn(width, height, |x, y| {
+        Rgb([
+            (x * 255 / width) as u8,
+            (y * 255 / height) as u8,
+            ((x + y) % 256) as u8,
+        ])
+    })
+}
+
+/// Copy of the frame with a changed area, like an updated sensor value.
+fn updated_frame(frame: &RgbImage) -> RgbImage {
+    let mut frame = frame.clone();
+    for y in 100..140 {
+        for x in 400..560 {
+            frame.put_pixel(x, y, Rgb([255, 255, 255]));
+        }
+    }
+    frame
+}
+
+fn to_rgb565_le(c: &mut Criterion) {
+    let frame = gradient_frame();
+
+    c.bench_function("to_rgb565_le full frame", |b| {
+        b.iter(|| black_box(&frame).to_rgb565_le())
+    });
+}
+
+fn send_image(c: &mut Criterion) {
+    let frames = [gradient_frame(), updated_frame(&gradient_frame())];
+
+    for enable_cache in [false, true] {
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let mut builder = AooScreenBuilder::new();
+        builder.enable_cache(enable_cache);
+        let mut screen = builder.open_port(Box::new(FakeSerialPort::recording(recorded.clone())));
+
+        let name = if enable_cache {
+            "send_image cached"
+        } else {
+            "send_image uncached"
+        };
+        let mut idx = 0;
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                idx = (idx + 1) % frames.len();
+                screen.send_image(&frames[idx]).unwrap();
+                recorded.lock().unwrap().clear();
+            })
+        });
+    }
+}
+
+criterion_group!(benches, to_rgb565_le, send_image);
+criterion_main!(benches);
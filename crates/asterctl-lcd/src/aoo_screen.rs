@@ -2,16 +2,21 @@
 // SPDX-FileCopyrightText: Copyright (c) 2025 Markus Zehnder
 
 use crate::FakeSerialPort;
-use crate::ToRgb565;
+use crate::display_model::DisplayModel;
+use crate::protocol_trace::{MessageKind, ProtocolTrace};
+use crate::throughput::{ThroughputMeter, TransferStats};
+use crate::{ToRgb565, from_rgb565_le, rgb_to_rgb565};
 
 use anyhow::{Context, anyhow};
 use bytes::{BufMut, BytesMut};
+use image::{Rgb, RgbImage};
 use log::{debug, error, info, warn};
-use serialport::{SerialPort, SerialPortType};
+use serialport::{SerialPort, SerialPortInfo, SerialPortType};
 use std::io::{Read, Write};
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 
+/// Display size of the default [DisplayModel].
 pub const DISPLAY_SIZE: (u32, u32) = (960, 376);
 
 const SERIAL_RETRY: u8 = 3;
@@ -22,6 +27,9 @@ const USB_UART_PID: u16 = 0x90A1;
 
 const IMG_CHUNK_SIZE: usize = 47;
 
+/// Time between two serial throughput log messages.
+const THROUGHPUT_LOG_INTERVAL: Duration = Duration::from_secs(60);
+
 static DISPLAY_OFF: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x0A, 0x00, 0x00, 0x00];
 static DISPLAY_ON: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x0B, 0x00, 0x00, 0x00];
 
@@ -35,7 +43,13 @@ static HEADER: [u8; 8] = [0xAA, 0x55, 0xAA, 0x55, 0x08, 0x00, 0x00, 0x00];
 pub struct AooScreenBuilder {
     timeout: Option<Duration>,
     enable_cache: Option<bool>,
+    cache_tolerance: Option<u8>,
     no_init_check: Option<bool>,
+    init_wait: Option<Duration>,
+    verify_frames: Option<bool>,
+    model: Option<DisplayModel>,
+    serial_number: Option<String>,
+    trace: Option<ProtocolTrace>,
 }
 
 #[allow(dead_code)]
@@ -55,12 +69,64 @@ impl AooScreenBuilder {
         self
     }
 
+    /// Treat pixels as unchanged for the frame cache if each RGB 565 color component differs by
+    /// at most `tolerance` from the previous frame. Defaults to 0 for an exact comparison.
+    ///
+    /// Nearly identical pixels, e.g. from tiny anti-aliasing changes around text, keep the color
+    /// of the previous frame, so that unchanged image chunks are not sent again. This trades a
+    /// minimal color accuracy for less transferred data.
+    pub fn cache_tolerance(&mut self, tolerance: u8) -> &mut Self {
+        self.cache_tolerance = Some(tolerance);
+        self
+    }
+
     /// Disable LCD initialization check and only write data to the display. Defaults to false.
     pub fn no_init_check(&mut self, no_check: bool) -> &mut Self {
         self.no_init_check = Some(no_check);
         self
     }
 
+    /// Set the time to wait for the display response after switching on the display in
+    /// [AooScreen::init]. Defaults to 1 sec.
+    pub fn init_wait(&mut self, wait: Duration) -> &mut Self {
+        self.init_wait = Some(wait);
+        self
+    }
+
+    /// Verify each sent frame with an acknowledge response of the display. Defaults to false.
+    ///
+    /// After the end of the frame, the display must respond with the `A` acknowledge marker,
+    /// otherwise the full frame is sent again. This depends on the display firmware, the original
+    /// app only checks the response of the initialization.
+    pub fn verify_frames(&mut self, verify: bool) -> &mut Self {
+        self.verify_frames = Some(verify);
+        self
+    }
+
+    /// Set the display model. Defaults to the model detected from the USB product string,
+    /// or [DisplayModel::default] if the model cannot be detected.
+    pub fn model(&mut self, model: DisplayModel) -> &mut Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Select the USB UART device with the given USB serial number, e.g. if multiple displays
+    /// with the same vid:pid are attached. Defaults to the first device matching the vid:pid.
+    pub fn serial_number(&mut self, serial_number: &str) -> &mut Self {
+        self.serial_number = Some(serial_number.to_string());
+        self
+    }
+
+    /// Trace all messages sent to the display, e.g. for comparing them with USB captures of the
+    /// original app.
+    ///
+    /// Each message is logged at trace level with the [TRACE_TARGET](crate::TRACE_TARGET) log
+    /// target, and written as text line to the optional `writer`. Disabled by default.
+    pub fn trace(&mut self, writer: Option<Box<dyn Write + Send>>) -> &mut Self {
+        self.trace = Some(ProtocolTrace::new(writer));
+        self
+    }
+
     /// Open the default AOOSTAR LCD USB UART device 416:90A1.
     pub fn open_default(self) -> anyhow::Result<AooScreen> {
         self.open_usb(USB_UART_VID, USB_UART_PID)
@@ -68,25 +134,44 @@ impl AooScreenBuilder {
 
     /// Simulate the LCD device. No real device or serial port is required.
     pub fn simulate(self) -> anyhow::Result<AooScreen> {
-        Ok(AooScreen {
-            port: Some(Box::new(FakeSerialPort::new())),
+        Ok(self.open_port(Box::new(FakeSerialPort::new())))
+    }
+
+    /// Use an already opened serial port, for example a [FakeSerialPort] for testing.
+    pub fn open_port(self, port: Box<dyn SerialPort>) -> AooScreen {
+        AooScreen {
+            port: Some(port),
             enable_cache: self.enable_cache.unwrap_or(true),
+            cache_tolerance: self.cache_tolerance.unwrap_or_default(),
             prev_frame: None,
             no_init_check: self.no_init_check.unwrap_or(false),
-        })
+            init_wait: self.init_wait.unwrap_or(Duration::from_secs(1)),
+            verify_frames: self.verify_frames.unwrap_or(false),
+            model: self.model.unwrap_or_default(),
+            trace: self.trace,
+            throughput: ThroughputMeter::new(),
+            throughput_logged: Instant::now(),
+        }
     }
 
-    /// Open the specified USB UART device id. Format: vid:pid
+    /// Open the specified USB UART device id. Format: vid:pid in hex notation, e.g. `416:90a1`.
+    ///
+    /// The hex ids are case-insensitive and may have a `0x` prefix.
     pub fn open_usb_id(self, id: &str) -> anyhow::Result<AooScreen> {
-        let (vid, pid) = id
-            .split_once(':')
-            .with_context(|| "Error parsing serial port ID. Expected `vid:pid` format.")?;
-        self.open_usb(u16::from_str_radix(vid, 16)?, u16::from_str_radix(pid, 16)?)
+        let (vid, pid) = parse_usb_id(id)?;
+        self.open_usb(vid, pid)
     }
 
     /// Open the specified USB UART
-    pub fn open_usb(self, vid: u16, pid: u16) -> anyhow::Result<AooScreen> {
-        let serial_dev = find_usb_serial_port(vid, pid)?;
+    pub fn open_usb(mut self, vid: u16, pid: u16) -> anyhow::Result<AooScreen> {
+        let (serial_dev, product) =
+            find_usb_serial_port_by_serial(vid, pid, self.serial_number.as_deref())?;
+        if self.model.is_none()
+            && let Some(model) = product.as_deref().and_then(DisplayModel::from_usb_product)
+        {
+            info!("Detected display model {model:?} from USB product {product:?}");
+            self.model = Some(model);
+        }
         self.open_device(&serial_dev)
     }
 
@@ -105,52 +190,52 @@ impl AooScreenBuilder {
             port.stop_bits()?
         );
 
-        Ok(AooScreen {
-            port: Some(port),
-            enable_cache: self.enable_cache.unwrap_or(true),
-            prev_frame: None,
-            no_init_check: self.no_init_check.unwrap_or(false),
-        })
+        Ok(self.open_port(port))
     }
 }
 
 pub struct AooScreen {
     port: Option<Box<dyn SerialPort>>,
     enable_cache: bool,
+    cache_tolerance: u8,
     prev_frame: Option<BytesMut>,
     no_init_check: bool,
+    init_wait: Duration,
+    verify_frames: bool,
+    model: DisplayModel,
+    trace: Option<ProtocolTrace>,
+    throughput: ThroughputMeter,
+    throughput_logged: Instant,
 }
 
 #[allow(dead_code)]
 impl AooScreen {
+    /// Switch on the display and check the response of the display.
+    ///
+    /// The display on command is resent up to 3 times if the display doesn't respond.
     pub fn init(&mut self) -> anyhow::Result<()> {
         let port = self.port.as_mut().ok_or(anyhow!("LCD port not open"))?;
 
-        port.write(&DISPLAY_ON)
-            .with_context(|| "Error sending display on command")?;
-
-        if self.no_init_check {
-            warn!("Test mode: only writing to the display");
-        } else {
-            // quick and dirty response check as in the original app
-            sleep(Duration::from_secs(1));
+        for attempt in 0..=SERIAL_RETRY {
+            if let Some(trace) = self.trace.as_mut() {
+                trace
+                    .message(&DISPLAY_ON, MessageKind::On)
+                    .with_context(|| "Failed to write protocol trace")?;
+            }
+            port.write(&DISPLAY_ON)
+                .with_context(|| "Error sending display on command")?;
 
-            let available = port
-                .bytes_to_read()
-                .with_context(|| "Failed to get available bytes from serial port")?;
-            if available == 0 {
-                return Err(anyhow!("Initialization failed, no response received"));
+            if self.no_init_check {
+                warn!("Test mode: only writing to the display");
+                break;
             }
-            let mut serial_buf: Vec<u8> = vec![0; available as usize];
-            port.read(serial_buf.as_mut_slice())
-                .with_context(|| "Failed to read from serial port")?;
-
-            let marker = b'A';
-            if !serial_buf.contains(&marker) {
-                return Err(anyhow!(
-                    "Initialization failed, received: {}",
-                    String::from_utf8_lossy(&serial_buf)
-                ));
+
+            // quick and dirty response check as in the original app
+            sleep(self.init_wait);
+            match Self::read_init_response(port.as_mut()) {
+                Ok(()) => break,
+                Err(e) if attempt < SERIAL_RETRY => warn!("{e}, retrying"),
+                Err(e) => return Err(e),
             }
         }
 
@@ -159,6 +244,42 @@ impl AooScreen {
         Ok(())
     }
 
+    /// Read the response of the display on command, which must contain the `A` marker.
+    ///
+    /// A missing response is detected by the read timeout of the serial port.
+    fn read_init_response(port: &mut dyn SerialPort) -> anyhow::Result<()> {
+        let mut serial_buf = [0u8; 16];
+        let len = match port.read(&mut serial_buf) {
+            Ok(len) => len,
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => 0,
+            Err(e) => return Err(e).with_context(|| "Failed to read from serial port"),
+        };
+        if len == 0 {
+            return Err(anyhow!("Initialization failed, no response received"));
+        }
+
+        let response = &serial_buf[..len];
+        let marker = b'A';
+        if !response.contains(&marker) {
+            return Err(anyhow!(
+                "Initialization failed, received: {}",
+                String::from_utf8_lossy(response)
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Display model of the screen.
+    pub fn model(&self) -> DisplayModel {
+        self.model
+    }
+
+    /// Display size as width, height tuple in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        self.model.size()
+    }
+
     pub fn close(&mut self) {
         if self.port.is_some() {
             if let Err(e) = self.off() {
@@ -169,17 +290,83 @@ impl AooScreen {
     }
 
     pub fn on(&mut self) -> anyhow::Result<()> {
-        self.send(&DISPLAY_ON)
+        self.send(&DISPLAY_ON, MessageKind::On)
             .with_context(|| "Failed to send display on")
     }
 
     pub fn off(&mut self) -> anyhow::Result<()> {
-        self.send(&DISPLAY_OFF)
+        self.send(&DISPLAY_OFF, MessageKind::Off)
             .with_context(|| "Failed to send display off")
     }
 
+    /// Switch off the display after the given delay.
+    ///
+    /// Blocks the calling thread until the display is switched off.
+    pub fn off_after(&mut self, delay: Duration) -> anyhow::Result<()> {
+        info!("Switching off display in {}s", delay.as_secs_f32());
+        sleep(delay);
+        self.off()
+    }
+
     pub fn send_image(&mut self, image: impl ToRgb565) -> anyhow::Result<()> {
-        let img_rgb565 = image.to_rgb565_le();
+        self.send_frame(image.to_rgb565_le())
+    }
+
+    /// Fill the display with a single color, e.g. a black "screensaver" frame without switching
+    /// off the display.
+    ///
+    /// The display protocol has no fill command, a solid color frame is sent instead.
+    pub fn fill(&mut self, color: Rgb<u8>) -> anyhow::Result<()> {
+        let (width, height) = self.size();
+        let pixel = rgb_to_rgb565(color[0], color[1], color[2]);
+        let pixel_count = width as usize * height as usize;
+
+        let mut frame = BytesMut::with_capacity(pixel_count * 2);
+        for _ in 0..pixel_count {
+            frame.put_u16_le(pixel);
+        }
+
+        self.send_frame(frame)
+    }
+
+    /// Send a full display frame in RGB 565 little endian format.
+    ///
+    /// With frame verification enabled, the full frame is resent if the display doesn't
+    /// acknowledge the frame, up to [SERIAL_RETRY] times.
+    fn send_frame(&mut self, mut img_rgb565: BytesMut) -> anyhow::Result<()> {
+        if self.enable_cache
+            && self.cache_tolerance > 0
+            && let Some(cache) = self.prev_frame.as_ref()
+        {
+            keep_similar_pixels(&mut img_rgb565, cache, self.cache_tolerance);
+        }
+
+        let mut retry = 0;
+        loop {
+            self.write_frame(&img_rgb565)?;
+
+            if !self.verify_frames || self.read_frame_ack()? {
+                break;
+            }
+
+            // the display state is unknown, the full frame has to be sent again
+            self.clear_cache();
+            if retry >= SERIAL_RETRY {
+                return Err(anyhow!("Frame not acknowledged by the display"));
+            }
+            warn!("Frame not acknowledged by the display, resending frame");
+            retry += 1;
+        }
+
+        if self.enable_cache {
+            self.prev_frame.replace(img_rgb565);
+        }
+
+        Ok(())
+    }
+
+    /// Write the changed chunks of a frame to the display.
+    fn write_frame(&mut self, img_rgb565: &BytesMut) -> anyhow::Result<()> {
         debug!(
             "Start sending image (size {}) {} cache... ",
             img_rgb565.len(),
@@ -191,7 +378,7 @@ impl AooScreen {
         );
 
         let start_time = Instant::now();
-        self.send(&HEADER_START)
+        self.send(&HEADER_START, MessageKind::FrameStart)
             .with_context(|| "Failed to send header start")?;
 
         let mut buf = BytesMut::with_capacity(HEADER.len() + 4 + IMG_CHUNK_SIZE);
@@ -216,26 +403,77 @@ impl AooScreen {
             buf.put_u32_le(offset as u32);
             buf.extend(chunk);
 
-            self.send(&buf)
+            let kind = MessageKind::Chunk {
+                offset,
+                header_len: HEADER.len() + 4,
+            };
+            self.send(&buf, kind)
                 .with_context(|| format!("Failed to send image data chunk {idx}"))?;
             sent_chunks += 1;
         }
 
-        self.send(&HEADER_END)
+        self.send(&HEADER_END, MessageKind::FrameEnd)
             .with_context(|| "Failed to send header end")?;
-
-        if self.enable_cache {
-            self.prev_frame.replace(img_rgb565);
+        if let Some(trace) = self.trace.as_mut() {
+            let skipped_chunks = img_rgb565.len().div_ceil(IMG_CHUNK_SIZE) - sent_chunks;
+            trace
+                .frame(sent_chunks, skipped_chunks)
+                .with_context(|| "Failed to write protocol trace")?;
         }
 
         debug!(
             "Image sent: {}ms, {sent_chunks} chunks",
             start_time.elapsed().as_millis()
         );
+        if self.throughput_logged.elapsed() >= THROUGHPUT_LOG_INTERVAL {
+            info!("Serial throughput: {}", self.transfer_stats());
+            self.throughput_logged = Instant::now();
+        }
 
         Ok(())
     }
 
+    /// Read the frame acknowledge response of the display.
+    ///
+    /// returns: true if the `A` acknowledge marker has been received, false for any other response
+    /// or a read timeout.
+    fn read_frame_ack(&mut self) -> anyhow::Result<bool> {
+        let port = self.port.as_mut().ok_or(anyhow!("LCD port not open"))?;
+
+        let mut serial_buf = [0u8; 16];
+        match port.read(&mut serial_buf) {
+            Ok(len) => {
+                let response = &serial_buf[..len];
+                if response.contains(&b'A') {
+                    Ok(true)
+                } else {
+                    debug!(
+                        "Invalid frame response: {}",
+                        String::from_utf8_lossy(response)
+                    );
+                    Ok(false)
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                debug!("No frame response received");
+                Ok(false)
+            }
+            Err(e) => Err(e).with_context(|| "Failed to read from serial port"),
+        }
+    }
+
+    /// Get the last frame sent to the display as RGB 888 image.
+    ///
+    /// This is the cached frame of the last sent image and not read back from the display.
+    ///
+    /// returns: None if no frame has been sent yet or the frame cache is disabled.
+    pub fn last_frame(&self) -> Option<RgbImage> {
+        let (width, height) = self.size();
+        self.prev_frame
+            .as_ref()
+            .and_then(|frame| from_rgb565_le(frame, width, height))
+    }
+
     pub fn enable_cache(&mut self, enable: bool) {
         self.enable_cache = enable;
         if !enable {
@@ -251,16 +489,34 @@ impl AooScreen {
         self.prev_frame = None;
     }
 
-    fn send(&mut self, data: &[u8]) -> anyhow::Result<()> {
+    /// Serial transfer statistics with the effective throughput over a rolling window.
+    ///
+    /// The throughput only considers the time spent writing to the serial port, which helps to
+    /// distinguish a slow serial connection from slow frame rendering. The statistics are also
+    /// logged every minute while sending frames.
+    pub fn transfer_stats(&self) -> TransferStats {
+        self.throughput.stats()
+    }
+
+    /// Send a message to the display, recording it in the optional protocol trace.
+    fn send(&mut self, data: &[u8], kind: MessageKind) -> anyhow::Result<()> {
+        if let Some(trace) = self.trace.as_mut() {
+            trace
+                .message(data, kind)
+                .with_context(|| "Failed to write protocol trace")?;
+        }
+
         // TODO not sure if retry logic is required. Need a real device to test...
         let mut retry = 0;
 
         let port = self.port.as_mut().ok_or(anyhow!("LCD port not open"))?;
 
         loop {
+            let start = Instant::now();
             return match port.write_all(data) {
                 Ok(()) => {
                     port.flush()?;
+                    self.throughput.record(data.len(), start.elapsed());
                     Ok(())
                 }
                 Err(e) => {
@@ -282,21 +538,726 @@ impl AooScreen {
     }
 }
 
-pub fn find_usb_serial_port(vid: u16, pid: u16) -> serialport::Result<String> {
-    info!("Looking for USB serial port {vid:x}:{pid:x}");
-    let ports = serialport::available_ports()?;
-    for p in ports {
-        debug!("Found serial port: {}", p.port_name);
-        if let SerialPortType::UsbPort(info) = p.port_type
-            && info.pid == pid
-            && info.vid == vid
+/// Replace the pixels of an RGB 565 little endian frame with the pixels of the previous frame if
+/// each color component differs by at most `tolerance`.
+///
+/// The frame is left unchanged if the previous frame has a different size.
+fn keep_similar_pixels(frame: &mut [u8], prev_frame: &[u8], tolerance: u8) {
+    if frame.len() != prev_frame.len() {
+        return;
+    }
+
+    let tolerance = u16::from(tolerance);
+    for (pixel, prev) in frame.chunks_exact_mut(2).zip(prev_frame.chunks_exact(2)) {
+        let new = u16::from_le_bytes([pixel[0], pixel[1]]);
+        let old = u16::from_le_bytes([prev[0], prev[1]]);
+        if (new >> 11).abs_diff(old >> 11) <= tolerance
+            && ((new >> 5) & 0x3F).abs_diff((old >> 5) & 0x3F) <= tolerance
+            && (new & 0x1F).abs_diff(old & 0x1F) <= tolerance
         {
-            return Ok(p.port_name);
+            pixel.copy_from_slice(prev);
         }
     }
+}
+
+/// Serial port found by [list_serial_ports].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerialPortEntry {
+    /// Port name, e.g. `/dev/ttyACM0`.
+    pub name: String,
+    /// USB vendor id, only set for USB serial ports.
+    pub vid: Option<u16>,
+    /// USB product id, only set for USB serial ports.
+    pub pid: Option<u16>,
+    /// Optional USB product string.
+    pub product: Option<String>,
+    /// Optional USB serial number.
+    pub serial_number: Option<String>,
+}
+
+/// List all available serial ports, e.g. to find the display device.
+pub fn list_serial_ports() -> serialport::Result<Vec<SerialPortEntry>> {
+    Ok(serial_port_entries(serialport::available_ports()?))
+}
+
+/// Convert the platform specific serial port descriptors.
+///
+/// USB ids of `0:0` are treated as missing, and the USB strings are trimmed from surrounding
+/// whitespace and NUL padding.
+fn serial_port_entries(ports: Vec<SerialPortInfo>) -> Vec<SerialPortEntry> {
+    let usb_id = |id: u16| (id != 0).then_some(id);
+    let usb_text = |text: Option<String>| {
+        text.map(|text| {
+            text.trim_matches(|c: char| c == '\0' || c.is_whitespace())
+                .to_string()
+        })
+        .filter(|text| !text.is_empty())
+    };
+
+    ports
+        .into_iter()
+        .map(|port| match port.port_type {
+            SerialPortType::UsbPort(info) => {
+                let (vid, pid) = match (usb_id(info.vid), usb_id(info.pid)) {
+                    (Some(vid), Some(pid)) => (Some(vid), Some(pid)),
+                    _ => (None, None),
+                };
+                SerialPortEntry {
+                    name: port.port_name,
+                    vid,
+                    pid,
+                    product: usb_text(info.product),
+                    serial_number: usb_text(info.serial_number),
+                }
+            }
+            _ => SerialPortEntry {
+                name: port.port_name,
+                vid: None,
+                pid: None,
+                product: None,
+                serial_number: None,
+            },
+        })
+        .collect()
+}
+
+/// Normalize a USB string for comparison: uppercase with `-` and `_` replaced by spaces.
+fn normalize_usb_text(text: &str) -> String {
+    text.trim().to_ascii_uppercase().replace(['-', '_'], " ")
+}
+
+/// Describe a serial port with its USB ids, product and serial number for logging.
+fn describe_port(entry: &SerialPortEntry) -> String {
+    let ids = match (entry.vid, entry.pid) {
+        (Some(vid), Some(pid)) => format!("{vid:04x}:{pid:04x}"),
+        _ => "-".to_string(),
+    };
+    format!(
+        "{} ({ids}, product: {}, serial: {})",
+        entry.name,
+        entry.product.as_deref().unwrap_or("-"),
+        entry.serial_number.as_deref().unwrap_or("-")
+    )
+}
+
+/// Parse a USB UART device id in `vid:pid` hex notation.
+fn parse_usb_id(id: &str) -> anyhow::Result<(u16, u16)> {
+    let (vid, pid) = id
+        .split_once(':')
+        .with_context(|| "Error parsing serial port ID. Expected `vid:pid` format.")?;
+    let parse = |value: &str| {
+        let value = value.trim();
+        let value = value
+            .strip_prefix("0x")
+            .or_else(|| value.strip_prefix("0X"))
+            .unwrap_or(value);
+        u16::from_str_radix(value, 16)
+            .with_context(|| format!("Invalid hex value in serial port ID: {id}"))
+    };
+
+    Ok((parse(vid)?, parse(pid)?))
+}
+
+/// Product string part to identify an AOOSTAR display without matching USB vid:pid.
+const AOOSTAR_PRODUCT: &str = "AOOSTAR";
+
+/// Find a USB serial port and return the port name.
+///
+/// If no port matches the vid:pid, for example if the USB ids are not reported on the platform,
+/// the first USB serial port without USB ids and a product string containing `AOOSTAR` is used.
+pub fn find_usb_serial_port(vid: u16, pid: u16) -> serialport::Result<String> {
+    find_usb_serial_port_with_product(vid, pid).map(|(port, _)| port)
+}
+
+/// Find a USB serial port like [find_usb_serial_port] and return the port name with the optional
+/// USB product string.
+pub fn find_usb_serial_port_with_product(
+    vid: u16,
+    pid: u16,
+) -> serialport::Result<(String, Option<String>)> {
+    find_usb_serial_port_by_serial(vid, pid, None)
+}
+
+/// Find a USB serial port like [find_usb_serial_port_with_product] with an optional USB serial
+/// number.
+///
+/// If a `serial_number` is given, only the USB serial port with this serial number is matched.
+/// The serial number is compared case-insensitive, since it is reported differently depending on
+/// the platform.
+pub fn find_usb_serial_port_by_serial(
+    vid: u16,
+    pid: u16,
+    serial_number: Option<&str>,
+) -> serialport::Result<(String, Option<String>)> {
+    match serial_number {
+        Some(serial) => info!("Looking for USB serial port {vid:x}:{pid:x} with serial {serial}"),
+        None => info!("Looking for USB serial port {vid:x}:{pid:x}"),
+    }
+    select_usb_serial_port(serialport::available_ports()?, vid, pid, serial_number)
+}
+
+fn select_usb_serial_port(
+    ports: Vec<SerialPortInfo>,
+    vid: u16,
+    pid: u16,
+    serial_number: Option<&str>,
+) -> serialport::Result<(String, Option<String>)> {
+    let entries = serial_port_entries(ports);
+    for entry in &entries {
+        debug!("Found serial port: {}", describe_port(entry));
+    }
+
+    let serial_matches = |entry: &SerialPortEntry| match serial_number {
+        Some(serial) => entry
+            .serial_number
+            .as_deref()
+            .is_some_and(|value| normalize_usb_text(value) == normalize_usb_text(serial)),
+        None => true,
+    };
+
+    if let Some(entry) = entries
+        .iter()
+        .find(|entry| entry.vid == Some(vid) && entry.pid == Some(pid) && serial_matches(entry))
+    {
+        return Ok((entry.name.clone(), entry.product.clone()));
+    }
 
+    // Fallback for platforms without USB ids, e.g. Windows: a device with different USB ids is
+    // never selected.
+    if let Some(entry) = entries.iter().find(|entry| {
+        entry.vid.is_none()
+            && entry
+                .product
+                .as_deref()
+                .is_some_and(|product| normalize_usb_text(product).contains(AOOSTAR_PRODUCT))
+            && serial_matches(entry)
+    }) {
+        warn!(
+            "USB serial port {vid:x}:{pid:x} not found, using AOOSTAR device {}",
+            describe_port(entry)
+        );
+        return Ok((entry.name.clone(), entry.product.clone()));
+    }
+
+    let candidates: Vec<_> = entries
+        .iter()
+        .filter(|entry| entry.vid.is_some() || entry.product.is_some())
+        .map(describe_port)
+        .collect();
+    let candidates = match candidates.is_empty() {
+        true => "No USB serial ports found".to_string(),
+        false => format!("Available USB serial ports: {}", candidates.join(", ")),
+    };
+    let description = match serial_number {
+        Some(serial) => {
+            format!("USB serial port {vid:x}:{pid:x} with serial {serial} not found. {candidates}")
+        }
+        None => format!("USB serial port {vid:x}:{pid:x} not found. {candidates}"),
+    };
     Err(serialport::Error::new(
         serialport::ErrorKind::NoDevice,
-        format!("USB serial port {vid:x}:{pid:x} not found"),
+        description,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Extract the frame data of the recorded image chunks.
+    fn recorded_frame(recorded: &[u8]) -> Vec<u8> {
+        let data = recorded
+            .strip_prefix(HEADER_START.as_slice())
+            .and_then(|data| data.strip_suffix(HEADER_END.as_slice()))
+            .expect("image start and end headers");
+
+        let mut frame = Vec::new();
+        let mut chunks = data;
+        while !chunks.is_empty() {
+            let chunk = chunks
+                .strip_prefix(HEADER.as_slice())
+                .expect("chunk header");
+            let offset = u32::from_le_bytes(chunk[..4].try_into().unwrap()) as usize;
+            let len = IMG_CHUNK_SIZE.min(chunk.len() - 4);
+            assert_eq!(offset, frame.len());
+            frame.extend_from_slice(&chunk[4..4 + len]);
+            chunks = &chunk[4 + len..];
+        }
+
+        frame
+    }
+
+    /// Open a display on a fake serial port, recording all sent data.
+    ///
+    /// The display has a frame size of 4 full image chunks, see [chunk_image]. The builder can be
+    /// further customized with `configure` before opening the port.
+    fn recording_screen(
+        port: FakeSerialPort,
+        configure: impl FnOnce(&mut AooScreenBuilder),
+    ) -> (AooScreen, Arc<Mutex<Vec<u8>>>) {
+        let recorded = Arc::new(Mutex::new(Vec::new()));
+        let mut builder = AooScreenBuilder::new();
+        builder.model(DisplayModel::Custom {
+            width: IMG_CHUNK_SIZE as u32,
+            height: 2,
+        });
+        configure(&mut builder);
+        let screen = builder.open_port(Box::new(port.with_recording(recorded.clone())));
+
+        (screen, recorded)
+    }
+
+    /// Solid color image of the [recording_screen] frame size.
+    fn chunk_image(color: Rgb<u8>) -> RgbImage {
+        RgbImage::from_pixel(IMG_CHUNK_SIZE as u32, 2, color)
+    }
+
+    fn count_chunks(recorded: &[u8]) -> usize {
+        recorded
+            .windows(HEADER.len())
+            .filter(|window| *window == HEADER)
+            .count()
+    }
+
+    /// Send the same image twice and return the number of sent image chunks.
+    fn send_identical_frames(enable_cache: bool) -> usize {
+        let (mut screen, recorded) = recording_screen(FakeSerialPort::new(), |_| {});
+        screen.enable_cache(enable_cache);
+        let image = chunk_image(Rgb([0, 0, 255]));
+
+        screen.send_image(&image).unwrap();
+        screen.send_image(&image).unwrap();
+
+        count_chunks(&recorded.lock().unwrap())
+    }
+
+    #[test]
+    fn trace_file_records_frame_headers_and_offsets() {
+        let path = std::env::temp_dir().join(format!("asterctl-lcd-{}.trace", std::process::id()));
+        let trace_file = std::fs::File::create(&path).unwrap();
+        let (mut screen, _) = recording_screen(FakeSerialPort::new(), |builder| {
+            builder.trace(Some(Box::new(trace_file)));
+        });
+        let image = chunk_image(Rgb([0, 0, 255]));
+
+        screen.send_image(&image).unwrap();
+        screen.send_image(&image).unwrap();
+        let trace = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<_> = trace.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "start aa55aa550500000004000f2f00040b00",
+                "chunk offset=0 len=47 aa55aa550800000000000000",
+                "chunk offset=47 len=47 aa55aa55080000002f000000",
+                "chunk offset=94 len=47 aa55aa55080000005e000000",
+                "chunk offset=141 len=47 aa55aa55080000008d000000",
+                "end aa55aa5506000000",
+                "frame sent=4 skipped=0",
+                "start aa55aa550500000004000f2f00040b00",
+                "end aa55aa5506000000",
+                "frame sent=0 skipped=4",
+            ]
+        );
+    }
+
+    #[test]
+    fn cache_skips_unchanged_chunks() {
+        assert_eq!(send_identical_frames(true), 4);
+    }
+
+    #[test]
+    fn disabled_cache_sends_all_chunks() {
+        assert_eq!(send_identical_frames(false), 8);
+    }
+
+    /// Send an image and a second image with a changed blue component of the first pixel, and
+    /// return the number of sent image chunks of the second frame.
+    fn send_changed_pixel(tolerance: u8, blue: u8) -> (usize, AooScreen) {
+        let (mut screen, recorded) = recording_screen(FakeSerialPort::new(), |builder| {
+            builder.cache_tolerance(tolerance);
+        });
+        let mut image = chunk_image(Rgb([0, 0, 255]));
+
+        screen.send_image(&image).unwrap();
+        recorded.lock().unwrap().clear();
+        image.put_pixel(0, 0, Rgb([0, 0, blue]));
+        screen.send_image(&image).unwrap();
+
+        (count_chunks(&recorded.lock().unwrap()), screen)
+    }
+
+    #[test]
+    fn cache_tolerance_skips_one_lsb_change() {
+        // 8-bit blue 247 is 30 instead of 31 in RGB 565
+        let (chunks, screen) = send_changed_pixel(1, 247);
+
+        assert_eq!(chunks, 0);
+        let last_frame = screen.last_frame().unwrap();
+        assert_eq!(last_frame.get_pixel(0, 0), &Rgb([0, 0, 255]));
+    }
+
+    #[test]
+    fn cache_tolerance_sends_larger_change() {
+        let (chunks, screen) = send_changed_pixel(1, 239);
+
+        assert_eq!(chunks, 1);
+        let last_frame = screen.last_frame().unwrap();
+        assert_eq!(last_frame.get_pixel(0, 0).0[2] >> 3, 239 >> 3);
+    }
+
+    #[test]
+    fn cache_without_tolerance_sends_one_lsb_change() {
+        let (chunks, _) = send_changed_pixel(0, 247);
+
+        assert_eq!(chunks, 1);
+    }
+
+    #[test]
+    fn transfer_stats_of_sent_frame() {
+        // simulated transfer time of 10'000 bytes/s with 10 bits per byte
+        let mut port = FakeSerialPort::new();
+        port.set_baud_rate(100_000).unwrap();
+        let mut builder = AooScreenBuilder::new();
+        builder.model(DisplayModel::Custom {
+            width: IMG_CHUNK_SIZE as u32,
+            height: 2,
+        });
+        // not recording, which would skip the simulated transfer time
+        let mut screen = builder.open_port(Box::new(port));
+        let image = chunk_image(Rgb([0, 0, 255]));
+
+        assert_eq!(screen.transfer_stats().bytes_per_sec(), None);
+        screen.send_image(&image).unwrap();
+
+        // the throughput calculation itself is covered by the throughput module tests
+        let stats = screen.transfer_stats();
+        let frame_bytes = HEADER_START.len() + 4 * (HEADER.len() + 4 + IMG_CHUNK_SIZE);
+        assert_eq!(stats.total_bytes, (frame_bytes + HEADER_END.len()) as u64);
+        assert_eq!(stats.window_bytes, stats.total_bytes);
+        assert!(!stats.window_write_time.is_zero());
+    }
+
+    /// Send a single frame of 4 image chunks with frame verification and the given display responses.
+    fn send_verified_frame(responses: &[u8]) -> (anyhow::Result<()>, usize, AooScreen) {
+        let port = FakeSerialPort::new().with_responses(responses);
+        let (mut screen, recorded) = recording_screen(port, |builder| {
+            builder.verify_frames(true);
+        });
+        let image = chunk_image(Rgb([0, 255, 0]));
+
+        let result = screen.send_image(&image);
+        let chunks = count_chunks(&recorded.lock().unwrap());
+
+        (result, chunks, screen)
+    }
+
+    #[test]
+    fn verified_frame_acknowledged() {
+        let (result, chunks, screen) = send_verified_frame(b"A");
+
+        assert!(result.is_ok());
+        assert_eq!(chunks, 4);
+        assert!(screen.last_frame().is_some());
+    }
+
+    #[test]
+    fn verified_frame_resent_on_invalid_response() {
+        let (result, chunks, screen) = send_verified_frame(b"E");
+
+        assert!(result.is_ok());
+        assert_eq!(chunks, 8);
+        assert!(screen.last_frame().is_some());
+    }
+
+    #[test]
+    fn verified_frame_fails_after_retries() {
+        let (result, chunks, screen) = send_verified_frame(b"EEEE");
+
+        assert!(result.is_err());
+        assert_eq!(chunks, 4 * (SERIAL_RETRY as usize + 1));
+        assert!(screen.last_frame().is_none());
+    }
+
+    fn usb_port(name: &str, vid: u16, pid: u16, serial_number: Option<&str>) -> SerialPortInfo {
+        SerialPortInfo {
+            port_name: name.to_string(),
+            port_type: SerialPortType::UsbPort(serialport::UsbPortInfo {
+                vid,
+                pid,
+                serial_number: serial_number.map(str::to_string),
+                manufacturer: None,
+                product: Some(format!("{name} product")),
+            }),
+        }
+    }
+
+    fn mocked_ports() -> Vec<SerialPortInfo> {
+        vec![
+            SerialPortInfo {
+                port_name: "/dev/ttyS0".to_string(),
+                port_type: SerialPortType::Unknown,
+            },
+            usb_port("/dev/ttyUSB0", 0x1234, 0x5678, Some("AB01")),
+            usb_port("/dev/ttyACM0", USB_UART_VID, USB_UART_PID, Some("LCD01")),
+            usb_port("/dev/ttyACM1", USB_UART_VID, USB_UART_PID, Some("LCD02")),
+        ]
+    }
+
+    #[test]
+    fn select_usb_serial_port_by_serial_number() {
+        let (port, product) =
+            select_usb_serial_port(mocked_ports(), USB_UART_VID, USB_UART_PID, Some("LCD02"))
+                .unwrap();
+
+        assert_eq!(port, "/dev/ttyACM1");
+        assert_eq!(product.as_deref(), Some("/dev/ttyACM1 product"));
+    }
+
+    #[test]
+    fn select_usb_serial_port_without_serial_number_uses_first_match() {
+        let (port, _) =
+            select_usb_serial_port(mocked_ports(), USB_UART_VID, USB_UART_PID, None).unwrap();
+
+        assert_eq!(port, "/dev/ttyACM0");
+    }
+
+    #[test]
+    fn select_usb_serial_port_unknown_serial_number() {
+        let result =
+            select_usb_serial_port(mocked_ports(), USB_UART_VID, USB_UART_PID, Some("AB01"));
+
+        assert!(result.is_err());
+    }
+
+    fn usb_port_info(
+        name: &str,
+        vid: u16,
+        pid: u16,
+        product: Option<&str>,
+        serial_number: Option<&str>,
+    ) -> SerialPortInfo {
+        SerialPortInfo {
+            port_name: name.to_string(),
+            port_type: SerialPortType::UsbPort(serialport::UsbPortInfo {
+                vid,
+                pid,
+                serial_number: serial_number.map(str::to_string),
+                manufacturer: None,
+                product: product.map(str::to_string),
+            }),
+        }
+    }
+
+    #[test]
+    fn select_usb_serial_port_of_cross_platform_descriptors() {
+        // macOS: NUL padded product string, lowercase serial number
+        let macos = vec![
+            usb_port_info("/dev/cu.Bluetooth", 0, 0, None, None),
+            usb_port_info(
+                "/dev/cu.usbmodem1101",
+                USB_UART_VID,
+                USB_UART_PID,
+                Some("AOOSTAR WTR MAX\0"),
+                Some("lcd01"),
+            ),
+        ];
+        let (port, product) =
+            select_usb_serial_port(macos, USB_UART_VID, USB_UART_PID, Some("LCD01")).unwrap();
+        assert_eq!(port, "/dev/cu.usbmodem1101");
+        assert_eq!(product.as_deref(), Some("AOOSTAR WTR MAX"));
+
+        // Windows: missing USB ids, product string with port name
+        let windows = vec![
+            usb_port_info("COM1", 0x1234, 0x5678, Some("USB Serial"), None),
+            usb_port_info("COM3", 0, 0, Some("Aoostar-LCD (COM3)"), Some("LCD01")),
+        ];
+        let (port, product) =
+            select_usb_serial_port(windows, USB_UART_VID, USB_UART_PID, None).unwrap();
+        assert_eq!(port, "COM3");
+        assert_eq!(product.as_deref(), Some("Aoostar-LCD (COM3)"));
+    }
+
+    #[test]
+    fn select_usb_serial_port_not_found_lists_candidates() {
+        let ports = vec![
+            usb_port_info(
+                "/dev/ttyUSB0",
+                0x1234,
+                0x5678,
+                Some("USB Serial"),
+                Some("AB01"),
+            ),
+            usb_port_info("/dev/ttyUSB1", 0, 0, None, None),
+        ];
+
+        let error = select_usb_serial_port(ports, USB_UART_VID, USB_UART_PID, None).unwrap_err();
+
+        assert_eq!(
+            error.description,
+            "USB serial port 416:90a1 not found. Available USB serial ports: \
+            /dev/ttyUSB0 (1234:5678, product: USB Serial, serial: AB01)"
+        );
+    }
+
+    #[test]
+    fn select_usb_serial_port_ignores_aoostar_product_with_other_usb_id() {
+        let ports = vec![usb_port_info(
+            "/dev/ttyACM0",
+            0x1234,
+            0x5678,
+            Some("AOOSTAR WTR MAX"),
+            None,
+        )];
+
+        let result = select_usb_serial_port(ports, USB_UART_VID, USB_UART_PID, None);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_usb_id_notations() {
+        assert_eq!(parse_usb_id("416:90A1").unwrap(), (0x416, 0x90a1));
+        assert_eq!(parse_usb_id("0x0416:0x90a1").unwrap(), (0x416, 0x90a1));
+        assert_eq!(parse_usb_id(" 416 : 90a1 ").unwrap(), (0x416, 0x90a1));
+        assert!(parse_usb_id("41690a1").is_err());
+        assert!(parse_usb_id("416:xyz").is_err());
+    }
+
+    #[test]
+    fn serial_port_entries_of_usb_and_other_ports() {
+        let entries = serial_port_entries(mocked_ports());
+
+        assert_eq!(entries.len(), 4);
+        assert_eq!(
+            entries[0],
+            SerialPortEntry {
+                name: "/dev/ttyS0".to_string(),
+                vid: None,
+                pid: None,
+                product: None,
+                serial_number: None,
+            }
+        );
+        assert_eq!(
+            entries[2],
+            SerialPortEntry {
+                name: "/dev/ttyACM0".to_string(),
+                vid: Some(USB_UART_VID),
+                pid: Some(USB_UART_PID),
+                product: Some("/dev/ttyACM0 product".to_string()),
+                serial_number: Some("LCD01".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn fill_sends_solid_color_frame() {
+        let (mut screen, recorded) = recording_screen(FakeSerialPort::new(), |builder| {
+            builder.model(DisplayModel::Custom {
+                width: 10,
+                height: 8,
+            });
+        });
+
+        screen.fill(Rgb([255, 0, 0])).unwrap();
+
+        let frame = recorded_frame(&recorded.lock().unwrap());
+        assert_eq!(frame.len(), 10 * 8 * 2);
+        for pixel in frame.chunks(2) {
+            assert_eq!(u16::from_le_bytes([pixel[0], pixel[1]]), 0xF800);
+        }
+        assert_eq!(
+            screen.last_frame(),
+            Some(RgbImage::from_pixel(10, 8, Rgb([255, 0, 0])))
+        );
+    }
+
+    #[test]
+    fn off_after_switches_off_display_after_delay() {
+        let (mut screen, recorded) = recording_screen(FakeSerialPort::new(), |_| {});
+        let delay = Duration::from_millis(50);
+
+        let start = Instant::now();
+        screen.off_after(delay).unwrap();
+
+        assert!(start.elapsed() >= delay);
+        assert_eq!(recorded.lock().unwrap().as_slice(), DISPLAY_OFF.as_slice());
+    }
+
+    /// Initialize a display with the given scripted responses and return the number of sent
+    /// display on commands.
+    fn init_with_responses(port: FakeSerialPort) -> (anyhow::Result<()>, usize) {
+        let (mut screen, recorded) = recording_screen(port, |builder| {
+            builder.init_wait(Duration::ZERO);
+        });
+
+        let result = screen.init();
+        let on_commands = recorded
+            .lock()
+            .unwrap()
+            .windows(DISPLAY_ON.len())
+            .filter(|window| *window == DISPLAY_ON)
+            .count();
+
+        (result, on_commands)
+    }
+
+    #[test]
+    fn init_acknowledged() {
+        let (result, on_commands) = init_with_responses(FakeSerialPort::new());
+
+        assert!(result.is_ok());
+        assert_eq!(on_commands, 1);
+    }
+
+    #[test]
+    fn init_retries_after_missing_response() {
+        let port = FakeSerialPort::new().no_response().respond(b"A");
+
+        let (result, on_commands) = init_with_responses(port);
+
+        assert!(result.is_ok());
+        assert_eq!(on_commands, 2);
+    }
+
+    #[test]
+    fn init_retries_after_invalid_response() {
+        let port = FakeSerialPort::new()
+            .respond(b"E")
+            .no_response()
+            .respond(b"xA");
+
+        let (result, on_commands) = init_with_responses(port);
+
+        assert!(result.is_ok());
+        assert_eq!(on_commands, 3);
+    }
+
+    #[test]
+    fn fake_port_bytes_to_read_keeps_scripted_responses() {
+        let mut port = FakeSerialPort::new().no_response().respond(b"A");
+
+        assert_eq!(port.bytes_to_read().unwrap(), 0);
+        assert_eq!(port.bytes_to_read().unwrap(), 0);
+        let timeout = port.read(&mut [0u8; 4]).unwrap_err();
+        assert_eq!(timeout.kind(), std::io::ErrorKind::TimedOut);
+        assert_eq!(port.bytes_to_read().unwrap(), 1);
+    }
+
+    #[test]
+    fn init_fails_without_response() {
+        let mut port = FakeSerialPort::new();
+        for _ in 0..=SERIAL_RETRY {
+            port = port.no_response();
+        }
+
+        let (result, on_commands) = init_with_responses(port);
+
+        assert!(result.is_err());
+        assert_eq!(on_commands, SERIAL_RETRY as usize + 1);
+    }
+}
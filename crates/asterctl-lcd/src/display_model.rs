@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2025 Markus Zehnder
+
+use crate::DISPLAY_SIZE;
+
+/// AOOSTAR display model with its display resolution.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum DisplayModel {
+    /// AOOSTAR WTR MAX with a 960 x 376 display.
+    #[default]
+    WtrMax,
+    /// AOOSTAR GEM12+ PRO with a 960 x 376 display.
+    Gem12PlusPro,
+    /// Other display model with the given width and height in pixels.
+    Custom { width: u32, height: u32 },
+}
+
+impl DisplayModel {
+    /// Display resolution as width, height tuple in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        match self {
+            DisplayModel::WtrMax | DisplayModel::Gem12PlusPro => DISPLAY_SIZE,
+            DisplayModel::Custom { width, height } => (*width, *height),
+        }
+    }
+
+    /// Infer the display model from a USB product string.
+    ///
+    /// returns: the detected model, or `None` if the product string is not recognized.
+    pub fn from_usb_product(product: &str) -> Option<Self> {
+        let product = product.to_ascii_uppercase().replace(['-', '_'], " ");
+
+        if product.contains("WTR MAX") {
+            Some(DisplayModel::WtrMax)
+        } else if product.contains("GEM12") {
+            Some(DisplayModel::Gem12PlusPro)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_usb_product_detects_known_models() {
+        let model = DisplayModel::from_usb_product("AOOSTAR WTR-MAX LCD").unwrap();
+        assert_eq!(model, DisplayModel::WtrMax);
+        assert_eq!(model.size(), (960, 376));
+
+        let model = DisplayModel::from_usb_product("aoostar gem12+ pro").unwrap();
+        assert_eq!(model, DisplayModel::Gem12PlusPro);
+        assert_eq!(model.size(), (960, 376));
+    }
+
+    #[test]
+    fn from_usb_product_unknown_product() {
+        assert_eq!(DisplayModel::from_usb_product("USB Serial"), None);
+    }
+
+    #[test]
+    fn custom_model_size() {
+        let model = DisplayModel::Custom {
+            width: 480,
+            height: 320,
+        };
+        assert_eq!(model.size(), (480, 320));
+    }
+}
@@ -2,9 +2,25 @@
 // SPDX-FileCopyrightText: Copyright (c) 2025 Markus Zehnder
 
 use serialport::{ClearBuffer, DataBits, FlowControl, Parity, SerialPort, StopBits};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use std::thread::sleep;
 use std::time::Duration;
 
+/// Scripted read response of a [FakeSerialPort].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FakeResponse {
+    /// Response data returned by the next reads.
+    Data(Vec<u8>),
+    /// Missing response: no bytes are available to read and the next read times out.
+    NoResponse,
+}
+
+/// Simulated serial port for testing and development.
+///
+/// Written data can be recorded with [FakeSerialPort::recording], and display responses can be
+/// scripted with [FakeSerialPort::respond] and [FakeSerialPort::no_response]. The `A` acknowledge
+/// marker is returned once all scripted responses have been read.
 pub struct FakeSerialPort {
     baud_rate: u32,
     data_bits: DataBits,
@@ -12,6 +28,8 @@ pub struct FakeSerialPort {
     parity: Parity,
     stop_bits: StopBits,
     timeout: Duration,
+    recorded: Option<Arc<Mutex<Vec<u8>>>>,
+    responses: Mutex<VecDeque<FakeResponse>>,
 }
 
 impl Default for FakeSerialPort {
@@ -29,19 +47,88 @@ impl FakeSerialPort {
             parity: Parity::None,
             stop_bits: StopBits::One,
             timeout: Default::default(),
+            recorded: None,
+            responses: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Create a fake serial port recording all written data into the given buffer.
+    ///
+    /// The transfer time is not simulated when recording.
+    pub fn recording(buffer: Arc<Mutex<Vec<u8>>>) -> FakeSerialPort {
+        Self::new().with_recording(buffer)
+    }
+
+    /// Record all written data into the given buffer.
+    ///
+    /// The transfer time is not simulated when recording.
+    pub fn with_recording(mut self, buffer: Arc<Mutex<Vec<u8>>>) -> FakeSerialPort {
+        self.recorded = Some(buffer);
+        self
+    }
+
+    /// Set the response bytes returned by the display, one byte per read.
+    ///
+    /// The `A` acknowledge marker is returned after all responses have been read.
+    pub fn with_responses(mut self, responses: &[u8]) -> FakeSerialPort {
+        self.script().clear();
+        for response in responses {
+            self = self.respond(&[*response]);
         }
+        self
+    }
+
+    /// Append a scripted response of the display, returned after all previous responses.
+    pub fn respond(self, data: &[u8]) -> FakeSerialPort {
+        self.script().push_back(FakeResponse::Data(data.to_vec()));
+        self
+    }
+
+    /// Append a scripted missing response of the display: no bytes are available to read, and
+    /// the next read times out.
+    pub fn no_response(self) -> FakeSerialPort {
+        self.script().push_back(FakeResponse::NoResponse);
+        self
+    }
+
+    fn script(&self) -> std::sync::MutexGuard<'_, VecDeque<FakeResponse>> {
+        self.responses
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
     }
 }
 
 impl std::io::Read for FakeSerialPort {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        buf[0] = b'A';
-        Ok(1)
+        let mut script = self.script();
+        match script.pop_front() {
+            Some(FakeResponse::Data(data)) => {
+                let len = data.len().min(buf.len());
+                buf[..len].copy_from_slice(&data[..len]);
+                if len < data.len() {
+                    script.push_front(FakeResponse::Data(data[len..].to_vec()));
+                }
+                Ok(len)
+            }
+            Some(FakeResponse::NoResponse) => Err(std::io::ErrorKind::TimedOut.into()),
+            None => {
+                buf[0] = b'A';
+                Ok(1)
+            }
+        }
     }
 }
 
 impl std::io::Write for FakeSerialPort {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some(recorded) = &self.recorded {
+            recorded
+                .lock()
+                .map_err(|_| std::io::Error::other("poisoned record buffer"))?
+                .extend_from_slice(buf);
+            return Ok(buf.len());
+        }
+
         // just some approximation, additional overhead like flushing etc is not considered
         let byte_rate =
             self.baud_rate / (1 + u8::from(self.data_bits) + u8::from(self.stop_bits)) as u32;
@@ -139,7 +226,11 @@ impl SerialPort for FakeSerialPort {
     }
 
     fn bytes_to_read(&self) -> serialport::Result<u32> {
-        Ok(1)
+        match self.script().front() {
+            Some(FakeResponse::Data(data)) => Ok(data.len() as u32),
+            Some(FakeResponse::NoResponse) => Ok(0),
+            None => Ok(1),
+        }
     }
 
     fn bytes_to_write(&self) -> serialport::Result<u32> {
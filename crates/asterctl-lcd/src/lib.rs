@@ -5,13 +5,22 @@
 #![deny(unsafe_code)]
 
 use bytes::{BufMut, BytesMut};
-use image::{RgbImage, RgbaImage};
+use image::{Rgb, RgbImage, RgbaImage};
 
 mod aoo_screen;
+mod display_model;
 mod fake_serialport;
+mod protocol_trace;
+mod throughput;
 
-pub use aoo_screen::{AooScreen, AooScreenBuilder, DISPLAY_SIZE};
-pub use fake_serialport::FakeSerialPort;
+pub use aoo_screen::{
+    AooScreen, AooScreenBuilder, DISPLAY_SIZE, SerialPortEntry, find_usb_serial_port,
+    find_usb_serial_port_by_serial, find_usb_serial_port_with_product, list_serial_ports,
+};
+pub use display_model::DisplayModel;
+pub use fake_serialport::{FakeResponse, FakeSerialPort};
+pub use protocol_trace::TRACE_TARGET;
+pub use throughput::TransferStats;
 
 /// Trait definition to get a RGB 565 representation from a source image.
 pub trait ToRgb565 {
@@ -20,10 +29,43 @@ pub trait ToRgb565 {
 
     /// Convert a single RGB 888 pixel to 16 bit RGB 565 format.
     fn convert_rgb(&self, r: u8, g: u8, b: u8) -> u16 {
-        ((r & 248) as u16) << 8 | ((g & 252) as u16) << 3 | ((b as u16) >> 3)
+        rgb_to_rgb565(r, g, b)
     }
 }
 
+/// Convert a single RGB 888 pixel to 16 bit RGB 565 format.
+pub fn rgb_to_rgb565(r: u8, g: u8, b: u8) -> u16 {
+    ((r & 248) as u16) << 8 | ((g & 252) as u16) << 3 | ((b as u16) >> 3)
+}
+
+/// Convert a single 16 bit RGB 565 pixel to RGB 888 format.
+///
+/// The upper bits are replicated into the lower bits to use the full 8 bit range.
+pub fn rgb565_to_rgb(pixel: u16) -> Rgb<u8> {
+    let r = ((pixel >> 11) & 0x1F) as u8;
+    let g = ((pixel >> 5) & 0x3F) as u8;
+    let b = (pixel & 0x1F) as u8;
+    Rgb([r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2])
+}
+
+/// Convert an RGB 565 image in little endian format to an RGB 888 image.
+///
+/// This is the inverse function of [ToRgb565::to_rgb565_le].
+///
+/// returns: None if the data size doesn't match the image dimensions.
+pub fn from_rgb565_le(data: &[u8], width: u32, height: u32) -> Option<RgbImage> {
+    if data.len() != width as usize * height as usize * 2 {
+        return None;
+    }
+
+    let pixels = data
+        .chunks_exact(2)
+        .flat_map(|pixel| rgb565_to_rgb(u16::from_le_bytes([pixel[0], pixel[1]])).0)
+        .collect();
+
+    RgbImage::from_raw(width, height, pixels)
+}
+
 // TODO quick & dirty approach for converting RgbImage & RgbaImage to RGB 565.
 //      There should be a more generic way, maybe with PixelEnumerator...
 impl ToRgb565 for &RgbImage {
@@ -51,3 +93,51 @@ impl ToRgb565 for &RgbaImage {
         img_rgb565
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, ImageReader};
+    use std::io::Cursor;
+
+    #[test]
+    fn rgb565_conversion_keeps_black_and_white() {
+        assert_eq!(rgb565_to_rgb(rgb_to_rgb565(0, 0, 0)), Rgb([0, 0, 0]));
+        assert_eq!(
+            rgb565_to_rgb(rgb_to_rgb565(255, 255, 255)),
+            Rgb([255, 255, 255])
+        );
+    }
+
+    #[test]
+    fn rgb565_round_trip_to_png_within_quantization_tolerance() {
+        let image = RgbImage::from_fn(64, 16, |x, y| {
+            Rgb([(x * 4) as u8, (y * 16 + x) as u8, (255 - x * 3) as u8])
+        });
+
+        let rgb565 = (&image).to_rgb565_le();
+        let restored = from_rgb565_le(&rgb565, 64, 16).expect("valid image size");
+        let mut png = Vec::new();
+        restored
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        let decoded = ImageReader::with_format(Cursor::new(png), ImageFormat::Png)
+            .decode()
+            .unwrap()
+            .to_rgb8();
+
+        assert_eq!(decoded.dimensions(), image.dimensions());
+        for (original, decoded) in image.pixels().zip(decoded.pixels()) {
+            let diff = |i: usize| original[i].abs_diff(decoded[i]);
+            assert!(
+                diff(0) <= 7 && diff(1) <= 3 && diff(2) <= 7,
+                "{original:?} {decoded:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn from_rgb565_le_rejects_invalid_size() {
+        assert!(from_rgb565_le(&[0; 6], 2, 2).is_none());
+    }
+}
@@ -0,0 +1,109 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2025 Markus Zehnder
+
+//! Protocol trace of the messages sent to the display.
+
+use log::trace;
+use std::fmt::Write as _;
+use std::io::Write;
+
+/// Log target of the protocol trace messages.
+pub const TRACE_TARGET: &str = "asterctl_lcd::protocol";
+
+/// Records every message sent to the display as a single text line.
+///
+/// The lines are logged at trace level with the [TRACE_TARGET] and optionally written to a trace
+/// writer, for example to compare the output with USB captures of the original app:
+/// - `on <bytes>`, `off <bytes>`: display on and off commands.
+/// - `start <bytes>`, `end <bytes>`: frame start and end headers.
+/// - `chunk offset=<offset> len=<len> <header bytes>`: image data chunk with its header and offset.
+/// - `frame sent=<chunks> skipped=<chunks>`: summary of a frame with the number of sent and
+///   skipped unchanged chunks.
+///
+/// All bytes are written in hex notation.
+pub(crate) struct ProtocolTrace {
+    writer: Option<Box<dyn Write + Send>>,
+}
+
+impl ProtocolTrace {
+    pub fn new(writer: Option<Box<dyn Write + Send>>) -> Self {
+        Self { writer }
+    }
+
+    /// Record a message sent to the display.
+    ///
+    /// The trace writer is flushed after all messages except image data chunks.
+    pub fn message(&mut self, data: &[u8], kind: MessageKind) -> std::io::Result<()> {
+        match kind {
+            MessageKind::Chunk { offset, header_len } => {
+                let header_len = header_len.min(data.len());
+                self.write_line(&format!(
+                    "chunk offset={offset} len={} {}",
+                    data.len() - header_len,
+                    hex(&data[..header_len])
+                ))
+            }
+            kind => {
+                self.write_line(&format!("{} {}", kind.name(), hex(data)))?;
+                self.flush()
+            }
+        }
+    }
+
+    /// Record the summary of a sent frame and flush the trace writer.
+    pub fn frame(&mut self, sent_chunks: usize, skipped_chunks: usize) -> std::io::Result<()> {
+        self.write_line(&format!(
+            "frame sent={sent_chunks} skipped={skipped_chunks}"
+        ))?;
+        self.flush()
+    }
+
+    /// Flush the trace writer.
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        trace!(target: TRACE_TARGET, "{line}");
+        match self.writer.as_mut() {
+            Some(writer) => writeln!(writer, "{line}"),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Kind of a message sent to the display.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum MessageKind {
+    On,
+    Off,
+    FrameStart,
+    FrameEnd,
+    /// Image data chunk at the given frame offset, with the length of the chunk header.
+    Chunk {
+        offset: usize,
+        header_len: usize,
+    },
+}
+
+impl MessageKind {
+    fn name(self) -> &'static str {
+        match self {
+            MessageKind::On => "on",
+            MessageKind::Off => "off",
+            MessageKind::FrameStart => "start",
+            MessageKind::FrameEnd => "end",
+            MessageKind::Chunk { .. } => "chunk",
+        }
+    }
+}
+
+fn hex(data: &[u8]) -> String {
+    data.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
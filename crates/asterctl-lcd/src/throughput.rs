@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2025 Markus Zehnder
+
+//! Serial transfer throughput of the messages sent to the display.
+
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+/// Rolling window of the throughput measurement.
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
+
+/// Serial transfer statistics of the messages sent to the display, see
+/// [AooScreen::transfer_stats](crate::AooScreen::transfer_stats).
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct TransferStats {
+    /// Total number of bytes sent since opening the display.
+    pub total_bytes: u64,
+    /// Number of bytes sent within the rolling window of the last 10 seconds.
+    pub window_bytes: u64,
+    /// Time spent writing the `window_bytes` to the serial port.
+    pub window_write_time: Duration,
+}
+
+impl TransferStats {
+    /// Effective serial throughput in bytes per second while writing to the serial port.
+    ///
+    /// This is independent of the time between frames, e.g. for rendering the next frame.
+    ///
+    /// returns: None if no data has been sent within the rolling window.
+    pub fn bytes_per_sec(&self) -> Option<f64> {
+        if self.window_write_time.is_zero() {
+            return None;
+        }
+        Some(self.window_bytes as f64 / self.window_write_time.as_secs_f64())
+    }
+}
+
+impl Display for TransferStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.bytes_per_sec() {
+            Some(rate) => write!(f, "{:.1} KB/s", rate / 1000.0)?,
+            None => write!(f, "idle")?,
+        }
+        write!(
+            f,
+            ", {} bytes in the last {}s, {} bytes total",
+            self.window_bytes,
+            THROUGHPUT_WINDOW.as_secs(),
+            self.total_bytes
+        )
+    }
+}
+
+/// Throughput measurement of the serial port writes over a rolling window.
+#[derive(Debug)]
+pub(crate) struct ThroughputMeter {
+    samples: VecDeque<Sample>,
+    total_bytes: u64,
+}
+
+/// A single serial port write.
+#[derive(Debug, Copy, Clone)]
+struct Sample {
+    /// End time of the write.
+    time: Instant,
+    bytes: usize,
+    duration: Duration,
+}
+
+impl ThroughputMeter {
+    pub fn new() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            total_bytes: 0,
+        }
+    }
+
+    /// Record a serial port write of `bytes` which took `duration` and ended now.
+    pub fn record(&mut self, bytes: usize, duration: Duration) {
+        self.record_at(Instant::now(), bytes, duration);
+    }
+
+    fn record_at(&mut self, time: Instant, bytes: usize, duration: Duration) {
+        self.total_bytes += bytes as u64;
+        self.samples.push_back(Sample {
+            time,
+            bytes,
+            duration,
+        });
+        self.prune(time);
+    }
+
+    /// Transfer statistics of the rolling window up to now.
+    pub fn stats(&self) -> TransferStats {
+        self.stats_at(Instant::now())
+    }
+
+    fn stats_at(&self, now: Instant) -> TransferStats {
+        let window = self
+            .samples
+            .iter()
+            .filter(|sample| now.saturating_duration_since(sample.time) <= THROUGHPUT_WINDOW);
+        let (window_bytes, window_write_time) = window
+            .fold((0u64, Duration::ZERO), |(bytes, duration), sample| {
+                (bytes + sample.bytes as u64, duration + sample.duration)
+            });
+
+        TransferStats {
+            total_bytes: self.total_bytes,
+            window_bytes,
+            window_write_time,
+        }
+    }
+
+    /// Remove all samples older than the rolling window.
+    fn prune(&mut self, now: Instant) {
+        while let Some(sample) = self.samples.front()
+            && now.saturating_duration_since(sample.time) > THROUGHPUT_WINDOW
+        {
+            self.samples.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throughput_of_rolling_window() {
+        let start = Instant::now();
+        let mut meter = ThroughputMeter::new();
+        meter.record_at(start, 5000, Duration::from_millis(100));
+        meter.record_at(
+            start + Duration::from_secs(5),
+            1000,
+            Duration::from_millis(10),
+        );
+        meter.record_at(
+            start + Duration::from_secs(12),
+            2000,
+            Duration::from_millis(10),
+        );
+
+        let stats = meter.stats_at(start + Duration::from_secs(12));
+        assert_eq!(stats.total_bytes, 8000);
+        assert_eq!(stats.window_bytes, 3000);
+        assert_eq!(stats.window_write_time, Duration::from_millis(20));
+        assert_eq!(stats.bytes_per_sec(), Some(150_000.0));
+
+        let stats = meter.stats_at(start + Duration::from_secs(30));
+        assert_eq!(stats.total_bytes, 8000);
+        assert_eq!(stats.bytes_per_sec(), None);
+    }
+}
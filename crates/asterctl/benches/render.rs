@@ -0,0 +1,63 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+// SPDX-FileCopyrightText: Copyright (c) 2025 Markus Zehnder
+
+//! Benchmark of rendering a representative sensor panel with [PanelRenderer::render].
+
+use asterctl::cfg::Panel;
+use asterctl::render::PanelRenderer;
+use asterctl_lcd::DISPLAY_SIZE;
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::collections::HashMap;
+use std::hint::black_box;
+
+/// Panel with text and progress sensors in two columns, using the built-in default font.
+fn benchmark_panel() -> Panel {
+    let mut sensors = Vec::new();
+    for idx in 0..8 {
+        let y = 20 + idx * 44;
+        sensors.push(format!(
+            r#"{{"mode": 1, "label": "text_{idx}", "value": "", "unit": " °C", "x": 20, "y": {y},
+            "fontSize": 28, "integerDigits": -1, "decimalDigits": 1, "pic": ""}}"#
+        ));
+        sensors.push(format!(
+            r##"{{"mode": 3, "label": "progress_{idx}", "value": "", "unit": "", "x": 400, "y": {y},
+            "width": 500, "height": 24, "direction": 1, "minValue": 0, "maxValue": 100,
+            "integerDigits": -1, "decimalDigits": -1, "pic": "",
+            "gradient": [{{"value": 0, "color": "#00ff00"}}, {{"value": 100, "color": "#ff0000"}}]}}"##
+        ));
+    }
+
+    serde_json::from_str(&format!(
+        r#"{{"name": "Benchmark", "sensor": [{}]}}"#,
+        sensors.join(",")
+    ))
+    .expect("valid benchmark panel")
+}
+
+/// Deterministic sensor values of the benchmark panel.
+fn benchmark_values() -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    for idx in 0..8 {
+        values.insert(format!("text_{idx}"), format!("{}.{}", 30 + idx * 7, idx));
+        values.insert(format!("progress_{idx}"), format!("{}", idx * 12));
+    }
+    values
+}
+
+fn render_panel(c: &mut Criterion) {
+    let panel = benchmark_panel();
+    let values = benchmark_values();
+    let mut renderer = PanelRenderer::new(DISPLAY_SIZE, "fonts", "cfg");
+
+    c.bench_function("render panel", |b| {
+        b.iter(|| {
+            renderer
+                .render(black_box(&panel), black_box(&values))
+                .map(|image| image.width())
+                .expect("rendered panel")
+        })
+    });
+}
+
+criterion_group!(benches, render_panel);
+criterion_main!(benches);
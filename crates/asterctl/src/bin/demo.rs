@@ -4,7 +4,7 @@
 use asterctl::cfg;
 use asterctl::font::FontHandler;
 use asterctl::render::PanelRenderer;
-use asterctl_lcd::{AooScreen, AooScreenBuilder, DISPLAY_SIZE};
+use asterctl_lcd::{AooScreen, AooScreenBuilder};
 
 use ab_glyph::PxScale;
 use clap::Parser;
@@ -93,9 +93,7 @@ fn main() -> anyhow::Result<()> {
     )?;
 
     if let Some(off) = args.off_after {
-        info!("Switching off display in {off}s");
-        sleep(Duration::from_secs(off as u64));
-        screen.off()?;
+        screen.off_after(Duration::from_secs(off as u64))?;
     }
 
     info!("Bye bye!");
@@ -109,7 +107,7 @@ fn run_demo(
     font_dir: PathBuf,
     save_images: bool,
 ) -> anyhow::Result<()> {
-    let rgb_img = demo_image()?;
+    let rgb_img = demo_image(screen.size())?;
 
     // fill left and right side of the loaded image with neighboring pixel color
     const WIDTH: u32 = 108;
@@ -125,6 +123,11 @@ fn run_demo(
             cfg::load_cfg(config_dir.join(config))?
         };
 
+        let number_format = cfg.setup.number_format();
+        let default_digits = (
+            cfg.setup.default_integer_digits,
+            cfg.setup.default_decimal_digits,
+        );
         if let Some(panel) = cfg.get_next_active_panel() {
             info!("Displaying demo panel...");
 
@@ -137,13 +140,15 @@ fn run_demo(
                 );
             }
 
-            let mut renderer = PanelRenderer::new(DISPLAY_SIZE, &font_dir, &config_dir);
+            let mut renderer = PanelRenderer::new(screen.size(), &font_dir, &config_dir);
+            renderer.set_number_format(number_format);
+            renderer.set_default_digits(default_digits.0, default_digits.1);
             renderer.set_save_render_img(save_images);
             renderer.set_save_processed_pic(save_images);
             renderer.set_save_progress_layer(save_images);
 
             match renderer.render(panel, &demo_values) {
-                Ok(image) => screen.send_image(&image)?,
+                Ok(image) => screen.send_image(image)?,
                 Err(e) => error!("Error rendering panel '{}': {e:?}", panel.friendly_name()),
             }
         } else {
@@ -154,14 +159,14 @@ fn run_demo(
     Ok(())
 }
 
-fn demo_image() -> anyhow::Result<RgbImage> {
+fn demo_image(size: (u32, u32)) -> anyhow::Result<RgbImage> {
     let reader = ImageReader::new(Cursor::new(include_bytes!("aybabtu.png")))
         .with_guessed_format()
         .expect("Cursor io never fails");
 
     Ok(reader
         .decode()?
-        .resize_exact(DISPLAY_SIZE.0, DISPLAY_SIZE.1, FilterType::Lanczos3)
+        .resize_exact(size.0, size.1, FilterType::Lanczos3)
         .to_rgb8())
 }
 
@@ -223,6 +228,7 @@ fn demo_blinds(
     save_images: bool,
 ) -> anyhow::Result<RgbImage> {
     let mut rgb_img = background.clone();
+    let (img_width, img_height) = rgb_img.dimensions();
 
     info!("Masking {width} pixels of left & right image...");
 
@@ -230,7 +236,7 @@ fn demo_blinds(
         fs::create_dir_all("out")?;
     }
 
-    for y in 0..DISPLAY_SIZE.1 {
+    for y in 0..img_height {
         let color = *rgb_img.get_pixel(width + 1, y);
         draw_line_segment_mut(
             &mut rgb_img,
@@ -238,11 +244,11 @@ fn demo_blinds(
             (width as f32, y as f32),
             color,
         );
-        let color = *rgb_img.get_pixel(DISPLAY_SIZE.0 - width - 1, y);
+        let color = *rgb_img.get_pixel(img_width - width - 1, y);
         draw_line_segment_mut(
             &mut rgb_img,
-            ((DISPLAY_SIZE.0 - width) as f32, y as f32),
-            (DISPLAY_SIZE.0 as f32, y as f32),
+            ((img_width - width) as f32, y as f32),
+            (img_width as f32, y as f32),
             color,
         );
 
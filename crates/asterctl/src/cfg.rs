@@ -6,21 +6,40 @@
 //! Derived from the available Monitor3.json file in AOOSTAR-X v1.3.4.
 //! Likely not fully compatible with files created with the original editor.
 
+use crate::font::font_name;
+use crate::{
+    Notation, NumberFormat, Rounding, UnitPosition, parse_bytes, parse_leading_number, parse_number,
+};
 use anyhow::Context;
+use chrono::NaiveTime;
 use image::{Rgb, Rgba};
 use imageproc::definitions::HasWhite;
 use log::{info, warn};
 use regex::Regex;
-use serde::de::Visitor;
+#[cfg(feature = "schema")]
+use schemars::{JsonSchema, JsonSchema_repr, Schema, SchemaGenerator, json_schema, schema_for};
+use serde::de::{IgnoredAny, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_repr::{Deserialize_repr, Serialize_repr};
+#[cfg(feature = "schema")]
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::io::BufReader;
 use std::num::ParseIntError;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::{fmt, fs};
 
+/// JSON schema of the monitor configuration file as interpreted by `asterctl`.
+///
+/// Unknown fields are ignored when loading a configuration. Known AOOSTAR-X fields which are not
+/// used by `asterctl` are listed in the `x-ignored` extension of the containing object.
+#[cfg(feature = "schema")]
+pub fn config_schema() -> Schema {
+    schema_for!(MonitorConfig)
+}
+
 pub fn load_cfg<P: AsRef<Path>>(path: P) -> anyhow::Result<MonitorConfig> {
     let path = path.as_ref();
     let file = fs::File::open(path).with_context(|| format!("Failed to load config {path:?}"))?;
@@ -34,12 +53,12 @@ pub fn load_cfg<P: AsRef<Path>>(path: P) -> anyhow::Result<MonitorConfig> {
         }
         let panel = &config.panels[active as usize - 1];
 
-        println!(
+        info!(
             "Panel {active}: {}",
             panel.img.as_deref().unwrap_or_default()
         );
         for sensor in &panel.sensor {
-            println!(
+            info!(
                 "  {}: {} {} {}",
                 sensor.label,
                 sensor
@@ -87,6 +106,17 @@ pub fn load_custom_panel<P: AsRef<Path>>(path: P) -> anyhow::Result<Panel> {
     {
         panel.img = Some(img_path.join(img).display().to_string());
     }
+    if let Some(font_family) = &panel.font_family
+        && !font_family.is_empty()
+        && !Path::new(&font_family).is_absolute()
+    {
+        panel.font_family = Some(font_path.join(font_family).display().to_string());
+    }
+    for background in panel.time_backgrounds.iter_mut() {
+        if !Path::new(&background.img).is_absolute() {
+            background.img = img_path.join(&background.img).display().to_string();
+        }
+    }
     for sensor in panel.sensor.iter_mut() {
         if let Some(pic) = &sensor.pic
             && !Path::new(pic).is_absolute()
@@ -99,6 +129,12 @@ pub fn load_custom_panel<P: AsRef<Path>>(path: P) -> anyhow::Result<Panel> {
         {
             sensor.font_family = Some(font_path.join(font_family).display().to_string());
         }
+        if let Some(fill) = sensor.text_fill.as_mut()
+            && let Some(image) = &fill.image
+            && !Path::new(image).is_absolute()
+        {
+            fill.image = Some(img_path.join(image).display().to_string());
+        }
     }
 
     Ok(panel)
@@ -106,9 +142,12 @@ pub fn load_custom_panel<P: AsRef<Path>>(path: P) -> anyhow::Result<Panel> {
 
 /// AOOSTAR-X monitor json configuration file
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(transform = list_ignored_fields))]
 pub struct MonitorConfig {
-    // _Not used_
-    // pub credentials: Option<Credentials>,
+    /// Web-app user login, see [Credentials]. _Not used_
+    #[serde(default, skip_serializing)]
+    pub credentials: Ignored,
     /// Configuration settings.
     pub setup: Setup,
     /// Panels: 1-based index into `panels`
@@ -126,32 +165,181 @@ pub struct MonitorConfig {
     /// Internal sensor filter
     #[serde(skip)]
     pub sensor_filter: Option<Vec<Regex>>,
+    /// Rotate through all panels if there are no valid active panels.
+    #[serde(skip)]
+    fallback_all_panels: bool,
 }
 
 impl MonitorConfig {
+    /// Get the next active panel in the `active_panels` rotation.
+    ///
+    /// Invalid entries in `active_panels` (zero or out of range) are skipped, duplicate entries are
+    /// shown multiple times in the rotation. The rotation wraps around after the last active panel.
+    ///
+    /// If there are no valid active panels and [Self::set_fallback_all_panels] is enabled, all panels
+    /// are shown in order.
+    ///
+    /// returns: the next active panel, or None if there are no valid active panels.
     pub fn get_next_active_panel(&mut self) -> Option<&Panel> {
+        let index = self.next_active_panel_index()?;
+        Some(&self.panels[index])
+    }
+
+    /// Advance the active panel rotation like [Self::get_next_active_panel] and return the index of
+    /// the next active panel in `panels`.
+    ///
+    /// returns: the 0-based panel index, or None if there are no valid active panels.
+    pub fn next_active_panel_index(&mut self) -> Option<usize> {
+        let panel_count = self.panels.len() as u32;
+        let mut active_panels: Vec<usize> = self
+            .active_panels
+            .iter()
+            .filter(|&&active| active > 0)
+            .filter(|&&active| {
+                if active > panel_count {
+                    warn!("Ignoring invalid active panel {active}");
+                    return false;
+                }
+                true
+            })
+            .map(|&active| active as usize)
+            .collect();
+        if active_panels.is_empty() && self.fallback_all_panels {
+            active_panels = (1..=self.panels.len()).collect();
+        }
+
+        if active_panels.is_empty() {
+            return None;
+        }
+
         let mut active_panel_idx = self.active_panel_idx.unwrap_or(0) + 1;
-        if active_panel_idx > self.panels.len() {
+        if active_panel_idx > active_panels.len() {
             active_panel_idx = 1;
         }
+        self.active_panel_idx = Some(active_panel_idx);
 
-        for (index, active) in self
-            .active_panels
+        Some(active_panels[active_panel_idx - 1] - 1)
+    }
+
+    /// Create a summary of the configured panels, their sensors and referenced asset files.
+    ///
+    /// The summary can be serialized to JSON for tooling.
+    pub fn summary(&self) -> ConfigSummary {
+        let panel_count = self.panels.len() as u32;
+        let panels = self
+            .panels
             .iter()
-            .filter(|&active| *active > 0)
             .enumerate()
-        {
-            if *active > self.panels.len() as u32 {
-                warn!("Ignoring invalid active panel {active}");
-                continue;
-            }
-            if index + 1 == active_panel_idx {
-                self.active_panel_idx = Some(active_panel_idx);
-                return Some(&self.panels[*active as usize - 1]);
-            }
+            .map(|(idx, panel)| PanelSummary {
+                name: panel.friendly_name(),
+                active: self.active_panels.contains(&(idx as u32 + 1)),
+                sensors: panel.sensor.len(),
+                tables: panel.tables.len(),
+            })
+            .collect();
+
+        let mut assets: Vec<String> = self
+            .panels
+            .iter()
+            .flat_map(|panel| {
+                let sensor_assets = panel.sensor.iter().flat_map(|sensor| {
+                    [
+                        sensor.pic.as_deref(),
+                        sensor
+                            .text_fill
+                            .as_ref()
+                            .and_then(|fill| fill.image.as_deref()),
+                    ]
+                });
+                let background_assets = panel
+                    .time_backgrounds
+                    .iter()
+                    .map(|background| Some(background.img.as_str()));
+                [panel.img.as_deref()]
+                    .into_iter()
+                    .chain(background_assets)
+                    .chain(sensor_assets)
+            })
+            .flatten()
+            .filter(|asset| !asset.is_empty())
+            .map(String::from)
+            .collect();
+        assets.sort();
+        assets.dedup();
+
+        let mut fonts: Vec<String> = self
+            .panels
+            .iter()
+            .flat_map(|panel| {
+                let sensor_fonts = panel.sensor.iter().map(|sensor| &sensor.font_family);
+                let table_fonts = panel.tables.iter().map(|table| &table.font_family);
+                [&panel.font_family]
+                    .into_iter()
+                    .chain(sensor_fonts)
+                    .chain(table_fonts)
+            })
+            .flatten()
+            .map(|font_family| font_name(font_family))
+            .filter(|font| !font.is_empty())
+            .map(String::from)
+            .collect();
+        fonts.sort();
+        fonts.dedup();
+
+        ConfigSummary {
+            active_panels: self
+                .active_panels
+                .iter()
+                .copied()
+                .filter(|&active| active > 0 && active <= panel_count)
+                .collect(),
+            panels,
+            assets,
+            fonts,
         }
+    }
+
+    /// Rotate through all defined panels in order if `active_panels` is empty or only contains
+    /// invalid entries.
+    pub fn set_fallback_all_panels(&mut self, enable: bool) {
+        self.fallback_all_panels = enable;
+    }
 
-        None
+    /// Find a panel by its name or id, or by its 1-based index in the panel list.
+    pub fn find_panel(&self, name: &str) -> Option<&Panel> {
+        self.find_panel_index(name)
+            .and_then(|index| self.panels.get(index))
+    }
+
+    /// Find the 0-based index of a panel by its name or id, or by its 1-based index in the panel
+    /// list.
+    fn find_panel_index(&self, name: &str) -> Option<usize> {
+        self.panels
+            .iter()
+            .position(|panel| {
+                panel.name.as_deref() == Some(name)
+                    || panel.id.as_deref() == Some(name)
+                    || panel.friendly_name() == name
+            })
+            .or_else(|| match name.parse::<usize>() {
+                Ok(index) if index > 0 && index <= self.panels.len() => Some(index - 1),
+                _ => None,
+            })
+    }
+
+    /// Show only the given panel instead of the configured active panels.
+    ///
+    /// The panel is found by its name or id, or by its 1-based index, see [find_panel].
+    ///
+    /// returns: false if the panel doesn't exist, the active panels are not changed.
+    pub fn set_single_panel(&mut self, name: &str) -> bool {
+        let Some(index) = self.find_panel_index(name) else {
+            return false;
+        };
+
+        self.active_panels = vec![index as u32 + 1];
+        self.active_panel_idx = None;
+        true
     }
 
     /// Adds a custom panel to the application and maps sensor labels if applicable.
@@ -172,9 +360,11 @@ impl MonitorConfig {
     /// Apply a sensor label mapping on the included panels.
     ///
     /// The mapping will also be applied on any custom panel added in the future with [include_custom_panel].
+    /// The original sensor labels are preserved, the mapped label is used as data source identifier
+    /// for the sensor value, unit and internal date time lookups. See [Sensor::source_label].
     ///
     /// **Attention**: this method may only be called once at startup.
-    /// Dynamically changing mappings are not supported, and the original sensor labels are not preserved.
+    /// Dynamically changing mappings are not supported.
     pub fn set_sensor_mapping(&mut self, mapping: HashMap<String, String>) {
         for panel in self.panels.iter_mut() {
             panel.map_sensor_labels(&mapping);
@@ -196,42 +386,108 @@ pub struct Credentials {
 ///
 /// Note: Trimmed down object to include only required fields for `asterctl`.
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", schemars(transform = list_ignored_fields))]
 pub struct Setup {
     /// Switch time between panels in seconds, interpreted as float and converted to milliseconds. Default: 5
-    pub switch_time: Option<String>, // existed as "30" string
+    ///
+    /// Accepts a number or a numeric string, since it existed as `"30"` string.
+    #[serde(default, deserialize_with = "option_seconds")]
+    #[cfg_attr(feature = "schema", schemars(schema_with = "option_seconds_schema"))]
+    pub switch_time: Option<f32>,
     /// Panel redraw interval in seconds. Default: 1
+    ///
+    /// Accepts a number or a numeric string.
+    #[serde(default = "default_refresh", deserialize_with = "refresh_seconds")]
+    #[cfg_attr(feature = "schema", schemars(schema_with = "seconds_schema"))]
     pub refresh: f32,
-    /*
+    /// Decimal separator for rendering sensor values. Default: `.`
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub decimal_separator: Option<char>,
+    /// Optional thousands grouping separator for rendering sensor values.
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub grouping_separator: Option<char>,
+    /// Rounding mode of the decimal places: `half_up`, `half_even` or `trunc`. Default: half_up
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub rounding: Rounding,
+    /// Default number of integer places for text sensors without an `integerDigits` setting.
+    ///
+    /// Used for sensors with a missing or `-1` setting. Not part of the AOOSTAR-X configuration.
+    #[serde(default, deserialize_with = "option_none_if_minus_one")]
+    pub default_integer_digits: Option<i32>,
+    /// Default number of decimal places for text sensors without a `decimalDigits` setting.
+    ///
+    /// Used for sensors with a missing or `-1` setting, a sensor setting of `0` always shows no
+    /// decimal places. Not part of the AOOSTAR-X configuration.
+    #[serde(default, deserialize_with = "option_none_if_minus_one")]
+    pub default_decimal_digits: Option<i32>,
     // The following fields of the AOOSTAR-X json configuration file are NOT used in `asterctl`
     /// Default: true
-    pub off_display: bool,
+    #[serde(default, skip_serializing)]
+    pub off_display: Ignored,
     /// Selection of default panels based on theme / control_params / control_disk_temp ?
-    pub theme: i32,
+    #[serde(default, skip_serializing)]
+    pub theme: Ignored,
     /// ? Default: true
-    pub control_params: bool,
+    #[serde(default, skip_serializing)]
+    pub control_params: Ignored,
     /// ? Default: true
-    pub control_disk_temp: bool,
+    #[serde(default, skip_serializing)]
+    pub control_disk_temp: Ignored,
     /// Default: false
-    pub custom_panel: bool,
-    /// Language index. Default: 0
-    pub language: Language,
-    /// Operation mode: performance, power saving, etc.
-    pub operation_mode: Option<OperationMode>,
+    #[serde(default, skip_serializing)]
+    pub custom_panel: Ignored,
+    /// Language index, see [Language]. Default: 0
+    #[serde(default, skip_serializing)]
+    pub language: Ignored,
+    /// Operation mode: performance, power saving, etc. See [OperationMode].
+    #[serde(default, skip_serializing)]
+    pub operation_mode: Ignored,
     /// Operation type 1 or 2 (?). Default: 1
-    #[serde(rename = "type")]
-    pub operation_type: Option<i16>,
+    #[serde(default, skip_serializing, rename = "type")]
+    pub operation_type: Ignored,
     /// Default: 300
-    pub disk_update: i32,
+    #[serde(default, skip_serializing)]
+    pub disk_update: Ignored,
     /// Home Assistant URL
-    #[serde(deserialize_with = "empty_string_as_none")]
-    #[serde(rename = "ha_url")]
-    pub ha_url: Option<String>, // "" in JSON ⇒ Option<String>
+    #[serde(default, skip_serializing, rename = "ha_url")]
+    pub ha_url: Ignored,
     /// Home Assistant long-lived access token
-    #[serde(deserialize_with = "empty_string_as_none")]
-    #[serde(rename = "ha_token")]
-    pub ha_token: Option<String>, // "" in JSON ⇒ Option<String>
-    */
+    #[serde(default, skip_serializing, rename = "ha_token")]
+    pub ha_token: Ignored,
+}
+
+fn default_refresh() -> f32 {
+    1.0
+}
+
+impl Setup {
+    /// Get the configured switch time between panels. Default: 5 seconds
+    pub fn switch_time(&self) -> Duration {
+        Duration::from_secs_f32(self.switch_time.unwrap_or(5.0))
+    }
+
+    /// Get the configured panel redraw interval.
+    pub fn refresh(&self) -> Duration {
+        Duration::from_secs_f32(self.refresh)
+    }
+
+    /// Get the configured number format for rendering sensor values.
+    pub fn number_format(&self) -> NumberFormat {
+        let default = NumberFormat::default();
+        NumberFormat {
+            decimal_separator: self.decimal_separator.unwrap_or(default.decimal_separator),
+            grouping_separator: self.grouping_separator,
+            rounding: self.rounding,
+        }
+    }
 }
 
 /// Language setting.
@@ -261,6 +517,7 @@ pub enum OperationMode {
 }
 
 #[derive(Debug, Copy, Clone, Serialize_repr, Deserialize_repr, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema_repr))]
 #[repr(u8)]
 pub enum SensorDirection {
     /// Also used for clockwise in circular/arc progress & rotating pointer/dial indicator
@@ -271,28 +528,107 @@ pub enum SensorDirection {
     BottomToTop = 4,
 }
 
+/// Summary of a monitor configuration, see [MonitorConfig::summary].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigSummary {
+    /// Valid 1-based indexes of the active panels.
+    pub active_panels: Vec<u32>,
+    /// Summary of all panels in configuration order.
+    pub panels: Vec<PanelSummary>,
+    /// Sorted list of referenced image files, without duplicates.
+    pub assets: Vec<String>,
+    /// Sorted list of referenced font names without file extension, without duplicates.
+    pub fonts: Vec<String>,
+}
+
+/// Summary of a single panel in a [ConfigSummary].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PanelSummary {
+    /// Friendly panel name.
+    pub name: String,
+    /// Panel is included in the active panels.
+    pub active: bool,
+    /// Number of sensors.
+    pub sensors: usize,
+    /// Number of tables.
+    pub tables: usize,
+}
+
 /// Custom DIY panel definition
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[cfg_attr(feature = "schema", schemars(transform = list_ignored_fields))]
 pub struct Panel {
     /// Custom panel id
     pub id: Option<String>,
     /// Custom panel name
     pub name: Option<String>,
-    /*
     // The following fields of the AOOSTAR-X json configuration file are NOT used in `asterctl`
     /// TODO
-    pub checked: Option<bool>,
+    #[serde(default, skip_serializing)]
+    pub checked: Ignored,
     /// TODO panel type: 5 = built-in? 6 = custom ?
-    #[serde(rename = "type")]
-    pub panel_type: i32,
-     */
+    #[serde(default, skip_serializing, rename = "type")]
+    pub panel_type: Ignored,
     /// Background image filename
     pub img: Option<String>,
+    /// Optional default font name of all text sensors and tables of the panel without their own
+    /// font family.
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(
+        default,
+        rename = "fontFamily",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub font_family: Option<String>,
     /// Sensors
     pub sensor: Vec<Sensor>,
+    /// Optional list of regular expressions to hide matching sensors on this panel.
+    ///
+    /// The sensor label and the mapped data source identifier are matched. Invalid expressions are
+    /// skipped. Not part of the AOOSTAR-X configuration.
+    #[serde(
+        default,
+        rename = "sensorFilter",
+        skip_serializing_if = "Vec::is_empty",
+        serialize_with = "serialize_regex_list",
+        deserialize_with = "deserialize_regex_list"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "Vec<String>"))]
+    pub sensor_filter: Vec<Regex>,
+    /// Optional tables of sensor values in aligned columns and rows.
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tables: Vec<Table>,
+    /// Optional background images for daily time ranges, e.g. a day and a night background.
+    ///
+    /// The first range containing the current local time replaces the `img` background. Not part
+    /// of the AOOSTAR-X configuration.
+    #[serde(
+        default,
+        rename = "timeBackgrounds",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub time_backgrounds: Vec<TimeBackground>,
 }
 
 impl Panel {
+    /// Get the background image filename for the given local time.
+    ///
+    /// returns: the image of the first time background range containing the time, or the `img`
+    /// background if no range matches.
+    pub fn background_at(&self, time: NaiveTime) -> Option<&str> {
+        self.time_backgrounds
+            .iter()
+            .find(|background| background.contains(time))
+            .map(|background| background.img.as_str())
+            .or(self.img.as_deref())
+    }
+
     pub fn friendly_name(&self) -> String {
         self.name
             .clone()
@@ -310,18 +646,142 @@ impl Panel {
             .unwrap_or_else(|| "panel".into())
     }
 
-    fn map_sensor_labels(&mut self, mapping: &HashMap<String, String>) {
+    /// Check if a sensor is hidden on this panel by the panel sensor filter.
+    pub fn is_sensor_filtered(&self, sensor: &Sensor) -> bool {
+        self.sensor_filter
+            .iter()
+            .any(|re| re.is_match(&sensor.label) || re.is_match(sensor.source_label()))
+    }
+
+    pub(crate) fn map_sensor_labels(&mut self, mapping: &HashMap<String, String>) {
         for sensor in self.sensor.iter_mut() {
-            if let Some(new_label) = mapping.get(&sensor.label) {
-                sensor.label = new_label.clone();
+            sensor.source = mapping.get(&sensor.label).cloned();
+        }
+    }
+}
+
+/// Panel background image for a daily time range.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct TimeBackground {
+    /// Start of the time range in `HH:MM` notation, inclusive.
+    #[serde(
+        serialize_with = "serialize_hh_mm",
+        deserialize_with = "deserialize_hh_mm"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub from: NaiveTime,
+    /// End of the time range in `HH:MM` notation, exclusive. A range ending before its start
+    /// spans midnight, e.g. `22:00` to `06:00`.
+    #[serde(
+        serialize_with = "serialize_hh_mm",
+        deserialize_with = "deserialize_hh_mm"
+    )]
+    #[cfg_attr(feature = "schema", schemars(with = "String"))]
+    pub to: NaiveTime,
+    /// Background image filename
+    pub img: String,
+}
+
+impl TimeBackground {
+    /// Check if the time is within the time range.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.from <= self.to {
+            self.from <= time && time < self.to
+        } else {
+            time >= self.from || time < self.to
+        }
+    }
+}
+
+/// Table of text sensor values in aligned columns and rows.
+///
+/// Each cell is rendered like a text sensor in the `width` of its column and the `row_height`.
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct Table {
+    /// Rounded x-position of the top left table corner.
+    #[serde(deserialize_with = "f32_as_rounded_i32")]
+    #[cfg_attr(feature = "schema", schemars(with = "f32"))]
+    pub x: i32,
+    /// Rounded y-position of the top left table corner.
+    #[serde(deserialize_with = "f32_as_rounded_i32")]
+    #[cfg_attr(feature = "schema", schemars(with = "f32"))]
+    pub y: i32,
+    /// Height of a table row in pixels.
+    pub row_height: u32,
+    /// Table columns from left to right.
+    pub columns: Vec<TableColumn>,
+    /// Table rows with the sensor label of each column cell. An empty label is an empty cell,
+    /// labels exceeding the number of columns are ignored.
+    pub rows: Vec<Vec<String>>,
+    /// Font name matching font filename without file extension.
+    pub font_family: Option<String>,
+    /// Font size of all cells.
+    pub font_size: Option<i32>,
+    /// Font color of all cells in `#RRGGBB` notation.
+    pub font_color: Option<FontColor>,
+    /// Number of integer places for the cell values.
+    #[serde(default, deserialize_with = "option_none_if_minus_one")]
+    pub integer_digits: Option<i32>,
+    /// Number of decimal places for the cell values.
+    #[serde(default, deserialize_with = "option_none_if_minus_one")]
+    pub decimal_digits: Option<i32>,
+}
+
+impl Table {
+    /// Get the text sensors of all table cells, positioned in their column and row.
+    ///
+    /// The sensor identifier mapping is not applied to the cell labels.
+    pub fn cell_sensors(&self) -> Vec<Sensor> {
+        let mut sensors = Vec::new();
+        for (row_idx, row) in self.rows.iter().enumerate() {
+            let y = self.y + row_idx as i32 * self.row_height as i32;
+            let mut x = self.x;
+            for (column, label) in self.columns.iter().zip(row) {
+                if !label.is_empty() {
+                    sensors.push(Sensor {
+                        mode: SensorMode::Text,
+                        label: label.clone(),
+                        x,
+                        y,
+                        width: Some(column.width),
+                        height: Some(self.row_height),
+                        font_family: self.font_family.clone(),
+                        font_size: self.font_size,
+                        font_color: self.font_color,
+                        text_align: Some(column.text_align),
+                        integer_digits: self.integer_digits,
+                        decimal_digits: self.decimal_digits,
+                        ..Default::default()
+                    });
+                }
+                x += column.width as i32;
             }
         }
+
+        sensors
     }
 }
 
-/// One Data Display Unit
+/// Column of a [Table].
 #[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TableColumn {
+    /// Column width in pixels.
+    pub width: u32,
+    /// Text alignment of the cells within the column width. Default: left
+    #[serde(default)]
+    pub text_align: TextAlign,
+}
+
+/// One Data Display Unit
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "camelCase")]
+#[cfg_attr(feature = "schema", schemars(transform = list_ignored_fields))]
 pub struct Sensor {
     /// Sensor mode: text, fan, progress, pointer
     pub mode: SensorMode,
@@ -340,12 +800,28 @@ pub struct Sensor {
     pub name: Option<String>,
     /// Label name for custom panels.
     pub item_name: Option<String>,
-    /// Label identifier, also used as data source identifier.
+    /// Label identifier, also used as data source identifier if not mapped.
     pub label: String,
-    /// Sensor value. Ignored: value is used from a sensor source
+    /// Mapped data source identifier from the sensor mapping.
+    #[serde(skip)]
+    source: Option<String>,
+    /// Sensor value. Only used as fallback if enabled, the value is used from a sensor source.
     #[serde(deserialize_with = "empty_string_as_none")]
+    #[cfg_attr(feature = "schema", schemars(required))]
     pub value: Option<String>, // "" or numbers, so Option<String>
 
+    /// Parsing of the sensor value for fan, progress and pointer indicators. Default: number
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub value_parser: ValueParser,
+
+    /// Optional normalization of the sensor value before formatting and parsing.
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub normalize: Option<ValueNormalization>,
+
     /// Image for progress, fan and pointer indicators
     pub min_value: Option<f32>,
     /// Image for progress, fan and pointer indicators
@@ -353,12 +829,59 @@ pub struct Sensor {
 
     /// Optional unit text to print after the value
     #[serde(deserialize_with = "empty_string_as_none")]
+    #[cfg_attr(feature = "schema", schemars(required))]
     pub unit: Option<String>,
+    /// Print the unit text before or after the value.
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub unit_position: UnitPosition,
+    /// Number notation of text sensor values: `plain`, `si` or `scientific`. Default: plain
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub notation: Notation,
+    /// Optional rounding mode of text sensor values: `half_up`, `half_even` or `trunc`.
+    ///
+    /// Overrides the `rounding` setup setting. Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub rounding: Option<Rounding>,
+    /// Optional text template with `{label}` placeholders for text sensors.
+    ///
+    /// The placeholders are replaced with the referenced sensor values, the single sensor value of
+    /// `label` is not used. Not part of the AOOSTAR-X configuration.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub template: Option<String>,
+    /// Optional display texts of raw text sensor values, e.g. `up` → `Online`.
+    ///
+    /// Takes precedence over the global value aliases. Not part of the AOOSTAR-X configuration.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub value_map: HashMap<String, String>,
+    /// Optional control sensor label to show this sensor only if the control sensor value equals
+    /// `visible_when_value`, e.g. a `detail_mode` sensor to toggle a group of sensors.
+    ///
+    /// The sensor is always visible without a control label. Not part of the AOOSTAR-X configuration.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub visible_when_label: Option<String>,
+    /// Control sensor value to show this sensor, see `visible_when_label`. Default: empty value.
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub visible_when_value: Option<String>,
+    /// Optional update interval of the sensor value in seconds, e.g. for slowly changing values on
+    /// a fast refreshing panel.
+    ///
+    /// The last resolved value is rendered until the interval elapsed. Without an interval, the
+    /// value is updated with every panel refresh. Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub update_interval: Option<f32>,
     /// Rounded x-position. Custom panel coordinates are stored as float!
     #[serde(deserialize_with = "f32_as_rounded_i32")]
+    #[cfg_attr(feature = "schema", schemars(with = "f32"))]
     pub x: i32,
     /// Rounded y-position. Custom panel coordinates are stored as float!
     #[serde(deserialize_with = "f32_as_rounded_i32")]
+    #[cfg_attr(feature = "schema", schemars(with = "f32"))]
     pub y: i32,
     /// Used for pointer type
     pub width: Option<u32>,
@@ -376,19 +899,127 @@ pub struct Sensor {
     /// _Not (yet) used_
     pub font_weight: Option<FontWeight>,
     pub text_align: Option<TextAlign>,
+    /// Vertical anchor of the text sensor `y` position. Default: AOOSTAR-X compatible centering.
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub text_anchor: TextAnchor,
+    /// Reorder right-to-left text runs of a text sensor, e.g. Arabic or Hebrew, for rendering.
+    ///
+    /// Left-to-right text is unchanged. Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub rtl: bool,
+    /// Clockwise rotation of a text sensor in degrees. Default: 0
+    ///
+    /// The text is aligned before it is rotated around its center. Not part of the AOOSTAR-X
+    /// configuration.
+    #[serde(default)]
+    pub rotation: i32,
+    /// Optional font colors of a text sensor for value thresholds.
+    ///
+    /// The color of the highest threshold reached by the sensor value is used instead of the
+    /// `font_color`. Not part of the AOOSTAR-X configuration.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub color_thresholds: Vec<ColorThreshold>,
+    /// Hysteresis of the `color_thresholds`: a threshold color is used once the value reaches the
+    /// threshold plus this margin, and dropped once the value falls below the threshold minus
+    /// this margin. Default: 0
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub color_hysteresis: f32,
+    /// Optional font color of the decimal places of a text sensor value.
+    ///
+    /// Uses the font color of the integer part if not set. Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub decimal_color: Option<FontColor>,
+    /// Optional font color of the unit of a text sensor.
+    ///
+    /// Uses the font color of the integer part if not set. Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub unit_color: Option<FontColor>,
+    /// Optional gradient or texture image fill of a text sensor instead of the flat font colors.
+    ///
+    /// Replaces the font, decimal and unit colors. Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub text_fill: Option<TextFill>,
 
     /// Number of integer places for the sensor value.
+    ///
+    /// `0` only shows the decimal places. `-1` or a missing field is unset (`None`) and uses the
+    /// `defaultIntegerDigits` setup setting, or all integer places without a setup default.
     // -1 ≈ unset ⇒ Option<i32>
-    #[serde(deserialize_with = "option_none_if_minus_one")]
+    #[serde(default, deserialize_with = "option_none_if_minus_one")]
     pub integer_digits: Option<i32>,
     /// Number of decimal places for the sensor value.
+    ///
+    /// `0` shows no decimal places, independent of the setup default. `-1` or a missing field is
+    /// unset (`None`) and uses the `defaultDecimalDigits` setup setting, or no decimal places
+    /// without a setup default.
     // -1 ≈ unset ⇒ Option<i32>
-    #[serde(deserialize_with = "option_none_if_minus_one")]
+    #[serde(default, deserialize_with = "option_none_if_minus_one")]
     pub decimal_digits: Option<i32>,
     /// Image for progress, fan and pointer indicators
     #[serde(deserialize_with = "empty_string_as_none")]
+    #[cfg_attr(feature = "schema", schemars(required))]
     pub pic: Option<String>,
 
+    /// Optional tint color for the `pic` image of fan, progress and pointer sensors, in `#RRGGBB` notation.
+    ///
+    /// The RGB channels of the image are multiplied with the tint color, intended for recoloring
+    /// white graphics. Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub tint: Option<FontColor>,
+
+    /// Optional color of fan, progress and pointer sensors if the value is at or below `min_value`,
+    /// in `#RRGGBB` notation.
+    ///
+    /// Replaces the `tint` color, and the `gradient` color of progress sensors. Not part of the
+    /// AOOSTAR-X configuration.
+    #[serde(default)]
+    pub at_min_color: Option<FontColor>,
+
+    /// Optional color of fan, progress and pointer sensors if the value is at or above `max_value`,
+    /// in `#RRGGBB` notation, e.g. to highlight a saturated value.
+    ///
+    /// Replaces the `tint` color, and the `gradient` color of progress sensors. Not part of the
+    /// AOOSTAR-X configuration.
+    #[serde(default)]
+    pub at_max_color: Option<FontColor>,
+
+    /// Blend mode for compositing fan, progress and pointer sensors onto the panel background.
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub blend: BlendMode,
+
+    /// Fill origin of progress sensors, used together with the `direction` axis.
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub fill_origin: FillOrigin,
+
+    /// Minimum visible progress fill in pixels of progress sensors, even at the minimum value.
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub min_visible: u32,
+
+    /// Feather width in pixels of progress sensors to ramp up the alpha channel at the progress
+    /// fill edge instead of a hard cut. Default: 0 = hard edge
+    ///
+    /// Not part of the AOOSTAR-X configuration.
+    #[serde(default)]
+    pub feather: u32,
+
+    /// Optional color gradient of progress sensors to fill the visible progress with the
+    /// interpolated color of the current value.
+    ///
+    /// The RGB colors of the `pic` image are replaced, or a `width` x `height` rectangle is filled
+    /// if no `pic` is set. Not part of the AOOSTAR-X configuration.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gradient: Vec<ColorStop>,
+
     /// Used for fan & pointer sensors
     pub min_angle: Option<i32>,
     /// Used for fan & pointer sensors
@@ -400,24 +1031,52 @@ pub struct Sensor {
     /// Pivot y
     #[serde(rename = "xz_y")]
     pub xz_y: Option<i32>,
-    /*
     // The following fields of the AOOSTAR-X json configuration file are NOT used in `asterctl`
-    /// _Not (yet) used_
-    pub text_direction: i32, // layout direction
+    /// _Not (yet) used_: layout direction
+    #[serde(default, skip_serializing)]
+    pub text_direction: Ignored,
     /// For type = 6
-    pub url: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub url: Ignored,
     /// For type = 6
-    pub data: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub data: Ignored,
     /// For type = 6
-    pub interval: Option<u32>,
-     */
+    #[serde(default, skip_serializing)]
+    pub interval: Ignored,
+}
+
+impl Sensor {
+    /// Check if the sensor is visible with the current value of its `visible_when_label` control
+    /// sensor.
+    ///
+    /// returns: true if no control sensor is set, or if the trimmed control sensor value equals
+    /// `visible_when_value`. A missing control sensor value never matches.
+    pub fn is_visible(&self, values: &HashMap<String, String>) -> bool {
+        let Some(control) = &self.visible_when_label else {
+            return true;
+        };
+        let expected = self.visible_when_value.as_deref().unwrap_or_default();
+        values
+            .get(control)
+            .is_some_and(|value| value.trim() == expected.trim())
+    }
+
+    /// Data source identifier to retrieve the sensor value and unit.
+    ///
+    /// This is the mapped label if a sensor mapping applies to the sensor label, otherwise the label itself.
+    pub fn source_label(&self) -> &str {
+        self.source.as_deref().unwrap_or(&self.label)
+    }
 }
 
 /// Sensor element type. Name is based on AOOSTAR-X web configuration
-#[derive(Debug, Clone, Copy, Serialize_repr, Deserialize_repr, Eq, Hash, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Serialize_repr, Deserialize_repr, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema_repr))]
 #[repr(u8)]
 pub enum SensorMode {
     /// Text element
+    #[default]
     Text = 1,
     /// Circular/arc progress indicator
     Fan = 2,
@@ -425,6 +1084,9 @@ pub enum SensorMode {
     Progress = 3,
     /// Rotating pointer/dial indicator
     Pointer = 4,
+    /// QR code of the sensor value, e.g. a URL. Requires the `qrcode` feature.
+    /// Not part of the AOOSTAR-X configuration.
+    QrCode = 5,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -471,7 +1133,94 @@ pub enum TimeDateLabel {
     HM3,
 }
 
+/// Numeric sensor value parsing for indicator sensors.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum ValueParser {
+    /// Plain number, optionally with a trailing `%` sign. See [parse_number].
+    #[default]
+    Number,
+    /// Leading number of a value with an embedded unit, e.g. `1.23 MB/s`. See [parse_leading_number].
+    Leading,
+    /// Human-readable byte value normalized to bytes, e.g. `1.23 MB/s`. See [parse_bytes].
+    Bytes,
+}
+
+impl ValueParser {
+    /// Parse a numeric sensor value.
+    ///
+    /// returns: the parsed number, or None if the value is not numeric.
+    pub fn parse(&self, value: &str) -> Option<f64> {
+        match self {
+            ValueParser::Number => parse_number(value),
+            ValueParser::Leading => parse_leading_number(value),
+            ValueParser::Bytes => parse_bytes(value),
+        }
+    }
+}
+
+/// Normalization of a raw sensor value, e.g. of a sensor source with quoted values or a locale
+/// specific decimal comma.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ValueNormalization {
+    /// Characters to strip from the start and end of the value, e.g. quotes or unit characters.
+    /// Surrounding whitespace is always removed.
+    #[serde(default)]
+    pub strip: String,
+    /// Replace a `,` decimal comma with a `.` decimal point.
+    #[serde(default)]
+    pub decimal_comma: bool,
+}
+
+impl ValueNormalization {
+    /// Normalize a sensor value.
+    ///
+    /// returns: the value without surrounding whitespace and `strip` characters, and a replaced
+    /// decimal comma if enabled.
+    pub fn apply(&self, value: &str) -> String {
+        let value = value.trim_matches(|c: char| c.is_whitespace() || self.strip.contains(c));
+
+        if self.decimal_comma {
+            value.replace(',', ".")
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+/// Fill origin of a progress sensor.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum FillOrigin {
+    /// The progress fills from the start of the sensor direction.
+    #[default]
+    Start,
+    /// The progress fills from the center outwards to both ends of the direction axis.
+    Center,
+    /// The progress fills from both ends of the direction axis inwards to the center.
+    Edges,
+}
+
+/// Compositing blend mode of indicator sensors.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum BlendMode {
+    /// Regular alpha blending: the sensor image is drawn over the background.
+    #[default]
+    Normal,
+    /// Additive blending: the sensor image brightens the background, e.g. for glow effects.
+    Add,
+    /// Multiply blending: the sensor image darkens the background, e.g. for shadows.
+    Multiply,
+}
+
 #[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum FontWeight {
     #[default]
@@ -480,6 +1229,7 @@ pub enum FontWeight {
 }
 
 #[derive(Debug, Copy, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum TextAlign {
     #[default]
@@ -488,6 +1238,23 @@ pub enum TextAlign {
     Right,
 }
 
+/// Vertical anchor of the text sensor `y` position.
+#[derive(Debug, Copy, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum TextAnchor {
+    /// The glyphs are vertically centered in the sensor `height` below `y`, like in AOOSTAR-X.
+    ///
+    /// The font size is based on an approximation of the AOOSTAR-X font scaling, and can be off by
+    /// a few pixels for some fonts.
+    #[default]
+    Center,
+    /// `y` is the top of the first text line, including the font ascent above the glyphs.
+    TopLeft,
+    /// `y` is the baseline of the first text line.
+    Baseline,
+}
+
 fn option_none_if_minus_one<'de, D>(deserializer: D) -> Result<Option<i32>, D::Error>
 where
     D: Deserializer<'de>,
@@ -498,6 +1265,44 @@ where
     }
 }
 
+/// Color of a progress gradient at the sensor `value`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ColorStop {
+    /// Sensor value of the color stop.
+    pub value: f32,
+    /// Color in `#RRGGBB` notation.
+    pub color: FontColor,
+}
+
+/// Fill of the text sensor glyphs with a color gradient or a texture image.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TextFill {
+    /// Texture image filename, tiled over the text starting at its top left corner. Takes
+    /// precedence over the `gradient`.
+    #[serde(default, deserialize_with = "empty_string_as_none")]
+    pub image: Option<String>,
+    /// Color stops of a linear gradient over the text. The stop `value` is the position in percent
+    /// of the text extent in the gradient `direction`: 0 = start, 100 = end.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gradient: Vec<ColorStop>,
+    /// Gradient direction. Default: left to right
+    #[serde(default)]
+    pub direction: Option<SensorDirection>,
+}
+
+/// Font color of a text sensor, used if the sensor value reaches the threshold `value`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+pub struct ColorThreshold {
+    /// Minimum sensor value for the color.
+    pub value: f32,
+    /// Font color in `#RRGGBB` notation.
+    pub color: FontColor,
+}
+
 /// Special font color type since it is represented either as numeric -1 or as a string :-(
 ///
 /// A good serde programming exercise...
@@ -562,6 +1367,23 @@ impl Serialize for FontColor {
     }
 }
 
+#[cfg(feature = "schema")]
+impl JsonSchema for FontColor {
+    fn schema_name() -> Cow<'static, str> {
+        "FontColor".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "Color in `#RRGGBB` notation, or -1 / empty string for the default white color.",
+            "anyOf": [
+                { "type": "string", "pattern": "^(#[0-9a-fA-F]{6}|-1|\\s*)$" },
+                { "const": -1 }
+            ]
+        })
+    }
+}
+
 impl<'de> Deserialize<'de> for FontColor {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -606,6 +1428,47 @@ impl<'de> Deserialize<'de> for FontColor {
     }
 }
 
+fn serialize_regex_list<S>(list: &[Regex], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.collect_seq(list.iter().map(Regex::as_str))
+}
+
+fn deserialize_regex_list<'de, D>(deserializer: D) -> Result<Vec<Regex>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let list = Option::<Vec<String>>::deserialize(deserializer)?.unwrap_or_default();
+    Ok(list
+        .into_iter()
+        .filter_map(|expr| match Regex::new(&expr) {
+            Ok(re) => Some(re),
+            Err(e) => {
+                warn!("Skipping invalid panel sensor filter: {expr}: {e}");
+                None
+            }
+        })
+        .collect())
+}
+
+fn serialize_hh_mm<S>(time: &NaiveTime, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    serializer.serialize_str(&time.format("%H:%M").to_string())
+}
+
+fn deserialize_hh_mm<'de, D>(deserializer: D) -> Result<NaiveTime, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|e| {
+        serde::de::Error::custom(format!("invalid time '{value}', expected HH:MM: {e}"))
+    })
+}
+
 fn empty_string_as_none<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
 where
     D: Deserializer<'de>,
@@ -614,6 +1477,130 @@ where
     Ok(option.and_then(|s| if s.trim().is_empty() { None } else { Some(s) }))
 }
 
+/// Known field of the AOOSTAR-X json configuration file which is not used in `asterctl`.
+///
+/// Any value is accepted and skipped when loading a configuration, the field is never serialized.
+/// The JSON schema lists these fields in the `x-ignored` extension of the containing object.
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+pub struct Ignored;
+
+impl<'de> Deserialize<'de> for Ignored {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        IgnoredAny::deserialize(deserializer)?;
+        Ok(Ignored)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl JsonSchema for Ignored {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        "Ignored".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({ "x-ignored": true })
+    }
+}
+
+/// Schema transform collecting all [Ignored] properties into the `x-ignored` list of the object.
+#[cfg(feature = "schema")]
+fn list_ignored_fields(schema: &mut Schema) {
+    let ignored: Vec<serde_json::Value> = schema
+        .get("properties")
+        .and_then(serde_json::Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(|(_, property)| property.get("x-ignored") == Some(&serde_json::Value::Bool(true)))
+        .map(|(name, _)| name.as_str().into())
+        .collect();
+    if !ignored.is_empty() {
+        schema.insert("x-ignored".into(), ignored.into());
+    }
+}
+
+/// Schema of a time value in seconds: a number or a numeric string.
+#[cfg(feature = "schema")]
+fn seconds_schema(_generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "number" },
+            { "type": "string", "pattern": "^\\s*([0-9]+\\.?[0-9]*)?\\s*$" }
+        ]
+    })
+}
+
+/// Schema of an optional time value in seconds: a number, a numeric string or null.
+#[cfg(feature = "schema")]
+fn option_seconds_schema(generator: &mut SchemaGenerator) -> Schema {
+    let mut schema = seconds_schema(generator);
+    if let Some(any_of) = schema
+        .get_mut("anyOf")
+        .and_then(serde_json::Value::as_array_mut)
+    {
+        any_of.push(serde_json::json!({ "type": "null" }));
+    }
+    schema
+}
+
+/// Time value in seconds, represented either as number or as string depending on the
+/// AOOSTAR-X version.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NumberOrString {
+    Number(f32),
+    String(String),
+    Other(serde::de::IgnoredAny),
+}
+
+/// Deserialize a time value in seconds from a number or a numeric string, e.g. `30` or `"30"`.
+///
+/// Unparseable, non-positive or values above one day are ignored with a warning.
+fn option_seconds<'de, D>(deserializer: D) -> Result<Option<f32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    const MAX_SECONDS: f32 = 86_400.0;
+
+    let seconds = match Option::<NumberOrString>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(NumberOrString::Number(value)) => Some(value),
+        Some(NumberOrString::String(value)) if value.trim().is_empty() => return Ok(None),
+        Some(NumberOrString::String(value)) => match value.trim().parse::<f32>() {
+            Ok(value) => Some(value),
+            Err(_) => {
+                warn!("Ignoring invalid time value in setup: {value:?}");
+                None
+            }
+        },
+        Some(NumberOrString::Other(_)) => {
+            warn!("Ignoring invalid time value in setup, expected a number or numeric string");
+            None
+        }
+    };
+
+    Ok(seconds.filter(|seconds| {
+        let valid = *seconds > 0.0 && *seconds <= MAX_SECONDS;
+        if !valid {
+            warn!("Ignoring time value in setup outside of range 0..{MAX_SECONDS}s: {seconds}");
+        }
+        valid
+    }))
+}
+
+fn refresh_seconds<'de, D>(deserializer: D) -> Result<f32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(option_seconds(deserializer)?.unwrap_or_else(default_refresh))
+}
+
 fn f32_as_rounded_i32<'de, D>(deserializer: D) -> Result<i32, D::Error>
 where
     D: Deserializer<'de>,
@@ -621,3 +1608,448 @@ where
     let rounded = f32::deserialize(deserializer).map(f32::round)?;
     Ok(rounded as i32)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    fn test_config(active_panels: &[u32], panel_count: usize) -> MonitorConfig {
+        let panels: Vec<String> = (1..=panel_count)
+            .map(|i| format!(r#"{{"name": "panel{i}", "sensor": []}}"#))
+            .collect();
+        serde_json::from_str(&format!(
+            r#"{{"setup": {{"refresh": 1}}, "mianban": {active_panels:?}, "diy": [{}]}}"#,
+            panels.join(",")
+        ))
+        .expect("invalid test config")
+    }
+
+    #[test]
+    fn summary_counts_panels_sensors_and_assets() {
+        let sensor = |mode: u8, label: &str, assets: &str| {
+            format!(
+                r#"{{"mode": {mode}, "label": "{label}", "value": "", "unit": "", "x": 0, "y": 0,
+                "integerDigits": -1, "decimalDigits": -1, {assets}}}"#
+            )
+        };
+        let cfg: MonitorConfig = serde_json::from_str(&format!(
+            r#"{{
+              "setup": {{"refresh": 1}},
+              "mianban": [2, 5],
+              "diy": [
+                {{"name": "cpu", "img": "cpu.png", "sensor": [{}, {}, {}]}},
+                {{"img": "net.png", "sensor": [{}], "tables": [{{"x": 0, "y": 0, "rowHeight": 20,
+                  "columns": [], "rows": [], "fontFamily": "Mono"}}]}}
+              ]
+            }}"#,
+            sensor(1, "cpu_temp", r#""fontFamily": "DejaVuSans", "pic": """#),
+            sensor(
+                1,
+                "cpu_freq",
+                r#""fontFamily": "DejaVuSans.ttf", "pic": """#
+            ),
+            sensor(3, "cpu_usage", r#""pic": "progress.png", "fontFamily": """#),
+            sensor(4, "net_up", r#""pic": "progress.png""#),
+        ))
+        .expect("invalid test config");
+
+        let summary = cfg.summary();
+
+        assert_eq!(summary.active_panels, vec![2]);
+        assert_eq!(
+            summary.panels,
+            vec![
+                PanelSummary {
+                    name: "cpu".into(),
+                    active: false,
+                    sensors: 3,
+                    tables: 0
+                },
+                PanelSummary {
+                    name: "net".into(),
+                    active: true,
+                    sensors: 1,
+                    tables: 1
+                },
+            ]
+        );
+        assert_eq!(summary.assets, vec!["cpu.png", "net.png", "progress.png"]);
+        assert_eq!(summary.fonts, vec!["DejaVuSans", "Mono"]);
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["activePanels"], serde_json::json!([2]));
+        assert_eq!(json["panels"][1]["sensors"], 1);
+    }
+
+    fn day_night_panel() -> Panel {
+        serde_json::from_str(
+            r#"{"img": "default.png", "sensor": [], "timeBackgrounds": [
+            {"from": "06:00", "to": "18:00", "img": "day.png"},
+            {"from": "22:00", "to": "02:00", "img": "night.png"}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case("06:00", Some("day.png"))]
+    #[case("12:30", Some("day.png"))]
+    #[case("17:59", Some("day.png"))]
+    #[case("18:00", Some("default.png"))]
+    #[case("23:15", Some("night.png"))]
+    #[case("00:00", Some("night.png"))]
+    #[case("01:59", Some("night.png"))]
+    #[case("02:00", Some("default.png"))]
+    fn time_background_selects_image_for_time(#[case] time: &str, #[case] expected: Option<&str>) {
+        let panel = day_night_panel();
+        let time = NaiveTime::parse_from_str(time, "%H:%M").unwrap();
+
+        assert_eq!(panel.background_at(time), expected);
+    }
+
+    #[test]
+    fn time_background_without_img_fallback() {
+        let mut panel = day_night_panel();
+        panel.img = None;
+        let time = NaiveTime::from_hms_opt(19, 0, 0).unwrap();
+
+        assert_eq!(panel.background_at(time), None);
+    }
+
+    #[test]
+    fn time_background_invalid_time_is_rejected() {
+        let result = serde_json::from_str::<Panel>(
+            r#"{"sensor": [], "timeBackgrounds": [{"from": "6am", "to": "18:00", "img": "a.png"}]}"#,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    #[case::zero(r#""integerDigits": 0, "decimalDigits": 0,"#, Some(0), Some(0))]
+    #[case::unset(r#""integerDigits": -1, "decimalDigits": -1,"#, None, None)]
+    #[case::absent("", None, None)]
+    #[case::fixed(r#""integerDigits": 3, "decimalDigits": 2,"#, Some(3), Some(2))]
+    fn sensor_digits_zero_unset_and_absent(
+        #[case] digits: &str,
+        #[case] integer_digits: Option<i32>,
+        #[case] decimal_digits: Option<i32>,
+    ) {
+        let sensor: Sensor = serde_json::from_str(&format!(
+            r#"{{"mode": 1, "label": "cpu", "value": "", "unit": "", "x": 0, "y": 0, {digits}
+            "pic": ""}}"#
+        ))
+        .unwrap();
+
+        assert_eq!(sensor.integer_digits, integer_digits);
+        assert_eq!(sensor.decimal_digits, decimal_digits);
+    }
+
+    fn test_setup(setup: &str) -> Setup {
+        serde_json::from_str(setup).expect("invalid test setup")
+    }
+
+    #[rstest]
+    #[case(r#"{"switchTime": "30", "refresh": "0.5"}"#, Some(30.0), 0.5)]
+    #[case(r#"{"switchTime": 12.5, "refresh": 2}"#, Some(12.5), 2.0)]
+    #[case(r#"{"switchTime": " 7 "}"#, Some(7.0), 1.0)]
+    #[case(r#"{"switchTime": "", "refresh": null}"#, None, 1.0)]
+    #[case(r#"{}"#, None, 1.0)]
+    fn setup_time_values_from_number_or_string(
+        #[case] setup: &str,
+        #[case] switch_time: Option<f32>,
+        #[case] refresh: f32,
+    ) {
+        let setup = test_setup(setup);
+
+        assert_eq!(setup.switch_time, switch_time);
+        assert_eq!(setup.refresh, refresh);
+    }
+
+    #[rstest]
+    #[case(r#"{"switchTime": "thirty", "refresh": "fast"}"#)]
+    #[case(r#"{"switchTime": 0, "refresh": -1}"#)]
+    #[case(r#"{"switchTime": -5, "refresh": "-0.5"}"#)]
+    #[case(r#"{"switchTime": true, "refresh": [1]}"#)]
+    #[case(r#"{"switchTime": "100000", "refresh": "NaN"}"#)]
+    fn setup_invalid_time_values_use_defaults(#[case] setup: &str) {
+        let setup = test_setup(setup);
+
+        assert_eq!(setup.switch_time(), Duration::from_secs(5));
+        assert_eq!(setup.refresh(), Duration::from_secs(1));
+    }
+
+    fn rotation(cfg: &mut MonitorConfig, count: usize) -> Vec<String> {
+        (0..count)
+            .map(|_| {
+                cfg.get_next_active_panel()
+                    .map(|panel| panel.friendly_name())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[case::all_panels(&[1, 2, 3], &["panel1", "panel2", "panel3", "panel1", "panel2"])]
+    #[case::reordered(&[3, 1], &["panel3", "panel1", "panel3", "panel1", "panel3"])]
+    #[case::single_panel(&[2], &["panel2", "panel2", "panel2", "panel2", "panel2"])]
+    #[case::zeros(&[0, 1, 0, 3, 0], &["panel1", "panel3", "panel1", "panel3", "panel1"])]
+    #[case::duplicates(&[1, 1, 2], &["panel1", "panel1", "panel2", "panel1", "panel1"])]
+    #[case::out_of_range(&[4, 2, 99, 3], &["panel2", "panel3", "panel2", "panel3", "panel2"])]
+    #[case::mixed(&[0, 5, 3, 3, 0], &["panel3", "panel3", "panel3", "panel3", "panel3"])]
+    fn get_next_active_panel_rotation(#[case] active_panels: &[u32], #[case] expected: &[&str]) {
+        let mut cfg = test_config(active_panels, 3);
+
+        assert_eq!(rotation(&mut cfg, expected.len()), expected);
+    }
+
+    #[rstest]
+    #[case::empty(&[])]
+    #[case::only_invalid(&[0, 4, 99])]
+    fn get_next_active_panel_without_valid_panels(#[case] active_panels: &[u32]) {
+        let mut cfg = test_config(active_panels, 3);
+
+        assert!(cfg.get_next_active_panel().is_none());
+        assert!(cfg.get_next_active_panel().is_none());
+    }
+
+    #[rstest]
+    #[case::empty(&[])]
+    #[case::only_invalid(&[0, 4, 99])]
+    fn get_next_active_panel_falls_back_to_all_panels(#[case] active_panels: &[u32]) {
+        let mut cfg = test_config(active_panels, 3);
+        cfg.set_fallback_all_panels(true);
+
+        assert_eq!(
+            rotation(&mut cfg, 5),
+            ["panel1", "panel2", "panel3", "panel1", "panel2"]
+        );
+    }
+
+    #[rstest]
+    #[case::name("panel3")]
+    #[case::index("3")]
+    fn set_single_panel_replaces_active_panels(#[case] name: &str) {
+        let mut cfg = test_config(&[1, 2], 3);
+        cfg.get_next_active_panel();
+
+        assert!(cfg.set_single_panel(name));
+        assert_eq!(rotation(&mut cfg, 3), ["panel3", "panel3", "panel3"]);
+    }
+
+    #[test]
+    fn set_single_panel_unknown_panel() {
+        let mut cfg = test_config(&[1, 2], 3);
+
+        assert!(!cfg.set_single_panel("panel4"));
+        assert!(!cfg.set_single_panel("4"));
+        assert_eq!(rotation(&mut cfg, 2), ["panel1", "panel2"]);
+    }
+
+    #[test]
+    fn get_next_active_panel_fallback_ignored_with_valid_panels() {
+        let mut cfg = test_config(&[2], 3);
+        cfg.set_fallback_all_panels(true);
+
+        assert_eq!(rotation(&mut cfg, 2), ["panel2", "panel2"]);
+    }
+
+    #[test]
+    fn panel_sensor_filter_matches_label_and_source() {
+        let sensors: Vec<String> = ["cpu_core_1", "cpu", "gpu"]
+            .iter()
+            .map(|label| {
+                format!(
+                    r#"{{"mode": 1, "label": "{label}", "value": "", "unit": "", "x": 0, "y": 0,
+                    "integerDigits": -1, "decimalDigits": -1, "pic": ""}}"#
+                )
+            })
+            .collect();
+        let mut panel: Panel = serde_json::from_str(&format!(
+            r#"{{"sensorFilter": ["^cpu_core_", "[invalid"], "sensor": [{}]}}"#,
+            sensors.join(",")
+        ))
+        .unwrap();
+        panel.map_sensor_labels(&HashMap::from([(
+            "gpu".to_string(),
+            "cpu_core_2".to_string(),
+        )]));
+
+        let filtered: Vec<bool> = panel
+            .sensor
+            .iter()
+            .map(|sensor| panel.is_sensor_filtered(sensor))
+            .collect();
+
+        assert_eq!(panel.sensor_filter.len(), 1);
+        assert_eq!(filtered, [true, false, true]);
+    }
+
+    #[test]
+    fn table_cell_sensors_are_positioned_in_columns_and_rows() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [], "tables": [{"x": 10, "y": 20, "rowHeight": 30, "fontSize": 18,
+            "columns": [{"width": 100}, {"width": 50, "textAlign": "right"}],
+            "rows": [["disk1_name", "disk1_used"], ["", "disk2_used", "ignored"]]}]}"#,
+        )
+        .unwrap();
+
+        let cells: Vec<_> = panel.tables[0]
+            .cell_sensors()
+            .into_iter()
+            .map(|s| (s.label, s.x, s.y, s.width, s.height, s.font_size))
+            .collect();
+
+        assert_eq!(
+            cells,
+            [
+                (
+                    "disk1_name".to_string(),
+                    10,
+                    20,
+                    Some(100),
+                    Some(30),
+                    Some(18)
+                ),
+                (
+                    "disk1_used".to_string(),
+                    110,
+                    20,
+                    Some(50),
+                    Some(30),
+                    Some(18)
+                ),
+                (
+                    "disk2_used".to_string(),
+                    110,
+                    50,
+                    Some(50),
+                    Some(30),
+                    Some(18)
+                ),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::decimal_comma("", true, "12,5", "12.5")]
+    #[case::keep_comma("", false, "12,5", "12,5")]
+    #[case::quotes("\"'", false, "\"42\"", "42")]
+    #[case::quoted_decimal_comma("\"", true, " \"12,5\" ", "12.5")]
+    #[case::trailing_unit("°C", false, "42.5 °C", "42.5")]
+    #[case::inner_chars_kept("\"", false, "a \"b\" c", "a \"b\" c")]
+    fn value_normalization(
+        #[case] strip: &str,
+        #[case] decimal_comma: bool,
+        #[case] value: &str,
+        #[case] expected: &str,
+    ) {
+        let normalize = ValueNormalization {
+            strip: strip.to_string(),
+            decimal_comma,
+        };
+
+        assert_eq!(normalize.apply(value), expected);
+    }
+
+    #[test]
+    fn value_normalization_parses_decimal_comma() {
+        let normalize = ValueNormalization {
+            decimal_comma: true,
+            ..Default::default()
+        };
+
+        assert_eq!(parse_number(&normalize.apply("12,5")), Some(12.5));
+    }
+
+    #[test]
+    fn get_next_active_panel_includes_custom_panel() {
+        let mut cfg = test_config(&[2, 0], 2);
+        let custom: Panel = serde_json::from_str(r#"{"name": "custom", "sensor": []}"#).unwrap();
+
+        assert_eq!(rotation(&mut cfg, 1), ["panel2"]);
+        cfg.include_custom_panel(custom);
+
+        assert_eq!(rotation(&mut cfg, 3), ["custom", "panel2", "custom"]);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn config_schema_lists_sensor_fields() {
+        let schema = serde_json::to_value(config_schema()).unwrap();
+        let sensor = &schema["$defs"]["Sensor"];
+
+        for field in [
+            "mode",
+            "label",
+            "value",
+            "unit",
+            "x",
+            "y",
+            "fontFamily",
+            "fontColor",
+            "integerDigits",
+            "pic",
+            "xz_x",
+            "updateInterval",
+        ] {
+            assert!(
+                sensor["properties"].get(field).is_some(),
+                "missing sensor field {field}"
+            );
+        }
+        assert!(sensor["properties"].get("source").is_none());
+        assert_eq!(
+            sensor["required"],
+            serde_json::json!(["mode", "label", "value", "unit", "x", "y", "pic"])
+        );
+        assert_eq!(sensor["properties"]["x"]["type"], "number");
+        assert_eq!(
+            sensor["x-ignored"],
+            serde_json::json!(["data", "interval", "textDirection", "url"])
+        );
+        assert_eq!(
+            schema["$defs"]["Panel"]["x-ignored"],
+            serde_json::json!(["checked", "type"])
+        );
+        assert_eq!(schema["x-ignored"], serde_json::json!(["credentials"]));
+        assert!(
+            schema["$defs"]["Setup"]["x-ignored"]
+                .as_array()
+                .unwrap()
+                .contains(&"ha_token".into())
+        );
+        assert_eq!(
+            schema["$defs"]["SensorMode"]["enum"],
+            serde_json::json!([1, 2, 3, 4, 5])
+        );
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn config_schema_accepts_numeric_string_times() {
+        let schema = serde_json::to_value(config_schema()).unwrap();
+        let setup = &schema["$defs"]["Setup"]["properties"];
+
+        for field in ["switchTime", "refresh"] {
+            let types: Vec<_> = setup[field]["anyOf"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|schema| schema["type"].clone())
+                .collect();
+            assert!(types.contains(&"number".into()), "{field}: {types:?}");
+            assert!(types.contains(&"string".into()), "{field}: {types:?}");
+        }
+    }
+
+    #[test]
+    fn ignored_aoostar_fields_are_accepted_and_not_serialized() {
+        let setup = test_setup(r#"{"offDisplay": true, "language": 1, "ha_url": "", "theme": {}}"#);
+        let json = serde_json::to_value(&setup).unwrap();
+
+        assert!(json.get("offDisplay").is_none());
+        assert!(json.get("ha_url").is_none());
+    }
+}
@@ -7,9 +7,9 @@ use ab_glyph::{FontArc, FontRef, FontVec};
 use anyhow::{Context, anyhow};
 use log::warn;
 use once_cell::sync::Lazy;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 static DEFAULT_TTF_FONT: Lazy<FontArc> = Lazy::new(|| {
     FontArc::new(
@@ -18,40 +18,118 @@ static DEFAULT_TTF_FONT: Lazy<FontArc> = Lazy::new(|| {
     )
 });
 
+/// Get the font name of a font family: the font filename without the `.ttf` file extension.
+///
+/// The font family of a configuration may be specified with or without file extension.
+pub fn font_name(font_family: &str) -> &str {
+    match font_family.len().checked_sub(4) {
+        Some(idx)
+            if font_family.is_char_boundary(idx)
+                && font_family[idx..].eq_ignore_ascii_case(".ttf") =>
+        {
+            &font_family[..idx]
+        }
+        _ => font_family,
+    }
+}
+
 pub struct FontHandler {
-    ttf_path: PathBuf,
+    /// Font directories, searched in order.
+    ttf_paths: Vec<PathBuf>,
     ttf_cache: HashMap<String, FontArc>,
+    /// Fonts which failed to load. Cached to only log the error once and to avoid reloading.
+    missing_fonts: HashSet<String>,
+    /// Optional default font replacing the embedded default font.
+    default_font: Option<FontArc>,
 }
 
 impl FontHandler {
+    /// Create a font handler for a single font directory.
     pub fn new(ttf_path: impl Into<PathBuf>) -> Self {
+        Self::with_search_path([ttf_path])
+    }
+
+    /// Create a font handler for multiple font directories, searched in the given order.
+    ///
+    /// A font is loaded from the first directory containing the font file.
+    pub fn with_search_path<P: Into<PathBuf>>(ttf_paths: impl IntoIterator<Item = P>) -> Self {
         Self {
-            ttf_path: ttf_path.into(),
+            ttf_paths: ttf_paths.into_iter().map(Into::into).collect(),
             ttf_cache: Default::default(),
+            missing_fonts: Default::default(),
+            default_font: None,
         }
     }
 
+    /// Append a font directory to the end of the search path.
+    ///
+    /// Fonts which failed to load before are searched again.
+    pub fn add_font_dir(&mut self, ttf_path: impl Into<PathBuf>) {
+        self.ttf_paths.push(ttf_path.into());
+        self.missing_fonts.clear();
+    }
+
+    /// Get the embedded DejaVuSans default font.
     pub fn default_font() -> FontArc {
         DEFAULT_TTF_FONT.clone()
     }
 
+    /// Set the default font for text without a font family and for fonts that cannot be loaded,
+    /// e.g. a font with CJK coverage. Defaults to the embedded [FontHandler::default_font].
+    pub fn set_default_font(&mut self, font: FontArc) {
+        self.default_font = Some(font);
+    }
+
+    /// Get the default font set with [FontHandler::set_default_font], or the embedded default font.
+    pub fn fallback_font(&self) -> FontArc {
+        self.default_font
+            .clone()
+            .unwrap_or_else(FontHandler::default_font)
+    }
+
+    /// Load a TrueType or OpenType font file.
+    pub fn load_font_file(path: &Path) -> anyhow::Result<FontArc> {
+        let data = fs::read(path).with_context(|| format!("Error reading font {path:?}"))?;
+        Ok(FontArc::new(
+            FontVec::try_from_vec(data).with_context(|| format!("Error parsing font {path:?}"))?,
+        ))
+    }
+
+    /// Get a font from the font directory or the default font if the font cannot be loaded.
+    ///
+    /// A font loading error is only logged once, subsequent requests for the same font return the
+    /// default font without trying to load it again. See [FontHandler::fallback_font].
     pub fn get_ttf_font_or_default(&mut self, name: &str) -> FontArc {
+        let name = font_name(name);
+        if self.missing_fonts.contains(name) {
+            return self.fallback_font();
+        }
+
         self.get_ttf_font(name).unwrap_or_else(|e| {
             warn!("Failed to load font: {e}. Using default");
-            FontHandler::default_font()
+            self.missing_fonts.insert(name.to_string());
+            self.fallback_font()
         })
     }
 
+    /// Get a font from the first font directory of the search path containing the font.
+    ///
+    /// The font name may include the `.ttf` file extension, see [font_name]. Loaded fonts are
+    /// cached by their name without file extension.
     pub fn get_ttf_font(&mut self, name: &str) -> anyhow::Result<FontArc> {
+        let name = font_name(name);
         if let Some(font) = self.ttf_cache.get(name) {
             return Ok(font.clone());
         }
-        let mut path = self.ttf_path.join(name);
-        path.set_extension("ttf");
-
-        if !path.exists() {
-            return Err(anyhow!("{name}.ttf not found"));
+        if self.missing_fonts.contains(name) {
+            return Err(anyhow!("{name}.ttf failed to load before"));
         }
+        let path = self
+            .ttf_paths
+            .iter()
+            .map(|dir| dir.join(name).with_extension("ttf"))
+            .find(|path| path.exists())
+            .ok_or_else(|| anyhow!("{name}.ttf not found"))?;
 
         let data = fs::read(path).with_context(|| format!("Error reading font {name}.ttf"))?;
         let font = FontArc::new(
@@ -67,5 +145,110 @@ impl FontHandler {
     #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.ttf_cache.clear();
+        self.missing_fonts.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ab_glyph::Font;
+
+    #[test]
+    fn missing_font_is_only_loaded_once() {
+        let font_dir =
+            std::env::temp_dir().join(format!("asterctl-font-test-{}", std::process::id()));
+        fs::create_dir_all(&font_dir).unwrap();
+        let font_file = font_dir.join("late.ttf");
+        let _ = fs::remove_file(&font_file);
+        let mut handler = FontHandler::new(&font_dir);
+
+        handler.get_ttf_font_or_default("late");
+        // the font becomes available, but the missing font must not be loaded again
+        fs::write(&font_file, include_bytes!("../../../fonts/DejaVuSans.ttf")).unwrap();
+        handler.get_ttf_font_or_default("late");
+
+        assert!(handler.get_ttf_font("late").is_err());
+        assert!(handler.ttf_cache.is_empty());
+
+        handler.clear();
+        assert!(handler.get_ttf_font("late").is_ok());
+
+        fs::remove_dir_all(&font_dir).unwrap();
+    }
+
+    #[test]
+    fn font_name_strips_ttf_extension() {
+        assert_eq!(font_name("DejaVuSans"), "DejaVuSans");
+        assert_eq!(font_name("DejaVuSans.ttf"), "DejaVuSans");
+        assert_eq!(font_name("DejaVuSans.TTF"), "DejaVuSans");
+        assert_eq!(font_name("Roboto.Light"), "Roboto.Light");
+        assert_eq!(font_name(".ttf"), "");
+        assert_eq!(font_name("ttf"), "ttf");
+    }
+
+    #[test]
+    fn font_with_file_extension_is_cached_by_name() {
+        let mut handler = FontHandler::new("../../fonts");
+
+        assert!(handler.get_ttf_font("DejaVuSans.ttf").is_ok());
+        assert!(handler.get_ttf_font("DejaVuSans").is_ok());
+        assert_eq!(handler.ttf_cache.len(), 1);
+        assert!(handler.ttf_cache.contains_key("DejaVuSans"));
+    }
+
+    #[test]
+    fn font_search_path_resolves_font_from_second_directory() {
+        let base_dir =
+            std::env::temp_dir().join(format!("asterctl-font-path-test-{}", std::process::id()));
+        let first_dir = base_dir.join("first");
+        let second_dir = base_dir.join("second");
+        fs::create_dir_all(&first_dir).unwrap();
+        fs::create_dir_all(&second_dir).unwrap();
+        fs::write(
+            second_dir.join("second_only.ttf"),
+            include_bytes!("../../../fonts/DejaVuSans.ttf"),
+        )
+        .unwrap();
+        let mut handler = FontHandler::with_search_path([&first_dir, &second_dir]);
+
+        assert!(handler.get_ttf_font("second_only").is_ok());
+        assert!(handler.ttf_cache.contains_key("second_only"));
+        assert!(handler.get_ttf_font("unknown").is_err());
+
+        let mut handler = FontHandler::new(&first_dir);
+        assert!(handler.get_ttf_font("second_only").is_err());
+        handler.add_font_dir(&second_dir);
+        assert!(handler.get_ttf_font("second_only").is_ok());
+
+        fs::remove_dir_all(&base_dir).unwrap();
+    }
+
+    #[test]
+    fn default_font_replaces_embedded_font_for_unknown_family() {
+        let font_dir =
+            std::env::temp_dir().join(format!("asterctl-default-font-{}", std::process::id()));
+        fs::create_dir_all(&font_dir).unwrap();
+        let font_file = font_dir.join("custom.ttf");
+        fs::write(&font_file, include_bytes!("../../../fonts/DejaVuSans.ttf")).unwrap();
+        let mut handler = FontHandler::new(font_dir.join("empty"));
+        let is_same_font =
+            |a: &FontArc, b: &FontArc| std::ptr::eq(a.font_data().as_ptr(), b.font_data().as_ptr());
+
+        let embedded = FontHandler::default_font();
+        assert!(is_same_font(
+            &handler.get_ttf_font_or_default("unknown"),
+            &embedded
+        ));
+
+        let custom = FontHandler::load_font_file(&font_file).unwrap();
+        handler.set_default_font(custom.clone());
+        let font = handler.get_ttf_font_or_default("unknown");
+        assert!(is_same_font(&font, &custom));
+        assert!(!is_same_font(&font, &embedded));
+        assert!(is_same_font(&handler.fallback_font(), &custom));
+
+        assert!(FontHandler::load_font_file(&font_dir.join("missing.ttf")).is_err());
+        fs::remove_dir_all(&font_dir).unwrap();
     }
 }
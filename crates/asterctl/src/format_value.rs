@@ -3,7 +3,12 @@
 
 //! Sensor value format functions based on the AOOSTAR-X application.
 
-#[derive(Debug, Clone)]
+#[cfg(feature = "schema")]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum IntegerDigits {
     /// Keep all integer digits
     Auto, // -1 in Python
@@ -33,6 +38,213 @@ impl From<Option<i32>> for IntegerDigits {
     }
 }
 
+/// Number format options for rendering sensor values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+    /// Decimal separator. Default: `.`
+    pub decimal_separator: char,
+    /// Optional thousands grouping separator of the integer part. Default: none
+    pub grouping_separator: Option<char>,
+    /// Rounding mode of the decimal places. Default: round half up
+    pub rounding: Rounding,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal_separator: '.',
+            grouping_separator: None,
+            rounding: Rounding::default(),
+        }
+    }
+}
+
+/// Notation of formatted numbers.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum Notation {
+    /// Fixed point number, e.g. `1234567`.
+    #[default]
+    Plain,
+    /// Number scaled with a SI prefix from `p` to `E`, e.g. `1.2M`.
+    Si,
+    /// Scientific notation with a single integer digit, e.g. `1.2e6`.
+    Scientific,
+}
+
+/// Rounding mode of formatted numbers.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum Rounding {
+    /// Round half away from zero, e.g. `2.5` to `3` and `-2.5` to `-3`.
+    #[default]
+    HalfUp,
+    /// Round half to the nearest even number (banker's rounding), e.g. `2.5` to `2` and `3.5` to `4`.
+    HalfEven,
+    /// Truncate the number towards zero, e.g. `2.9` to `2`.
+    Trunc,
+}
+
+/// SI prefixes from 10^-12 to 10^18 in steps of 10^3.
+const SI_PREFIXES: [&str; 11] = ["p", "n", "µ", "m", "", "k", "M", "G", "T", "P", "E"];
+/// Index of the unscaled SI prefix in [SI_PREFIXES].
+const SI_BASE_IDX: i32 = 4;
+
+/// Placement of the unit text relative to the formatted value.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum UnitPosition {
+    /// Unit is printed after the value, e.g. `42 %`.
+    #[default]
+    Suffix,
+    /// Unit is printed before the value, e.g. `$12.50`.
+    Prefix,
+}
+
+impl UnitPosition {
+    /// Combine a formatted value with the unit text.
+    pub fn place(self, value: &str, unit: &str) -> String {
+        match self {
+            UnitPosition::Suffix => format!("{value}{unit}"),
+            UnitPosition::Prefix => format!("{unit}{value}"),
+        }
+    }
+}
+
+/// Part of a formatted sensor value, see [FormattedValue::segments].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValuePart {
+    Integer,
+    Decimal,
+    Unit,
+}
+
+/// Formatted sensor value split into its integer, decimal and unit parts.
+///
+/// The parts can be styled individually, the [Display] implementation combines them as returned
+/// by [format_value_with].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormattedValue {
+    /// Integer part with sign and grouping separators, or the unchanged non-numeric value.
+    pub integer: String,
+    /// Decimal separator and decimal places, followed by the SI prefix or exponent of the notation.
+    pub decimal: String,
+    /// Unit text.
+    pub unit: String,
+    /// Placement of the unit text.
+    pub unit_position: UnitPosition,
+}
+
+impl FormattedValue {
+    /// Create a formatted value from a non-numeric value and its unit.
+    pub fn text(value: &str, unit: &str, unit_position: UnitPosition) -> Self {
+        Self {
+            integer: value.to_string(),
+            decimal: String::new(),
+            unit: unit.to_string(),
+            unit_position,
+        }
+    }
+
+    /// The value parts in display order.
+    pub fn segments(&self) -> [(ValuePart, &str); 3] {
+        let integer = (ValuePart::Integer, self.integer.as_str());
+        let decimal = (ValuePart::Decimal, self.decimal.as_str());
+        let unit = (ValuePart::Unit, self.unit.as_str());
+        match self.unit_position {
+            UnitPosition::Suffix => [integer, decimal, unit],
+            UnitPosition::Prefix => [unit, integer, decimal],
+        }
+    }
+}
+
+impl Display for FormattedValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (_, text) in self.segments() {
+            f.write_str(text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse a numeric sensor value.
+///
+/// Surrounding whitespace and a trailing `%` sign are ignored, e.g. `" 42.5 % "` is parsed as 42.5.
+///
+/// returns: the parsed number, or None if the value is not numeric.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(asterctl::parse_number("42.5%"), Some(42.5));
+/// assert_eq!(asterctl::parse_number("n/a"), None);
+/// ```
+pub fn parse_number(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let value = value.strip_suffix('%').map(str::trim_end).unwrap_or(value);
+
+    value.parse::<f64>().ok()
+}
+
+/// Parse the leading number of a sensor value with an embedded unit.
+///
+/// Leading whitespace is ignored, any text after the number is ignored, e.g. `"1.23 MB/s"` is
+/// parsed as 1.23.
+///
+/// returns: the parsed leading number, or None if the value doesn't start with a number.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(asterctl::parse_leading_number("1.23 MB/s"), Some(1.23));
+/// ```
+pub fn parse_leading_number(value: &str) -> Option<f64> {
+    leading_number(value).map(|(number, _)| number)
+}
+
+/// Parse a human-readable byte value and normalize it to bytes.
+///
+/// The leading number is multiplied with the binary factor of the unit prefix following the number:
+/// `B`, `KB`, `MB`, `GB`, `TB`, `PB` (1024 based, as used in `aster-sysinfo`). The unit is case-insensitive,
+/// an optional `iB` suffix is supported (e.g. `MiB`), and any text after the unit is ignored.
+/// A value without a unit is returned as is.
+///
+/// returns: the number of bytes, or None if the value doesn't start with a number.
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(asterctl::parse_bytes("1.5 KB/s"), Some(1536.0));
+/// ```
+pub fn parse_bytes(value: &str) -> Option<f64> {
+    let (number, rest) = leading_number(value)?;
+    let unit = rest.trim_start().to_ascii_uppercase();
+
+    let exponent = ['K', 'M', 'G', 'T', 'P']
+        .iter()
+        .position(|prefix| unit.starts_with(*prefix))
+        .map(|pos| pos as i32 + 1)
+        .unwrap_or(0);
+
+    Some(number * 1024f64.powi(exponent))
+}
+
+/// Split a value into the leading number and the remaining text.
+fn leading_number(value: &str) -> Option<(f64, &str)> {
+    let value = value.trim_start();
+    let end = value
+        .char_indices()
+        .find(|&(idx, c)| !(c.is_ascii_digit() || c == '.' || (idx == 0 && (c == '-' || c == '+'))))
+        .map(|(idx, _)| idx)
+        .unwrap_or(value.len());
+
+    let number = value[..end].parse::<f64>().ok()?;
+    Some((number, &value[end..]))
+}
+
 /// Format a sensor value in string format to the specified fixed point number.
 ///
 /// # Arguments
@@ -42,6 +254,9 @@ impl From<Option<i32>> for IntegerDigits {
 /// * `decimal_digits`: fixed point numbers
 /// * `unit`: unit suffix to append after the formatted number
 ///
+/// A value with a trailing `%` sign is treated as a percentage number, see [parse_number].
+/// The `%` sign is used as unit if no `unit` is specified.
+///
 /// returns: String
 ///
 /// # Examples
@@ -56,18 +271,192 @@ pub fn format_value(
     decimal_digits: usize,
     unit: &str,
 ) -> String {
-    let num = match value.parse::<f64>() {
-        Ok(n) => n,
-        Err(_) => return format!("{}{}", value, unit),
+    format_value_with(
+        value,
+        integer_digits,
+        decimal_digits,
+        unit,
+        &NumberFormat::default(),
+    )
+}
+
+/// Format a sensor value in string format to the specified fixed point number with the given
+/// decimal and grouping separators.
+///
+/// See [format_value] for the formatting rules.
+///
+/// # Examples
+///
+/// ```
+/// use asterctl::{IntegerDigits, NumberFormat, format_value_with};
+///
+/// let number_format = NumberFormat {
+///     decimal_separator: ',',
+///     grouping_separator: Some('.'),
+///     ..Default::default()
+/// };
+/// let value = format_value_with("1234.5", IntegerDigits::Auto, 2, " €", &number_format);
+/// assert_eq!(value, "1.234,50 €");
+/// ```
+pub fn format_value_with(
+    value: &str,
+    integer_digits: IntegerDigits,
+    decimal_digits: usize,
+    unit: &str,
+    number_format: &NumberFormat,
+) -> String {
+    format_value_parts(
+        value,
+        integer_digits,
+        decimal_digits,
+        unit,
+        UnitPosition::Suffix,
+        Notation::Plain,
+        number_format,
+    )
+    .to_string()
+}
+
+/// Format a sensor value like [format_value_with] with the given unit placement and number
+/// notation, keeping the integer, decimal and unit parts separated.
+///
+/// The `decimal_digits` apply to the scaled number of the [Notation::Si] and
+/// [Notation::Scientific] notations.
+///
+/// # Examples
+///
+/// ```
+/// use asterctl::{IntegerDigits, Notation, NumberFormat, UnitPosition, format_value_parts};
+///
+/// let number_format = NumberFormat::default();
+/// let value = format_value_parts(
+///     "12.345",
+///     IntegerDigits::Auto,
+///     1,
+///     "$",
+///     UnitPosition::Prefix,
+///     Notation::Plain,
+///     &number_format,
+/// );
+/// assert_eq!(value.integer, "12");
+/// assert_eq!(value.decimal, ".3");
+/// assert_eq!(value.unit, "$");
+/// assert_eq!(value.to_string(), "$12.3");
+/// ```
+pub fn format_value_parts(
+    value: &str,
+    integer_digits: IntegerDigits,
+    decimal_digits: usize,
+    unit: &str,
+    unit_position: UnitPosition,
+    notation: Notation,
+    number_format: &NumberFormat,
+) -> FormattedValue {
+    let num = match parse_number(value) {
+        Some(n) => n,
+        None => return FormattedValue::text(value, unit, unit_position),
+    };
+    let unit = if unit.is_empty() && value.trim_end().ends_with('%') {
+        "%"
+    } else {
+        unit
     };
 
-    // Round number to the specified decimal digits
+    let (integer, decimal) = match notation {
+        Notation::Plain => format_fixed(num, integer_digits, decimal_digits, number_format),
+        Notation::Si => {
+            let (scaled, prefix) = si_scale(num, decimal_digits, number_format.rounding);
+            let (integer, decimal) =
+                format_fixed(scaled, integer_digits, decimal_digits, number_format);
+            (integer, format!("{decimal}{prefix}"))
+        }
+        Notation::Scientific => {
+            let (mantissa, exponent) =
+                scientific_scale(num, decimal_digits, number_format.rounding);
+            let (integer, decimal) =
+                format_fixed(mantissa, IntegerDigits::Auto, decimal_digits, number_format);
+            (integer, format!("{decimal}e{exponent}"))
+        }
+    };
+
+    FormattedValue {
+        integer,
+        decimal,
+        unit: unit.to_string(),
+        unit_position,
+    }
+}
+
+/// Round a number to the given decimal places with the rounding mode.
+fn round_to(num: f64, decimal_digits: usize, rounding: Rounding) -> f64 {
     let factor = 10f64.powi(decimal_digits as i32);
-    let rounded = if decimal_digits == 0 {
-        num.round()
-    } else {
-        (num * factor).round() / factor
+    let scaled = num * factor;
+    let rounded = match rounding {
+        Rounding::HalfUp => scaled.round(),
+        Rounding::HalfEven => scaled.round_ties_even(),
+        Rounding::Trunc => {
+            // don't truncate representation errors, e.g. 0.29 * 100 = 28.999999999999996
+            let nearest = scaled.round();
+            if (scaled - nearest).abs() < 1e-9 {
+                nearest
+            } else {
+                scaled.trunc()
+            }
+        }
     };
+    rounded / factor
+}
+
+/// Scale a number to the range 1..1000 with the matching SI prefix.
+///
+/// The range is checked after rounding to `decimal_digits`, e.g. 999.96 with one decimal place
+/// is scaled to 1.0k instead of 1000.0.
+fn si_scale(num: f64, decimal_digits: usize, rounding: Rounding) -> (f64, &'static str) {
+    if num == 0.0 || !num.is_finite() {
+        return (num, "");
+    }
+
+    let max_idx = SI_PREFIXES.len() as i32 - 1;
+    let mut idx = ((num.abs().log10() / 3.0).floor() as i32 + SI_BASE_IDX).clamp(0, max_idx);
+    let mut scaled = num / 1000f64.powi(idx - SI_BASE_IDX);
+    if round_to(scaled, decimal_digits, rounding).abs() >= 1000.0 && idx < max_idx {
+        idx += 1;
+        scaled /= 1000.0;
+    }
+
+    (scaled, SI_PREFIXES[idx as usize])
+}
+
+/// Split a number into a mantissa in the range 1..10 and a base 10 exponent.
+///
+/// The range is checked after rounding the mantissa to `decimal_digits`.
+fn scientific_scale(num: f64, decimal_digits: usize, rounding: Rounding) -> (f64, i32) {
+    if num == 0.0 || !num.is_finite() {
+        return (num, 0);
+    }
+
+    let mut exponent = num.abs().log10().floor() as i32;
+    let mut mantissa = num / 10f64.powi(exponent);
+    if round_to(mantissa, decimal_digits, rounding).abs() >= 10.0 {
+        exponent += 1;
+        mantissa /= 10.0;
+    }
+
+    (mantissa, exponent)
+}
+
+/// Format a number as fixed point number with the given integer and decimal places.
+///
+/// returns: the integer part, and the decimal part including the decimal separator.
+fn format_fixed(
+    num: f64,
+    integer_digits: IntegerDigits,
+    decimal_digits: usize,
+    number_format: &NumberFormat,
+) -> (String, String) {
+    // Round number to the specified decimal digits
+    let factor = 10f64.powi(decimal_digits as i32);
+    let rounded = round_to(num, decimal_digits, number_format.rounding);
 
     // Get integer and decimal parts
     // The integer part may increase due to rounding!
@@ -99,13 +488,94 @@ pub fn format_value(
         }
     };
 
-    let formatted = if decimal_digits > 0 {
-        format!("{}.{}", integer_filled, decimal_part)
+    let integer_filled = match number_format.grouping_separator {
+        Some(separator) => group_digits(&integer_filled, separator),
+        None => integer_filled,
+    };
+
+    if decimal_digits > 0 {
+        (
+            integer_filled,
+            format!("{}{}", number_format.decimal_separator, decimal_part),
+        )
     } else {
-        integer_filled
+        (integer_filled, String::new())
+    }
+}
+
+/// Insert a grouping separator every three digits of an integer number, keeping a leading sign.
+fn group_digits(integer: &str, separator: char) -> String {
+    let (sign, digits) = match integer.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", integer),
     };
 
-    format!("{}{}", formatted, unit)
+    let mut grouped = String::with_capacity(integer.len() + digits.len() / 3);
+    grouped.push_str(sign);
+    for (idx, digit) in digits.chars().enumerate() {
+        if idx > 0 && (digits.len() - idx) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+/// Substitute `{label}` placeholders in a text template with sensor values.
+///
+/// Placeholders are resolved with the given `lookup` function. A placeholder without a value is
+/// replaced with an empty string. Literal braces can be printed with `{{` and `}}`, an unclosed
+/// `{` is printed as is.
+///
+/// # Arguments
+///
+/// * `template`: text template with `{label}` placeholders
+/// * `lookup`: returns the sensor value for a placeholder label
+///
+/// returns: String
+///
+/// # Examples
+///
+/// ```
+/// let text = asterctl::format_template("CPU: {cpu}% / {cores} cores", |label| match label {
+///     "cpu" => Some("42".to_string()),
+///     "cores" => Some("8".to_string()),
+///     _ => None,
+/// });
+/// assert_eq!(text, "CPU: 42% / 8 cores");
+/// ```
+pub fn format_template<F>(template: &str, lookup: F) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        output.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            output.push_str(&tail[..1]);
+            rest = &tail[2..];
+        } else if let Some(after) = tail.strip_prefix('}') {
+            output.push('}');
+            rest = after;
+        } else if let Some(end) = tail.find('}') {
+            let label = tail[1..end].trim();
+            if let Some(value) = lookup(label) {
+                output.push_str(&value);
+            }
+            rest = &tail[end + 1..];
+        } else {
+            output.push_str(tail);
+            rest = "";
+        }
+    }
+    output.push_str(rest);
+
+    output
 }
 
 #[cfg(test)]
@@ -197,4 +667,282 @@ mod tests {
         let result = format_value(input, IntegerDigits::from(digits), decimals, unit);
         assert_eq!(output, result);
     }
+
+    #[rstest]
+    #[case::half_up("2.5", Rounding::HalfUp, 0, "3")]
+    #[case::half_up("3.5", Rounding::HalfUp, 0, "4")]
+    #[case::half_up("-2.5", Rounding::HalfUp, 0, "-3")]
+    #[case::half_up("2.29", Rounding::HalfUp, 1, "2.3")]
+    #[case::half_even("2.5", Rounding::HalfEven, 0, "2")]
+    #[case::half_even("3.5", Rounding::HalfEven, 0, "4")]
+    #[case::half_even("0.125", Rounding::HalfEven, 2, "0.12")]
+    #[case::half_even("2.29", Rounding::HalfEven, 1, "2.3")]
+    #[case::trunc("2.5", Rounding::Trunc, 0, "2")]
+    #[case::trunc("3.5", Rounding::Trunc, 0, "3")]
+    #[case::trunc("2.29", Rounding::Trunc, 1, "2.2")]
+    #[case::trunc("-2.29", Rounding::Trunc, 1, "-2.2")]
+    #[case::trunc("0.29", Rounding::Trunc, 2, "0.29")]
+    fn test_format_value_with_rounding_mode(
+        #[case] input: &str,
+        #[case] rounding: Rounding,
+        #[case] decimals: usize,
+        #[case] output: &str,
+    ) {
+        let number_format = NumberFormat {
+            rounding,
+            ..Default::default()
+        };
+        let result = format_value_with(input, IntegerDigits::Auto, decimals, "", &number_format);
+        assert_eq!(output, result);
+    }
+
+    #[rstest]
+    #[case("42.5%", 42.5)]
+    #[case(" 42.5 % ", 42.5)]
+    #[case("42.5", 42.5)]
+    #[case("-3", -3.0)]
+    #[case("100 %", 100.0)]
+    fn test_parse_number(#[case] input: &str, #[case] output: f64) {
+        assert_eq!(Some(output), parse_number(input));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("%")]
+    #[case("42.5%%")]
+    #[case("% 42.5")]
+    #[case("invalid")]
+    fn test_parse_number_invalid(#[case] input: &str) {
+        assert_eq!(None, parse_number(input));
+    }
+
+    #[rstest]
+    #[case("1.23 MB/s", 1.23)]
+    #[case("42", 42.0)]
+    #[case(" -5.5°C", -5.5)]
+    #[case("17GB", 17.0)]
+    fn test_parse_leading_number(#[case] input: &str, #[case] output: f64) {
+        assert_eq!(Some(output), parse_leading_number(input));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("MB/s 1.23")]
+    #[case(".")]
+    #[case("-")]
+    fn test_parse_leading_number_invalid(#[case] input: &str) {
+        assert_eq!(None, parse_leading_number(input));
+    }
+
+    #[rstest]
+    #[case("1.23 MB/s", 1.23 * 1024.0 * 1024.0)]
+    #[case("42", 42.0)]
+    #[case("512 B", 512.0)]
+    #[case("1.5 KB/s", 1536.0)]
+    #[case("2 GiB", 2.0 * 1024.0 * 1024.0 * 1024.0)]
+    #[case("1tb", 1024f64.powi(4))]
+    fn test_parse_bytes(#[case] input: &str, #[case] output: f64) {
+        assert_eq!(Some(output), parse_bytes(input));
+    }
+
+    #[rstest]
+    #[case("42.5%", "", "42.5%")]
+    #[case(" 42.5 % ", "", "42.5%")]
+    #[case("42.5%", " %", "42.5 %")]
+    #[case("42.5", "%", "42.5%")]
+    fn test_format_value_with_percentage(
+        #[case] input: &str,
+        #[case] unit: &str,
+        #[case] output: &str,
+    ) {
+        let result = format_value(input, IntegerDigits::Auto, 1, unit);
+        assert_eq!(output, result);
+    }
+
+    #[rstest]
+    #[case("1234567.891", -1, 2, None, "1234567,89")]
+    #[case("1234567.891", -1, 2, Some('.'), "1.234.567,89")]
+    #[case("-1234.5", -1, 1, Some('.'), "-1.234,5")]
+    #[case("123.456", -1, 1, Some('.'), "123,5")]
+    #[case("123456", 7, 0, Some(' '), "0 123 456")]
+    #[case("1234.5", 0, 1, Some('.'), ",5")]
+    fn test_format_value_with_comma_decimal(
+        #[case] input: &str,
+        #[case] digits: i32,
+        #[case] decimals: usize,
+        #[case] grouping: Option<char>,
+        #[case] output: &str,
+    ) {
+        let number_format = NumberFormat {
+            decimal_separator: ',',
+            grouping_separator: grouping,
+            ..Default::default()
+        };
+        let result = format_value_with(
+            input,
+            IntegerDigits::from(digits),
+            decimals,
+            "",
+            &number_format,
+        );
+        assert_eq!(output, result);
+    }
+
+    #[test]
+    fn test_format_value_with_grouping_keeps_non_numeric_value() {
+        let number_format = NumberFormat {
+            decimal_separator: ',',
+            grouping_separator: Some('.'),
+            ..Default::default()
+        };
+        let result = format_value_with("1.5 GB", IntegerDigits::Auto, 2, "", &number_format);
+        assert_eq!("1.5 GB", result);
+    }
+
+    #[rstest]
+    #[case("12.5", "$", UnitPosition::Prefix, "$12.50")]
+    #[case("-3", "€ ", UnitPosition::Prefix, "€ -3.00")]
+    #[case("42.5%", "", UnitPosition::Prefix, "%42.50")]
+    #[case("n/a", "$", UnitPosition::Prefix, "$n/a")]
+    #[case("12.5", " €", UnitPosition::Suffix, "12.50 €")]
+    #[case("n/a", " €", UnitPosition::Suffix, "n/a €")]
+    fn test_format_value_parts_with_unit_position(
+        #[case] input: &str,
+        #[case] unit: &str,
+        #[case] position: UnitPosition,
+        #[case] output: &str,
+    ) {
+        let result = format_value_parts(
+            input,
+            IntegerDigits::Auto,
+            2,
+            unit,
+            position,
+            Notation::Plain,
+            &NumberFormat::default(),
+        );
+        assert_eq!(output, result.to_string());
+    }
+
+    #[rstest]
+    #[case("1234567", Notation::Plain, 1, "1234567.0")]
+    #[case("0.000012", Notation::Plain, 3, "0.000")]
+    #[case("1234567", Notation::Si, 1, "1.2M")]
+    #[case("1234567", Notation::Si, 0, "1M")]
+    #[case("2500", Notation::Si, 2, "2.50k")]
+    #[case("999.96", Notation::Si, 1, "1.0k")]
+    #[case("42", Notation::Si, 1, "42.0")]
+    #[case("-3200000000", Notation::Si, 1, "-3.2G")]
+    #[case("0.0125", Notation::Si, 1, "12.5m")]
+    #[case("0.000012", Notation::Si, 0, "12µ")]
+    #[case("0", Notation::Si, 1, "0.0")]
+    #[case("1234567", Notation::Scientific, 1, "1.2e6")]
+    #[case("-1234567", Notation::Scientific, 2, "-1.23e6")]
+    #[case("0.000012", Notation::Scientific, 1, "1.2e-5")]
+    #[case("9.96", Notation::Scientific, 1, "1.0e1")]
+    #[case("0", Notation::Scientific, 1, "0.0e0")]
+    #[case("n/a", Notation::Scientific, 1, "n/a")]
+    fn test_format_value_with_notation(
+        #[case] input: &str,
+        #[case] notation: Notation,
+        #[case] decimals: usize,
+        #[case] output: &str,
+    ) {
+        let result = format_value_parts(
+            input,
+            IntegerDigits::Auto,
+            decimals,
+            "",
+            UnitPosition::Suffix,
+            notation,
+            &NumberFormat::default(),
+        );
+        assert_eq!(output, result.to_string());
+    }
+
+    #[test]
+    fn test_format_value_with_si_notation_and_unit() {
+        let number_format = NumberFormat {
+            decimal_separator: ',',
+            ..Default::default()
+        };
+        let result = format_value_parts(
+            "1536000",
+            IntegerDigits::Auto,
+            2,
+            "B",
+            UnitPosition::Suffix,
+            Notation::Si,
+            &number_format,
+        );
+        assert_eq!("1,54MB", result.to_string());
+    }
+
+    #[rstest]
+    #[case::suffix("1234.56", UnitPosition::Suffix, Notation::Plain, ["1,234", ".6", " V"], "1,234.6 V")]
+    #[case::prefix("12.5", UnitPosition::Prefix, Notation::Plain, ["12", ".5", " V"], " V12.5")]
+    #[case::si("1500000", UnitPosition::Suffix, Notation::Si, ["1", ".5M", " V"], "1.5M V")]
+    #[case::text("n/a", UnitPosition::Suffix, Notation::Plain, ["n/a", "", " V"], "n/a V")]
+    fn test_format_value_parts(
+        #[case] value: &str,
+        #[case] unit_position: UnitPosition,
+        #[case] notation: Notation,
+        #[case] parts: [&str; 3],
+        #[case] output: &str,
+    ) {
+        let number_format = NumberFormat {
+            grouping_separator: Some(','),
+            ..Default::default()
+        };
+        let formatted = format_value_parts(
+            value,
+            IntegerDigits::Auto,
+            1,
+            " V",
+            unit_position,
+            notation,
+            &number_format,
+        );
+
+        assert_eq!(
+            [
+                formatted.integer.as_str(),
+                formatted.decimal.as_str(),
+                formatted.unit.as_str()
+            ],
+            parts
+        );
+        assert_eq!(formatted.to_string(), output);
+    }
+
+    fn template_lookup(label: &str) -> Option<String> {
+        match label {
+            "cpu_usage_percent" => Some("42.5".to_string()),
+            "cpu_count" => Some("8".to_string()),
+            _ => None,
+        }
+    }
+
+    #[rstest]
+    #[case(
+        "CPU: {cpu_usage_percent}% / {cpu_count} cores",
+        "CPU: 42.5% / 8 cores"
+    )]
+    #[case("{cpu_count}{cpu_count}", "88")]
+    #[case("{ cpu_count }", "8")]
+    #[case("no placeholder", "no placeholder")]
+    #[case("{{cpu_count}} = {cpu_count}", "{cpu_count} = 8")]
+    #[case("unclosed {cpu_count", "unclosed {cpu_count")]
+    fn test_format_template(#[case] template: &str, #[case] output: &str) {
+        assert_eq!(output, format_template(template, template_lookup));
+    }
+
+    #[test]
+    fn test_format_template_with_missing_placeholder() {
+        let result = format_template(
+            "CPU: {cpu_usage_percent}% GPU: {gpu_usage}%",
+            template_lookup,
+        );
+        assert_eq!("CPU: 42.5% GPU: %", result);
+    }
 }
@@ -3,22 +3,41 @@
 
 //! Image helper functions.
 
+use anyhow::anyhow;
+use asterctl_lcd::from_rgb565_le;
 use image::imageops::FilterType;
-use image::{DynamicImage, GenericImageView, ImageBuffer, ImageReader, Rgba, RgbaImage};
+use image::{
+    DynamicImage, GenericImageView, ImageBuffer, ImageFormat, ImageReader, Rgb, Rgba, RgbaImage,
+};
 use imageproc::geometric_transformations::{Interpolation, rotate};
-use log::{debug, warn};
+use log::{debug, info, warn};
 use std::collections::HashMap;
 use std::f32::consts::PI;
+use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 /// Width, height type
 pub type Size = (u32, u32);
 
+/// File extension of raw RGB 565 images.
+const RGB565_EXTENSION: &str = "565";
+
+/// Load an image and resize it to the given size if the image dimensions don't match.
+///
+/// Raw RGB 565 images with the `.565` file extension are supported, see [load_rgb565_image].
+/// Lossless QOI images with the `.qoi` file extension decode much faster than PNG images and are
+/// recommended for slow devices.
 pub fn load_image<P>(path: P, size: Option<Size>) -> anyhow::Result<DynamicImage>
 where
     P: AsRef<Path>,
 {
-    let img = ImageReader::open(path)?.decode()?;
+    let path = path.as_ref();
+    let img = if is_rgb565_file(path) {
+        load_rgb565_image(path, size)?
+    } else {
+        ImageReader::open(path)?.decode()?
+    };
     debug!(
         "Image dimensions: {:?}, {:?}",
         img.dimensions(),
@@ -39,10 +58,84 @@ where
     }
 }
 
+fn is_rgb565_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(RGB565_EXTENSION))
+}
+
+/// Load a raw RGB 565 image in little endian format, as sent to the display.
+///
+/// The pixel data is either prefixed with a 4-byte header containing the width and height as
+/// little endian u16 values, or without a header if the file size matches the expected `size`.
+///
+/// # Arguments
+///
+/// * `path`: raw image file path.
+/// * `size`: optional expected image size for files without a header.
+///
+/// returns: the RGB image, or an error if the file size doesn't match the image dimensions.
+pub fn load_rgb565_image<P: AsRef<Path>>(
+    path: P,
+    size: Option<Size>,
+) -> anyhow::Result<DynamicImage> {
+    let path = path.as_ref();
+    let data = fs::read(path)?;
+
+    let image = match size {
+        Some((width, height)) if data.len() == width as usize * height as usize * 2 => {
+            from_rgb565_le(&data, width, height)
+        }
+        _ if data.len() >= 4 => {
+            let width = u16::from_le_bytes([data[0], data[1]]) as u32;
+            let height = u16::from_le_bytes([data[2], data[3]]) as u32;
+            from_rgb565_le(&data[4..], width, height)
+        }
+        _ => None,
+    }
+    .ok_or_else(|| {
+        anyhow!(
+            "Invalid RGB 565 image {path:?}: file size {} doesn't match the image dimensions",
+            data.len()
+        )
+    })?;
+
+    Ok(DynamicImage::ImageRgb8(image))
+}
+
+/// List all image files of a directory, sorted by filename.
+///
+/// Files without a supported image file extension and subdirectories are skipped.
+pub fn list_images<P: AsRef<Path>>(dir: P) -> std::io::Result<Vec<PathBuf>> {
+    let mut images = Vec::new();
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_file() && (ImageFormat::from_path(&path).is_ok() || is_rgb565_file(&path)) {
+            images.push(path);
+        } else {
+            debug!("Skipping non-image file {path:?}");
+        }
+    }
+    images.sort();
+
+    Ok(images)
+}
+
+/// Default delay before retrying to load an image which failed to load.
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(1);
+
 /// Cache for loaded images to avoid repeated file I/O
+///
+/// Images which fail to load, e.g. a partially written file, are not cached permanently. Loading
+/// is retried on the next access after the retry delay.
 pub struct ImageCache {
     img_path: PathBuf,
-    cache: HashMap<PathBuf, Option<RgbaImage>>,
+    cache: HashMap<PathBuf, RgbaImage>,
+    /// Tinted images per image path and tint color, see [ImageCache::get_tinted].
+    tinted: HashMap<(PathBuf, Rgb<u8>), RgbaImage>,
+    /// Time of the last failed load attempt per image path.
+    failed: HashMap<PathBuf, Instant>,
+    retry_delay: Duration,
 }
 
 impl ImageCache {
@@ -50,66 +143,156 @@ impl ImageCache {
         Self {
             img_path: img_path.into(),
             cache: HashMap::new(),
+            tinted: HashMap::new(),
+            failed: HashMap::new(),
+            retry_delay: DEFAULT_RETRY_DELAY,
         }
     }
 
+    /// Set the delay before retrying to load a previously failed image. Default: 1s
+    pub fn set_retry_delay(&mut self, delay: Duration) {
+        self.retry_delay = delay;
+    }
+
     /// Load and cache an image, returns None if loading fails
     pub fn get<P: AsRef<Path>>(&mut self, path: P, size: Option<Size>) -> Option<&RgbaImage> {
-        let path = path.as_ref();
-        let path = if path.is_absolute() {
-            path.to_path_buf()
-        } else {
-            self.img_path.join(path)
-        };
+        let path = self.full_path(path.as_ref());
 
         if !self.cache.contains_key(&path) {
-            let image_result = match load_image(&path, size) {
-                Ok(img) => Some(img.to_rgba8()),
+            let retry = match self.failed.get(&path) {
+                Some(failed_at) if failed_at.elapsed() < self.retry_delay => return None,
+                Some(_) => true,
+                None => false,
+            };
+
+            match load_image(&path, size) {
+                Ok(img) => {
+                    if retry {
+                        info!("Loaded previously failed image {path:?}");
+                    }
+                    self.failed.remove(&path);
+                    self.cache.insert(path.clone(), img.to_rgba8());
+                }
                 Err(e) => {
-                    warn!("Failed to load image {:?}: {:?}", path, e);
-                    None
+                    if retry {
+                        debug!("Failed to load image {:?}: {:?}", path, e);
+                    } else {
+                        warn!("Failed to load image {:?}: {:?}", path, e);
+                    }
+                    self.failed.insert(path, Instant::now());
+                    return None;
                 }
-            };
+            }
+        }
 
-            self.cache.insert(path.clone(), image_result);
+        self.cache.get(&path)
+    }
+
+    /// Load and cache an image tinted with the given color, see [tint].
+    ///
+    /// The tinted image is cached per image path and tint color.
+    ///
+    /// returns: None if loading the image fails
+    pub fn get_tinted<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        size: Option<Size>,
+        color: Rgb<u8>,
+    ) -> Option<&RgbaImage> {
+        let key = (self.full_path(path.as_ref()), color);
+        if !self.tinted.contains_key(&key) {
+            let tinted = tint(self.get(&key.0, size)?, color);
+            self.tinted.insert(key.clone(), tinted);
         }
 
-        self.cache.get(&path).and_then(|opt| opt.as_ref())
+        self.tinted.get(&key)
+    }
+
+    /// Get the full path of an image, relative paths are resolved from the image directory.
+    fn full_path(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.img_path.join(path)
+        }
     }
 
     #[allow(dead_code)]
     pub fn clear(&mut self) {
         self.cache.clear();
+        self.tinted.clear();
+        self.failed.clear();
     }
 }
 
 /// Quality settings for rotation
-#[derive(Debug, Clone, Copy)]
-#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
 pub enum RotationQuality {
     /// Nearest neighbor
     Fast,
     /// Bilinear
+    #[default]
     Good,
     /// Bicubic
     Best,
 }
 
 /// Rotate image by specified angle in degrees
-pub fn rotate_image(image: &RgbaImage, angle_degrees: i32) -> RgbaImage {
-    match angle_degrees {
+///
+/// Multiples of 90 degrees use fast, lossless rotations, the `quality` and `expand` options only
+/// apply to arbitrary angles.
+///
+/// # Arguments
+///
+/// * `image`: image to rotate.
+/// * `angle_degrees`: clockwise rotation angle.
+/// * `quality`: interpolation method for arbitrary angles.
+/// * `expand`: enlarge the canvas to the bounding box of the rotated image, so that no corners are
+///   clipped. Otherwise, the original image dimensions are kept. The image center is kept in both
+///   cases.
+pub fn rotate_image(
+    image: &RgbaImage,
+    angle_degrees: i32,
+    quality: RotationQuality,
+    expand: bool,
+) -> RgbaImage {
+    match angle_degrees.rem_euclid(360) {
         0 => image.clone(),
         90 => rotate_90_degrees(image, true),
         270 => rotate_90_degrees(image, false),
         180 => rotate_180_degrees(image),
-        angle => {
-            let angle_radians = angle as f32 * PI / 180.0;
-            // TODO check Bilinear vs Bicubic
-            rotate_about_center(image, angle_radians, RotationQuality::Good)
+        _ => {
+            let angle_radians = angle_degrees as f32 * PI / 180.0;
+            if expand {
+                let expanded = expand_canvas(image, angle_radians);
+                rotate_about_center(&expanded, angle_radians, quality)
+            } else {
+                rotate_about_center(image, angle_radians, quality)
+            }
         }
     }
 }
 
+/// Center an image on a transparent canvas large enough to hold the image rotated by the given angle.
+fn expand_canvas(image: &RgbaImage, angle_radians: f32) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let (sin, cos) = (angle_radians.sin().abs(), angle_radians.cos().abs());
+    let new_width = (width as f32 * cos + height as f32 * sin).ceil() as u32;
+    let new_height = (width as f32 * sin + height as f32 * cos).ceil() as u32;
+    let new_width = new_width.max(width);
+    let new_height = new_height.max(height);
+
+    let mut canvas = RgbaImage::new(new_width, new_height);
+    image::imageops::replace(
+        &mut canvas,
+        image,
+        ((new_width - width) / 2) as i64,
+        ((new_height - height) / 2) as i64,
+    );
+
+    canvas
+}
+
 /// Rotate image about its center, maintaining original dimensions
 fn rotate_about_center(
     image: &RgbaImage,
@@ -173,3 +356,426 @@ pub fn rotate_180_degrees(image: &RgbaImage) -> RgbaImage {
 
     rotated
 }
+
+/// Colorize an image by multiplying the RGB channels with the given tint color.
+///
+/// The alpha channel is preserved. A white image is recolored to the tint color, black stays black.
+pub fn tint(image: &RgbaImage, color: Rgb<u8>) -> RgbaImage {
+    let mut tinted = image.clone();
+
+    for pixel in tinted.pixels_mut() {
+        for i in 0..3 {
+            pixel[i] = ((pixel[i] as u16 * color[i] as u16 + 127) / 255) as u8;
+        }
+    }
+
+    tinted
+}
+
+/// Offset directions of the [PixelShift] cycle, multiplied with the shift magnitude.
+const PIXEL_SHIFT_CYCLE: [(i32, i32); 8] = [
+    (0, 0),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, -1),
+];
+
+/// Burn-in protection by shifting the entire frame by a few pixels on a slow schedule.
+///
+/// The frame is moved around the original position in a cycle of eight offsets, changing at every
+/// interval.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PixelShift {
+    /// Maximum offset in pixels in each direction.
+    pub magnitude: u32,
+    /// Time between two shifts.
+    pub interval: Duration,
+}
+
+impl PixelShift {
+    /// Get the frame offset in pixels for the given elapsed time since the start.
+    pub fn offset(&self, elapsed: Duration) -> (i32, i32) {
+        if self.interval.is_zero() {
+            return (0, 0);
+        }
+        let tick = (elapsed.as_millis() / self.interval.as_millis().max(1)) as usize;
+        let (dx, dy) = PIXEL_SHIFT_CYCLE[tick % PIXEL_SHIFT_CYCLE.len()];
+        let magnitude = self.magnitude as i32;
+
+        (dx * magnitude, dy * magnitude)
+    }
+}
+
+/// Move an image by the given offset, keeping the image dimensions.
+///
+/// The revealed edges are filled by repeating the edge pixels of the image, which continues the
+/// panel background instead of showing a contrasting border.
+pub fn translate_image(image: &RgbaImage, dx: i32, dy: i32) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    if width == 0 || height == 0 {
+        return image.clone();
+    }
+
+    RgbaImage::from_fn(width, height, |x, y| {
+        let src_x = (x as i64 - dx as i64).clamp(0, width as i64 - 1) as u32;
+        let src_y = (y as i64 - dy as i64).clamp(0, height as i64 - 1) as u32;
+        *image.get_pixel(src_x, src_y)
+    })
+}
+
+/// Transition effect between two frames, e.g. when switching panels.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Transition {
+    /// Instant switch to the next frame.
+    #[default]
+    Cut,
+    /// Blend the previous frame into the next frame.
+    Crossfade,
+    /// Move the next frame in from the right, pushing the previous frame out to the left.
+    Slide,
+}
+
+impl Transition {
+    /// Get the intermediate frame of the transition at the given progress.
+    ///
+    /// # Arguments
+    ///
+    /// * `from`: previous frame.
+    /// * `to`: next frame with the same dimensions as the previous frame.
+    /// * `progress`: transition progress from 0.0 (previous frame) to 1.0 (next frame).
+    ///
+    /// returns: the intermediate frame with the dimensions of the next frame.
+    pub fn frame(self, from: &RgbaImage, to: &RgbaImage, progress: f32) -> RgbaImage {
+        let progress = progress.clamp(0.0, 1.0);
+        match self {
+            Transition::Cut => to.clone(),
+            Transition::Crossfade => RgbaImage::from_fn(to.width(), to.height(), |x, y| {
+                let to_pixel = to.get_pixel(x, y);
+                let Some(from_pixel) = from.get_pixel_checked(x, y) else {
+                    return *to_pixel;
+                };
+                Rgba(std::array::from_fn(|c| {
+                    let from = from_pixel[c] as f32;
+                    let to = to_pixel[c] as f32;
+                    (from + (to - from) * progress).round() as u8
+                }))
+            }),
+            Transition::Slide => {
+                let shift = (to.width() as f32 * progress).round() as i64;
+                let mut frame = RgbaImage::new(to.width(), to.height());
+                image::imageops::replace(&mut frame, from, -shift, 0);
+                image::imageops::replace(&mut frame, to, to.width() as i64 - shift, 0);
+                frame
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    #[test]
+    fn crossfade_blends_frames() {
+        let from = RgbaImage::from_pixel(4, 2, Rgba([255, 0, 0, 255]));
+        let to = RgbaImage::from_pixel(4, 2, Rgba([0, 0, 255, 255]));
+
+        let quarter = Transition::Crossfade.frame(&from, &to, 0.25);
+        let half = Transition::Crossfade.frame(&from, &to, 0.5);
+
+        assert!(quarter.pixels().all(|p| *p == Rgba([191, 0, 64, 255])));
+        assert!(half.pixels().all(|p| *p == Rgba([128, 0, 128, 255])));
+        assert_eq!(Transition::Crossfade.frame(&from, &to, 0.0), from);
+        assert_eq!(Transition::Crossfade.frame(&from, &to, 1.0), to);
+    }
+
+    #[test]
+    fn slide_moves_next_frame_in_from_the_right() {
+        let from = RgbaImage::from_pixel(4, 2, Rgba([255, 0, 0, 255]));
+        let to = RgbaImage::from_pixel(4, 2, Rgba([0, 0, 255, 255]));
+
+        let frame = Transition::Slide.frame(&from, &to, 0.25);
+
+        for y in 0..2 {
+            assert_eq!(*frame.get_pixel(2, y), Rgba([255, 0, 0, 255]));
+            assert_eq!(*frame.get_pixel(3, y), Rgba([0, 0, 255, 255]));
+        }
+        assert_eq!(Transition::Slide.frame(&from, &to, 1.0), to);
+    }
+
+    #[test]
+    fn cut_switches_instantly() {
+        let from = RgbaImage::from_pixel(4, 2, Rgba([255, 0, 0, 255]));
+        let to = RgbaImage::from_pixel(4, 2, Rgba([0, 0, 255, 255]));
+
+        assert_eq!(Transition::Cut.frame(&from, &to, 0.1), to);
+    }
+
+    #[test]
+    fn pixel_shift_offset_changes_at_interval() {
+        let shift = PixelShift {
+            magnitude: 2,
+            interval: Duration::from_secs(60),
+        };
+
+        assert_eq!(shift.offset(Duration::ZERO), (0, 0));
+        assert_eq!(shift.offset(Duration::from_secs(59)), (0, 0));
+        assert_eq!(shift.offset(Duration::from_secs(60)), (2, 0));
+        assert_eq!(shift.offset(Duration::from_secs(150)), (2, 2));
+        assert_eq!(shift.offset(Duration::from_secs(6 * 60)), (-2, -2));
+        assert_eq!(shift.offset(Duration::from_secs(8 * 60)), (0, 0));
+    }
+
+    #[test]
+    fn translate_image_repeats_edge_pixels() {
+        let image = RgbaImage::from_fn(6, 4, |x, y| Rgba([x as u8 * 40, y as u8 * 60, 255, 255]));
+
+        let translated = translate_image(&image, 2, -1);
+
+        assert_eq!(translated.dimensions(), (6, 4));
+        assert_eq!(translated.get_pixel(2, 0), image.get_pixel(0, 1));
+        assert_eq!(translated.get_pixel(5, 2), image.get_pixel(3, 3));
+        for y in 0..3 {
+            assert_eq!(translated.get_pixel(0, y), image.get_pixel(0, y + 1));
+            assert_eq!(translated.get_pixel(1, y), image.get_pixel(0, y + 1));
+        }
+        for x in 2..6 {
+            assert_eq!(translated.get_pixel(x, 3), image.get_pixel(x - 2, 3));
+        }
+    }
+
+    /// Test image with a sharp-edged diagonal pattern for interpolation comparisons.
+    fn pattern_image(width: u32, height: u32) -> RgbaImage {
+        RgbaImage::from_fn(width, height, |x, y| {
+            if (x / 3 + y / 5) % 2 == 0 {
+                Rgba([255, 255, 255, 255])
+            } else {
+                Rgba([0, 0, 0, 255])
+            }
+        })
+    }
+
+    #[test]
+    fn rotate_image_bicubic_differs_from_bilinear() {
+        let image = pattern_image(40, 20);
+
+        let bilinear = rotate_image(&image, 30, RotationQuality::Good, false);
+        let bicubic = rotate_image(&image, 30, RotationQuality::Best, false);
+
+        assert_eq!(bilinear.dimensions(), bicubic.dimensions());
+        assert_ne!(bilinear, bicubic);
+    }
+
+    #[test]
+    fn rotate_image_expand_increases_dimensions() {
+        let image = pattern_image(40, 10);
+
+        let clipped = rotate_image(&image, 45, RotationQuality::Good, false);
+        assert_eq!(clipped.dimensions(), (40, 10));
+
+        // bounding box of 40x10 rotated by 45°: (40 + 10) * cos(45°) = 35.36
+        let expanded = rotate_image(&image, 45, RotationQuality::Good, true);
+        assert_eq!(expanded.dimensions(), (40, 36));
+        let visible = |img: &RgbaImage| img.pixels().filter(|p| p[3] > 0).count();
+        assert!(visible(&expanded) > visible(&clipped));
+    }
+
+    #[test]
+    fn rotate_image_expand_keeps_right_angle_rotations() {
+        let image = pattern_image(40, 10);
+
+        assert_eq!(
+            rotate_image(&image, 90, RotationQuality::Best, true).dimensions(),
+            (10, 40)
+        );
+        assert_eq!(
+            rotate_image(&image, -180, RotationQuality::Best, true),
+            rotate_180_degrees(&image)
+        );
+    }
+
+    #[test]
+    fn list_images_cycles_in_filename_order() {
+        let dir = std::env::temp_dir().join(format!("asterctl-img-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("subdir.png")).unwrap();
+        for file in ["c.png", "a.jpg", "notes.txt", "b.bmp", "README"] {
+            fs::write(dir.join(file), b"").unwrap();
+        }
+
+        let images = list_images(&dir).unwrap();
+        let names: Vec<_> = images
+            .iter()
+            .cycle()
+            .take(5)
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+
+        assert_eq!(images.len(), 3);
+        assert_eq!(names, ["a.jpg", "b.bmp", "c.png", "a.jpg", "b.bmp"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn rgb565_test_data() -> (RgbImage, Vec<u8>) {
+        let expected = RgbImage::from_vec(
+            3,
+            2,
+            vec![
+                255, 0, 0, 0, 255, 0, 0, 0, 255, //
+                0, 0, 0, 255, 255, 255, 132, 130, 132,
+            ],
+        )
+        .unwrap();
+        let pixels = [0xF800u16, 0x07E0, 0x001F, 0x0000, 0xFFFF, 0x8410];
+        let data = pixels
+            .iter()
+            .flat_map(|pixel| pixel.to_le_bytes())
+            .collect();
+        (expected, data)
+    }
+
+    #[test]
+    fn load_rgb565_image_with_header() {
+        let (expected, pixels) = rgb565_test_data();
+        let path =
+            std::env::temp_dir().join(format!("asterctl-img-header-{}.565", std::process::id()));
+        let mut data = vec![3, 0, 2, 0];
+        data.extend(pixels);
+        fs::write(&path, data).unwrap();
+
+        let image = load_image(&path, None);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(image.unwrap().to_rgb8(), expected);
+    }
+
+    #[test]
+    fn load_rgb565_image_without_header() {
+        let (expected, data) = rgb565_test_data();
+        let path =
+            std::env::temp_dir().join(format!("asterctl-img-raw-{}.565", std::process::id()));
+        fs::write(&path, data).unwrap();
+
+        let image = load_image(&path, Some((3, 2)));
+        let invalid_size = load_rgb565_image(&path, Some((4, 4)));
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(image.unwrap().to_rgb8(), expected);
+        assert!(invalid_size.is_err());
+    }
+
+    #[test]
+    fn image_cache_loads_qoi_image_like_png() {
+        let dir =
+            std::env::temp_dir().join(format!("asterctl-img-qoi-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let image = pattern_image(5, 3);
+        image.save(dir.join("image.png")).unwrap();
+        image.save(dir.join("image.qoi")).unwrap();
+
+        let mut cache = ImageCache::new(&dir);
+        let png = cache.get("image.png", None).cloned();
+        let qoi = cache.get("image.qoi", None).cloned();
+        let listed = list_images(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(qoi.unwrap(), png.unwrap());
+        assert_eq!(listed.len(), 2);
+    }
+
+    #[test]
+    fn image_cache_retries_failed_load() {
+        let dir =
+            std::env::temp_dir().join(format!("asterctl-img-cache-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let image = RgbaImage::from_pixel(2, 2, Rgba([255, 0, 0, 255]));
+        image.save(dir.join("image.png")).unwrap();
+        let png = fs::read(dir.join("image.png")).unwrap();
+        // simulate a partially written file
+        fs::write(dir.join("image.png"), &png[..png.len() / 2]).unwrap();
+        let mut cache = ImageCache::new(&dir);
+        cache.set_retry_delay(Duration::ZERO);
+
+        assert!(cache.get("image.png", None).is_none());
+
+        fs::write(dir.join("image.png"), &png).unwrap();
+        assert_eq!(cache.get("image.png", None), Some(&image));
+
+        // image is cached and no longer read from disk
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(cache.get("image.png", None), Some(&image));
+    }
+
+    #[test]
+    fn image_cache_caches_tinted_image_per_color() {
+        let dir =
+            std::env::temp_dir().join(format!("asterctl-img-tint-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let image = RgbaImage::from_pixel(2, 2, Rgba([255, 255, 255, 128]));
+        image.save(dir.join("image.png")).unwrap();
+        let mut cache = ImageCache::new(&dir);
+
+        let red = cache
+            .get_tinted("image.png", None, Rgb([255, 0, 0]))
+            .cloned();
+        let blue = cache
+            .get_tinted("image.png", None, Rgb([0, 0, 255]))
+            .cloned();
+        // tinted images are cached and no longer read from disk
+        fs::remove_dir_all(&dir).unwrap();
+        let cached_red = cache
+            .get_tinted("image.png", None, Rgb([255, 0, 0]))
+            .cloned();
+
+        assert_eq!(red.unwrap().get_pixel(0, 0), &Rgba([255, 0, 0, 128]));
+        assert_eq!(blue.unwrap().get_pixel(0, 0), &Rgba([0, 0, 255, 128]));
+        assert_eq!(cached_red, Some(tint(&image, Rgb([255, 0, 0]))));
+        assert_eq!(cache.tinted.len(), 2);
+    }
+
+    #[test]
+    fn image_cache_waits_for_retry_delay() {
+        let mut cache = ImageCache::new("/does/not/exist");
+
+        assert!(cache.get("image.png", None).is_none());
+        let failed_at = cache
+            .failed
+            .get(Path::new("/does/not/exist/image.png"))
+            .copied();
+        assert!(cache.get("image.png", None).is_none());
+
+        assert!(failed_at.is_some());
+        assert_eq!(
+            cache
+                .failed
+                .get(Path::new("/does/not/exist/image.png"))
+                .copied(),
+            failed_at
+        );
+    }
+
+    #[test]
+    fn tint_white_image_to_red() {
+        let image = RgbaImage::from_fn(4, 2, |x, _| Rgba([255, 255, 255, (x * 80) as u8]));
+
+        let tinted = tint(&image, Rgb([255, 0, 0]));
+
+        for (x, _, pixel) in tinted.enumerate_pixels() {
+            assert_eq!(*pixel, Rgba([255, 0, 0, (x * 80) as u8]));
+        }
+    }
+
+    #[test]
+    fn tint_scales_gray_pixels() {
+        let image = RgbaImage::from_pixel(1, 1, Rgba([128, 0, 255, 255]));
+
+        let tinted = tint(&image, Rgb([255, 128, 64]));
+
+        assert_eq!(*tinted.get_pixel(0, 0), Rgba([128, 0, 64, 255]));
+    }
+}
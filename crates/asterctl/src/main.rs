@@ -5,20 +5,32 @@
 #![deny(unsafe_code)]
 
 use asterctl::cfg::{MonitorConfig, Panel, load_custom_panel};
-use asterctl::render::PanelRenderer;
-use asterctl::sensors::{read_filter_file, read_key_value_file, start_file_slurper};
+use asterctl::font::FontHandler;
+use asterctl::img::{PixelShift, Transition};
+use asterctl::render::{ImageProcessingError, PanelRenderer};
+use asterctl::sensors::{
+    IdleDetector, MockSensors, SensorActivity, StaleWatchdog, read_filter_file,
+    read_key_value_file, read_lock, read_mapping_file, read_path, start_file_slurper,
+    start_mock_sensors,
+};
+#[cfg(feature = "sqlite")]
+use asterctl::sensors::{SQLITE_DEFAULT_QUERY, SqliteSource, read_sqlite, start_sqlite_poller};
 use asterctl::{cfg, img};
-use asterctl_lcd::{AooScreen, AooScreenBuilder, DISPLAY_SIZE};
+use asterctl_lcd::{
+    AooScreen, AooScreenBuilder, DISPLAY_SIZE, DisplayModel, SerialPortEntry, TRACE_TARGET,
+    list_serial_ports,
+};
 
-use anyhow::anyhow;
-use clap::Parser;
+use anyhow::{Context, anyhow};
+use clap::{Parser, Subcommand};
 use env_logger::Env;
-use log::{debug, error, info};
+use image::{ImageFormat, Rgb, RgbaImage};
+use log::{LevelFilter, debug, error, info, warn};
 use regex::Regex;
 use std::collections::HashMap;
-use std::fs;
+use std::fs::File;
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
-use std::str::FromStr;
 use std::sync::{Arc, RwLock};
 use std::thread::sleep;
 use std::time::{Duration, Instant};
@@ -35,6 +47,34 @@ struct Args {
     #[arg(short, long)]
     usb: Option<String>,
 
+    /// USB serial number of the display, if multiple displays with the same "vid:pid" are attached.
+    ///
+    /// Ignored if the `--device` option is specified.
+    #[arg(long)]
+    serial_number: Option<String>,
+
+    /// Display size in `WIDTHxHEIGHT` pixels, e.g. for a modded or non-standard panel.
+    ///
+    /// Default: size of the detected display model, or 960x376.
+    #[arg(long, value_parser = parse_display_size)]
+    display_size: Option<(u32, u32)>,
+
+    /// List the available serial ports with their USB ids, product and serial number, and exit.
+    #[arg(long)]
+    list_ports: bool,
+
+    /// Print the `--list-ports` output as JSON.
+    #[arg(long, requires = "list_ports")]
+    json: bool,
+
+    /// Print the JSON schema of the configuration file as interpreted by asterctl, and exit.
+    ///
+    /// AOOSTAR-X fields which are not used are listed in the `x-ignored` property of the
+    /// containing object. Requires the `schema` feature.
+    #[cfg(feature = "schema")]
+    #[arg(long)]
+    dump_schema: bool,
+
     /// Switch display on and exit. This will show the last displayed image.
     #[arg(long)]
     on: bool,
@@ -44,9 +84,26 @@ struct Args {
     off: bool,
 
     /// Image to display, other sizes than 960x376 will be scaled.
+    ///
+    /// If a directory is specified, all images in the directory are displayed in a slideshow.
     #[arg(short, long)]
     image: Option<String>,
 
+    /// Fill the display with a solid color in RRGGBB hex notation and exit, e.g. 000000 for black.
+    #[arg(long, value_parser = parse_rgb_color)]
+    fill: Option<Rgb<u8>>,
+
+    /// Save the last frame sent to the display as image file after the `--image` or `--fill` command.
+    ///
+    /// This is the frame sent by `asterctl` in the reduced display color depth, not a readback from
+    /// the display.
+    #[arg(long)]
+    capture: Option<PathBuf>,
+
+    /// Image switch time in seconds of the `--image` directory slideshow.
+    #[arg(long, default_value_t = 5.0, value_parser = parse_interval)]
+    switch_time: f32,
+
     /// AOOSTAR-X json configuration file to parse.
     ///
     /// The configuration file will be loaded from the `config_dir` directory if no full path is
@@ -62,17 +119,128 @@ struct Args {
 
     /// Configuration directory containing configuration files and background images
     /// specified in the `config` file.
-    #[arg(long, default_value_t = String::from("cfg"))]
+    #[arg(long, env = "ASTERCTL_CONFIG_DIR", default_value_t = String::from("cfg"))]
     config_dir: String, // default_value_t requires Display trait which PathBuf does not implement
 
     /// Font directory for fonts specified in the `config` file.
-    #[arg(long, default_value_t = String::from("fonts"))]
+    #[arg(long, env = "ASTERCTL_FONT_DIR", default_value_t = String::from("fonts"))]
     font_dir: String,
 
+    /// Additional font directory, searched after `--font-dir` if a font is not found.
+    ///
+    /// Can be specified multiple times, directories are searched in the given order.
+    #[arg(long)]
+    extra_font_dir: Vec<PathBuf>,
+
+    /// Default font file for text sensors without a font family or with a missing font, e.g. a
+    /// font with CJK coverage.
+    ///
+    /// The embedded DejaVuSans font is used if not specified.
+    #[arg(long)]
+    default_font: Option<PathBuf>,
+
     /// Single sensor value input file or directory for multiple sensor input files.
-    #[arg(long, default_value_t = String::from("cfg/sensors"))]
+    #[arg(long, env = "ASTERCTL_SENSOR_PATH", default_value_t = String::from("cfg/sensors"))]
     sensor_path: String,
 
+    /// Demo mode: generate changing mock values for known sensor labels instead of reading the
+    /// `--sensor-path`, e.g. a CPU usage sine wave or drifting temperatures.
+    #[arg(long)]
+    mock_sensors: bool,
+
+    /// Maximum time in seconds to wait for the sensor path at startup if it doesn't exist yet.
+    #[arg(long, default_value_t = 1.0, value_parser = parse_seconds)]
+    sensor_wait: f32,
+
+    /// Additional SQLite database file to query sensor values from, merged with the sensor values
+    /// of the `--sensor-path`.
+    #[cfg(feature = "sqlite")]
+    #[arg(long)]
+    sqlite: Option<PathBuf>,
+
+    /// Query of the `--sqlite` database, returning the sensor label in the first and the sensor
+    /// value in the second column.
+    #[cfg(feature = "sqlite")]
+    #[arg(long, default_value_t = String::from(SQLITE_DEFAULT_QUERY), requires = "sqlite")]
+    sqlite_query: String,
+
+    /// Time in seconds between two queries of the `--sqlite` database.
+    #[cfg(feature = "sqlite")]
+    #[arg(long, default_value_t = 5.0, requires = "sqlite", value_parser = parse_interval)]
+    sqlite_interval: f32,
+
+    /// Maximum time in seconds without any sensor value update before the sensor values are stale.
+    ///
+    /// Stale sensor values are indicated with a warning banner or the `--stale-panel`.
+    #[arg(long, value_parser = parse_interval)]
+    stale_timeout: Option<f32>,
+
+    /// Custom panel directory to show instead of the active panels while the sensor values are
+    /// stale. Requires the `--stale-timeout` option.
+    #[arg(long, requires = "stale_timeout")]
+    stale_panel: Option<PathBuf>,
+
+    /// Burn-in protection: shift the sensor panel frame by up to n pixels in each direction.
+    ///
+    /// The frame is moved around its original position in the `--pixel-shift-interval`. The revealed
+    /// edges repeat the edge pixels of the frame.
+    #[arg(long)]
+    pixel_shift: Option<u32>,
+
+    /// Time in seconds between two pixel shifts of the `--pixel-shift` option.
+    #[arg(long, default_value_t = 300.0, requires = "pixel_shift", value_parser = parse_interval)]
+    pixel_shift_interval: f32,
+
+    /// Power saving: sensor label of an activity value, e.g. the CPU usage, to switch off the
+    /// display while the system is idle.
+    ///
+    /// The display is switched off if the sensor value stays below the `--idle-threshold` for the
+    /// `--idle-timeout`, and switched on again with the first value at or above the threshold.
+    #[arg(long, requires = "idle_threshold")]
+    idle_sensor: Option<String>,
+
+    /// Activity threshold of the `--idle-sensor` value.
+    #[arg(long, requires = "idle_sensor")]
+    idle_threshold: Option<f64>,
+
+    /// Time in seconds the `--idle-sensor` value must stay below the threshold before the display
+    /// is switched off.
+    #[arg(long, default_value_t = 300.0, requires = "idle_sensor", value_parser = parse_seconds)]
+    idle_timeout: f32,
+
+    /// Show an error frame with the panel name and the error if a sensor panel fails to render.
+    ///
+    /// Without this option, the last frame remains on the display.
+    #[arg(long)]
+    error_frame: bool,
+
+    /// Transition effect when switching sensor panels: `cut`, `crossfade` or `slide`.
+    ///
+    /// Crossfade and slide transitions send intermediate full frames, which take longer to send
+    /// than partial frame updates.
+    #[arg(long, default_value = "cut", value_parser = parse_transition)]
+    transition: Transition,
+
+    /// Duration of the `--transition` effect in seconds.
+    #[arg(long, default_value_t = 1.0, value_parser = parse_seconds)]
+    transition_time: f32,
+
+    /// Show only the given sensor panel instead of the active panel rotation: panel name, id or
+    /// 1-based panel index. Requires the `--config` option.
+    #[arg(long, requires = "config")]
+    panel: Option<String>,
+
+    /// Render the sensor panel once with the current sensor values, send it to the display and
+    /// exit. The frame remains on the display. Requires the `--config` option.
+    ///
+    /// Without `--panel`, the first active panel is shown.
+    #[arg(long, requires = "config")]
+    once: bool,
+
+    /// Show all panels in order if the configuration file doesn't define valid active panels.
+    #[arg(long)]
+    fallback_all_panels: bool,
+
     /// Sensor identifier mapping file. Ignored if the file does not exist.
     ///
     /// The configuration file will be loaded from the `config_dir` directory if no full path is
@@ -80,10 +248,50 @@ struct Args {
     #[arg(long, default_value_t = String::from("sensor-mapping.cfg"))]
     sensor_mapping: String,
 
-    /// Switch off display n seconds after loading image or running demo.
+    /// Global value alias file with display texts of raw text sensor values, e.g. `up: Online`.
+    /// Ignored if the file does not exist.
+    ///
+    /// The file will be loaded from the `config_dir` directory if no full path is specified.
+    #[arg(long, default_value_t = String::from("value-aliases.cfg"))]
+    value_aliases: String,
+
+    /// Switch off display n seconds after the display command finished.
+    ///
+    /// Applies to `--image`, `--fill`, and the image slideshow or sensor panel mode limited with
+    /// the `--run-for` option.
     #[arg(short, long)]
     off_after: Option<u32>,
 
+    /// Stop the image slideshow or sensor panel mode after n seconds. Default: run until stopped.
+    #[arg(long, value_parser = parse_interval)]
+    run_for: Option<f32>,
+
+    /// Use the sensor `value` field of the configuration file if no sensor value is available.
+    #[arg(long)]
+    use_config_values: bool,
+
+    /// Disable the frame cache and always send full frames to the display.
+    ///
+    /// Only changed image areas are sent by default. This increases the transferred data and is
+    /// intended for debugging display update issues.
+    #[arg(long)]
+    no_cache: bool,
+
+    /// Treat pixels as unchanged for the frame cache if each RGB 565 color component differs by
+    /// at most n from the last sent frame. 0 compares the pixels exactly.
+    ///
+    /// Avoids resending image areas with nearly identical pixels, e.g. from tiny anti-aliasing
+    /// changes around text, at the cost of a minimal color accuracy.
+    #[arg(long, default_value_t = 0)]
+    cache_tolerance: u8,
+
+    /// Verify each sent frame with an acknowledge response of the display and resend the frame
+    /// if it is not acknowledged.
+    ///
+    /// Requires a display firmware responding to each frame.
+    #[arg(long)]
+    verify_frames: bool,
+
     /// Test mode: only write to the display without checking response.
     #[arg(short, long)]
     write_only: bool,
@@ -92,25 +300,174 @@ struct Args {
     #[arg(short, long)]
     save: bool,
 
+    /// Image format for saving images with the `--save` option: png, jpeg, bmp.
+    #[arg(long, default_value_t = String::from("png"))]
+    save_format: String,
+
+    /// Optional filename template without file extension for saving images with the `--save` option.
+    ///
+    /// Supported placeholders: {type}, {panel}, {label}, {suffix}, {timestamp}.
+    #[arg(long)]
+    save_name: Option<String>,
+
+    /// Preview mode: render the first active panel to the given image file whenever sensor values
+    /// change, without using the display. Requires the `--config` option.
+    #[arg(long, requires = "config")]
+    render_on_change: Option<PathBuf>,
+
+    /// Test mode: draw the bounding box and anchor point of each sensor on the saved panel images for
+    /// layout debugging.
+    #[arg(long)]
+    debug_boxes: bool,
+
+    /// Test mode: log the render duration of each sensor after rendering a panel.
+    #[arg(long)]
+    profile: bool,
+
     /// Simulate serial port for testing and development, `--device` and `--usb` options are ignored.
     #[arg(long)]
     simulate: bool,
+
+    /// Log all protocol messages sent to the display: frame headers, chunk offsets and skipped
+    /// chunks. Use with `--simulate` for a dry run without a display.
+    #[arg(long)]
+    trace: bool,
+
+    /// Write the protocol trace to a file, e.g. `frames.trace`, without logging the messages.
+    #[arg(long)]
+    trace_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-fn main() -> anyhow::Result<()> {
-    env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Explain how a single sensor is resolved, formatted and positioned without using the display.
+    ///
+    /// Requires the `--config` option.
+    ExplainSensor {
+        /// Panel name, id or 1-based panel index.
+        panel: String,
+        /// Sensor label identifier in the panel configuration.
+        label: String,
+    },
+}
 
+fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
+    let mut logger = env_logger::Builder::from_env(Env::default().default_filter_or("info"));
+    if args.trace {
+        logger.filter_module(TRACE_TARGET, LevelFilter::Trace);
+    }
+    logger.init();
+
+    if args.list_ports {
+        let ports = list_serial_ports()?;
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&ports_json(&ports))?);
+        } else {
+            print!("{}", ports_table(&ports));
+        }
+        return Ok(());
+    }
+
+    #[cfg(feature = "schema")]
+    if args.dump_schema {
+        println!("{}", serde_json::to_string_pretty(&cfg::config_schema())?);
+        return Ok(());
+    }
+
+    if let Some(Command::ExplainSensor { panel, label }) = &args.command {
+        let config = args
+            .config
+            .as_ref()
+            .ok_or_else(|| anyhow!("The --config option is required to explain a sensor"))?;
+        let cfg_dir = PathBuf::from(&args.config_dir);
+        let mapping_cfg = PathBuf::from(&args.sensor_mapping);
+        let cfg = load_configuration(config, &cfg_dir, args.panels.clone(), &mapping_cfg)?;
+
+        let mut values = HashMap::new();
+        read_path(&args.sensor_path, &mut values, cfg.sensor_filter.as_deref())?;
+
+        let mut renderer =
+            configure_renderer(&args, &cfg, args.display_size.unwrap_or(DISPLAY_SIZE))?;
+
+        let panel_cfg = cfg
+            .find_panel(panel)
+            .ok_or_else(|| anyhow!("Panel not found: {panel}"))?;
+        let explanation = renderer
+            .explain_sensor(panel_cfg, label, &values)
+            .ok_or_else(|| anyhow!("Sensor {label} not found in panel {panel}"))?;
+        print!("{explanation}");
+
+        return Ok(());
+    }
+
+    if let Some(output) = &args.render_on_change {
+        let config = args
+            .config
+            .as_ref()
+            .ok_or_else(|| anyhow!("The --config option is required to render on change"))?;
+        let cfg_dir = PathBuf::from(&args.config_dir);
+        let mapping_cfg = PathBuf::from(&args.sensor_mapping);
+        let mut cfg = load_configuration(config, &cfg_dir, args.panels.clone(), &mapping_cfg)?;
+        cfg.set_fallback_all_panels(args.fallback_all_panels);
+        select_panel(&mut cfg, args.panel.as_deref())?;
+
+        let mut renderer =
+            configure_renderer(&args, &cfg, args.display_size.unwrap_or(DISPLAY_SIZE))?;
+
+        let values = Arc::new(RwLock::new(HashMap::new()));
+        let sensor_input = SensorInput {
+            path: PathBuf::from(&args.sensor_path),
+            wait: Duration::from_secs_f32(args.sensor_wait),
+            mock: args.mock_sensors,
+            #[cfg(feature = "sqlite")]
+            sqlite: sqlite_source(
+                args.sqlite.clone(),
+                args.sqlite_query.clone(),
+                args.sqlite_interval,
+            ),
+        };
+        let activity = sensor_input.start(&cfg, values.clone())?;
+        let panel = cfg.get_next_active_panel().ok_or(anyhow!(
+            "No active panel. Use --fallback-all-panels to show all panels"
+        ))?;
+
+        return run_render_on_change(&mut renderer, panel, &values, &activity, output);
+    }
+
     // initialize display with given UART port parameter
     let mut builder = AooScreenBuilder::new();
     builder.no_init_check(args.write_only);
+    builder.enable_cache(!args.no_cache);
+    builder.cache_tolerance(args.cache_tolerance);
+    builder.verify_frames(args.verify_frames);
+    if let Some(serial_number) = &args.serial_number {
+        builder.serial_number(serial_number);
+    }
+    if let Some((width, height)) = args.display_size {
+        builder.model(DisplayModel::Custom { width, height });
+    }
+    if args.trace || args.trace_file.is_some() {
+        let writer = match &args.trace_file {
+            Some(path) => {
+                let file = File::create(path)
+                    .with_context(|| format!("Error creating trace file {path:?}"))?;
+                Some(Box::new(BufWriter::new(file)) as Box<dyn Write + Send>)
+            }
+            None => None,
+        };
+        builder.trace(writer);
+    }
     let mut screen = if args.simulate {
         builder.simulate()?
-    } else if let Some(device) = args.device {
-        builder.open_device(&device)?
-    } else if let Some(usb) = args.usb {
-        builder.open_usb_id(&usb)?
+    } else if let Some(device) = &args.device {
+        builder.open_device(device)?
+    } else if let Some(usb) = &args.usb {
+        builder.open_usb_id(usb)?
     } else {
         builder.open_default()?
     };
@@ -127,51 +484,239 @@ fn main() -> anyhow::Result<()> {
     // switch on screen for remaining commands
     screen.init()?;
 
-    if let Some(config) = args.config {
+    let run_for = args.run_for.map(Duration::from_secs_f32);
+
+    if let Some(color) = args.fill {
+        info!("Filling display with color {color:?}");
+        screen.fill(color)?;
+        if let Some(capture) = &args.capture {
+            capture_frame(&screen, capture)?;
+        }
+    } else if let Some(config) = &args.config {
         info!("Starting sensor panel mode");
-        let img_save_path = if args.save {
-            let img_save_path = PathBuf::from("out");
-            fs::create_dir_all(&img_save_path)?;
-            Some(img_save_path)
-        } else {
-            None
+        let cfg_dir = PathBuf::from(&args.config_dir);
+        let mapping_cfg = PathBuf::from(&args.sensor_mapping);
+        let mut cfg = load_configuration(config, &cfg_dir, args.panels.clone(), &mapping_cfg)?;
+        cfg.set_fallback_all_panels(args.fallback_all_panels);
+        select_panel(&mut cfg, args.panel.as_deref())?;
+
+        let renderer = configure_renderer(&args, &cfg, screen.size())?;
+
+        let stale_monitor = match args.stale_timeout {
+            Some(timeout) => Some(StaleMonitor {
+                watchdog: StaleWatchdog::new(Duration::from_secs_f32(timeout)),
+                panel: args.stale_panel.map(load_custom_panel).transpose()?,
+            }),
+            None => None,
         };
+        let pixel_shift = args.pixel_shift.map(|magnitude| PixelShift {
+            magnitude,
+            interval: Duration::from_secs_f32(args.pixel_shift_interval),
+        });
 
-        let cfg_dir = PathBuf::from(args.config_dir);
-        let font_dir = PathBuf::from(args.font_dir);
-        let sensor_path = PathBuf::from(args.sensor_path);
-        let mapping_cfg = PathBuf::from(args.sensor_mapping);
-        let cfg = load_configuration(&config, &cfg_dir, args.panels, &mapping_cfg)?;
-        run_sensor_panel(
-            &mut screen,
-            cfg,
-            cfg_dir,
-            font_dir,
-            sensor_path,
-            img_save_path,
-        )?;
-        return Ok(());
-    }
+        let idle_detector = args
+            .idle_sensor
+            .zip(args.idle_threshold)
+            .map(|(label, threshold)| {
+                IdleDetector::new(label, threshold, Duration::from_secs_f32(args.idle_timeout))
+            });
 
-    if let Some(image) = args.image {
+        let sensor_input = SensorInput {
+            path: PathBuf::from(args.sensor_path),
+            wait: Duration::from_secs_f32(args.sensor_wait),
+            mock: args.mock_sensors,
+            #[cfg(feature = "sqlite")]
+            sqlite: sqlite_source(args.sqlite, args.sqlite_query, args.sqlite_interval),
+        };
+        if args.once {
+            run_panel_once(&mut screen, cfg, renderer, sensor_input, args.error_frame)?;
+        } else {
+            run_sensor_panel(
+                &mut screen,
+                cfg,
+                renderer,
+                sensor_input,
+                PanelOptions {
+                    stale_monitor,
+                    pixel_shift,
+                    idle_detector,
+                    save_images: args.save,
+                    run_for,
+                    error_frame: args.error_frame,
+                    transition: PanelTransition {
+                        effect: args.transition,
+                        duration: Duration::from_secs_f32(args.transition_time),
+                    },
+                },
+            )?;
+        }
+    } else if let Some(image) = args.image.as_deref()
+        && Path::new(image).is_dir()
+    {
+        let switch_time = Duration::from_secs_f32(args.switch_time);
+        run_slideshow(&mut screen, image, switch_time, run_for)?;
+    } else if let Some(image) = args.image {
         info!("Loading and displaying background image {image}...");
-        let rgb_img = img::load_image(&image, Some(DISPLAY_SIZE))?.to_rgb8();
+        let rgb_img = img::load_image(&image, Some(screen.size()))?.to_rgb8();
         let timestamp = Instant::now();
         screen.send_image(&rgb_img)?;
         debug!("Image sent in {}ms", timestamp.elapsed().as_millis());
-    }
 
-    if let Some(off) = args.off_after {
-        info!("Switching off display in {off}s");
-        sleep(Duration::from_secs(off as u64));
-        screen.off()?;
+        if let Some(capture) = &args.capture {
+            capture_frame(&screen, capture)?;
+        }
     }
 
+    let off_after = args.off_after.map(|off| Duration::from_secs(off as u64));
+    switch_off_after(&mut screen, off_after)?;
+
     info!("Bye bye!");
 
     Ok(())
 }
 
+/// Switch off the display after the optional `--off-after` delay, once the display command
+/// finished.
+fn switch_off_after(screen: &mut AooScreen, off_after: Option<Duration>) -> anyhow::Result<()> {
+    if let Some(off_after) = off_after {
+        screen.off_after(off_after)?;
+    }
+
+    Ok(())
+}
+
+/// SQLite database sensor value source of the `--sqlite` option.
+#[cfg(feature = "sqlite")]
+fn sqlite_source(path: Option<PathBuf>, query: String, interval: f32) -> Option<SqliteSource> {
+    path.map(|path| SqliteSource {
+        path,
+        query,
+        interval: Duration::from_millis((interval * 1000.0) as u64),
+    })
+}
+
+/// Format a USB vendor or product id in hex notation, as used by the `--usb` option.
+fn usb_id(id: Option<u16>) -> Option<String> {
+    id.map(|id| format!("{id:04x}"))
+}
+
+/// Serial ports as JSON array with the `name`, `vid`, `pid`, `product` and `serial` fields.
+///
+/// The USB ids are in hex notation, unknown values are `null`.
+fn ports_json(ports: &[SerialPortEntry]) -> serde_json::Value {
+    ports
+        .iter()
+        .map(|port| {
+            serde_json::json!({
+                "name": port.name,
+                "vid": usb_id(port.vid),
+                "pid": usb_id(port.pid),
+                "product": port.product,
+                "serial": port.serial_number,
+            })
+        })
+        .collect()
+}
+
+/// Serial ports as human-readable table.
+fn ports_table(ports: &[SerialPortEntry]) -> String {
+    let rows: Vec<[String; 4]> = ports
+        .iter()
+        .map(|port| {
+            let usb = match (usb_id(port.vid), usb_id(port.pid)) {
+                (Some(vid), Some(pid)) => format!("{vid}:{pid}"),
+                _ => "-".to_string(),
+            };
+            [
+                port.name.clone(),
+                usb,
+                port.product.clone().unwrap_or_else(|| "-".into()),
+                port.serial_number.clone().unwrap_or_else(|| "-".into()),
+            ]
+        })
+        .collect();
+    let header = ["PORT", "VID:PID", "PRODUCT", "SERIAL"].map(String::from);
+    let widths: Vec<usize> = (0..header.len())
+        .map(|col| {
+            std::iter::once(&header)
+                .chain(&rows)
+                .map(|row| row[col].chars().count())
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let mut table = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{value:width$}"))
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    }
+    table
+}
+
+/// Parse a panel transition effect name.
+fn parse_transition(value: &str) -> Result<Transition, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "cut" => Ok(Transition::Cut),
+        "crossfade" => Ok(Transition::Crossfade),
+        "slide" => Ok(Transition::Slide),
+        _ => Err(format!(
+            "invalid transition '{value}', expected cut, crossfade or slide"
+        )),
+    }
+}
+
+/// Parse a positive time interval in seconds.
+fn parse_interval(value: &str) -> Result<f32, String> {
+    match value.trim().parse::<f32>() {
+        Ok(seconds) if seconds > 0.0 && seconds.is_finite() => Ok(seconds),
+        _ => Err(format!(
+            "invalid interval '{value}', expected a positive number of seconds"
+        )),
+    }
+}
+
+/// Parse a non-negative time in seconds.
+fn parse_seconds(value: &str) -> Result<f32, String> {
+    match value.trim().parse::<f32>() {
+        Ok(seconds) if seconds >= 0.0 && seconds.is_finite() => Ok(seconds),
+        _ => Err(format!(
+            "invalid time '{value}', expected a non-negative number of seconds"
+        )),
+    }
+}
+
+/// Parse a display size in `WIDTHxHEIGHT` notation.
+fn parse_display_size(value: &str) -> Result<(u32, u32), String> {
+    let invalid = || format!("invalid display size '{value}', expected WIDTHxHEIGHT");
+    let (width, height) = value.split_once(['x', 'X']).ok_or_else(invalid)?;
+    let width = width.trim().parse::<u32>().map_err(|_| invalid())?;
+    let height = height.trim().parse::<u32>().map_err(|_| invalid())?;
+    if width == 0 || height == 0 {
+        return Err(invalid());
+    }
+    Ok((width, height))
+}
+
+/// Parse a color in `RRGGBB` hex notation with an optional `#` prefix.
+fn parse_rgb_color(value: &str) -> Result<Rgb<u8>, String> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!(
+            "invalid color '{value}', expected RRGGBB hex notation"
+        ));
+    }
+
+    let channel =
+        |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).map_err(|e| e.to_string());
+    Ok(Rgb([channel(0)?, channel(2)?, channel(4)?]))
+}
+
 fn load_configuration<P: AsRef<Path>>(
     config: P,
     config_dir: P,
@@ -201,7 +746,7 @@ fn load_configuration<P: AsRef<Path>>(
     };
     if mapping_cfg.is_file() {
         let mut mapping = HashMap::new();
-        read_key_value_file(&mapping_cfg, &mut mapping, None)?;
+        read_mapping_file(&mapping_cfg, &mut mapping)?;
         cfg.set_sensor_mapping(mapping);
     } else {
         info!("Sensor mapping file {mapping_cfg:?} not found");
@@ -212,6 +757,70 @@ fn load_configuration<P: AsRef<Path>>(
     Ok(cfg)
 }
 
+/// Create a panel renderer for the given display size with the rendering options of the command
+/// line arguments and the configuration setup.
+fn configure_renderer(
+    args: &Args,
+    cfg: &MonitorConfig,
+    size: (u32, u32),
+) -> anyhow::Result<PanelRenderer> {
+    let cfg_dir = Path::new(&args.config_dir);
+    let mut renderer = PanelRenderer::new(size, &args.font_dir, cfg_dir);
+    for font_dir in &args.extra_font_dir {
+        renderer.add_font_dir(font_dir);
+    }
+    if let Some(font) = &args.default_font {
+        renderer.set_default_font(FontHandler::load_font_file(font)?);
+    }
+    renderer.set_number_format(cfg.setup.number_format());
+    renderer.set_default_digits(
+        cfg.setup.default_integer_digits,
+        cfg.setup.default_decimal_digits,
+    );
+    renderer.set_use_config_values(args.use_config_values);
+    renderer.set_value_aliases(load_value_aliases(cfg_dir, &args.value_aliases)?);
+    renderer.set_debug_boxes(args.debug_boxes);
+    renderer.set_profile(args.profile);
+    if args.save {
+        let img_save_path = PathBuf::from("out");
+        let save_format = ImageFormat::from_extension(&args.save_format)
+            .ok_or_else(|| anyhow!("Unsupported image format: {}", args.save_format))?;
+
+        renderer.set_img_save_path(img_save_path);
+        renderer.set_img_save_format(save_format);
+        if let Some(save_name) = &args.save_name {
+            renderer.set_img_name_template(save_name);
+        }
+        renderer.set_save_render_img(true);
+        // renderer.set_save_processed_pic(true);
+        // renderer.set_save_progress_layer(true);
+    }
+
+    Ok(renderer)
+}
+
+/// Load the global value alias file. A missing file is ignored.
+fn load_value_aliases<P: AsRef<Path>>(
+    config_dir: &Path,
+    value_aliases: P,
+) -> anyhow::Result<HashMap<String, String>> {
+    let value_aliases = value_aliases.as_ref();
+    let alias_cfg = if value_aliases.is_absolute() {
+        value_aliases.to_path_buf()
+    } else {
+        config_dir.join(value_aliases)
+    };
+
+    let mut aliases = HashMap::new();
+    if alias_cfg.is_file() {
+        read_key_value_file(&alias_cfg, &mut aliases, None)?;
+    } else {
+        info!("Value alias file {alias_cfg:?} not found");
+    }
+
+    Ok(aliases)
+}
+
 fn load_sensor_filter(mapping_cfg: &Path) -> anyhow::Result<Option<Vec<Regex>>> {
     if let Some(parent) = mapping_cfg.parent()
         && let Some(file_stem) = mapping_cfg.file_stem()
@@ -232,65 +841,419 @@ fn load_sensor_filter(mapping_cfg: &Path) -> anyhow::Result<Option<Vec<Regex>>>
     Ok(None)
 }
 
-fn run_sensor_panel<B: Into<PathBuf>>(
+/// Save the last frame sent to the display as image file.
+fn capture_frame(screen: &AooScreen, path: &Path) -> anyhow::Result<()> {
+    let frame = screen
+        .last_frame()
+        .ok_or_else(|| anyhow!("No frame available to capture, the frame cache is required"))?;
+    frame
+        .save(path)
+        .with_context(|| format!("Error saving captured frame to {path:?}"))?;
+    info!("Captured last frame to {path:?}");
+
+    Ok(())
+}
+
+/// Show all images of a directory in an endless slideshow, or until the optional `run_for`
+/// duration elapsed.
+fn run_slideshow<P: AsRef<Path>>(
     screen: &mut AooScreen,
-    mut cfg: MonitorConfig,
-    config_dir: B,
-    font_dir: B,
-    sensor_path: B,
-    img_save_path: Option<B>,
+    image_dir: P,
+    switch_time: Duration,
+    run_for: Option<Duration>,
 ) -> anyhow::Result<()> {
-    let font_dir = font_dir.into();
-    let config_dir = config_dir.into();
-    let img_save_path = img_save_path.map(|p| p.into());
+    let image_dir = image_dir.as_ref();
+    let images = img::list_images(image_dir)?;
+    if images.is_empty() {
+        return Err(anyhow!("No images found in {image_dir:?}"));
+    }
 
-    let mut renderer = PanelRenderer::new(DISPLAY_SIZE, &font_dir, &config_dir);
-    if let Some(img_save_path) = &img_save_path {
-        renderer.set_img_save_path(img_save_path);
-        renderer.set_save_render_img(true);
-        // renderer.set_save_processed_pic(true);
-        // renderer.set_save_progress_layer(true);
+    info!(
+        "Starting slideshow with {} images from {image_dir:?}",
+        images.len()
+    );
+    let start_time = Instant::now();
+    for image in images.iter().cycle() {
+        let switch_start_time = Instant::now();
+
+        info!("Displaying image {image:?}");
+        match img::load_image(image, Some(screen.size())) {
+            Ok(img) => screen.send_image(&img.to_rgb8())?,
+            Err(e) => error!("Error loading image {image:?}: {e}"),
+        }
+
+        let elapsed = switch_start_time.elapsed();
+        if switch_time > elapsed {
+            sleep(switch_time - elapsed);
+        }
+
+        if is_run_time_elapsed(start_time, run_for) {
+            info!("Stopping slideshow after {:?}", start_time.elapsed());
+            break;
+        }
     }
 
-    let sensor_values: Arc<RwLock<HashMap<String, String>>> = Arc::new(RwLock::new(HashMap::new()));
+    Ok(())
+}
+
+/// Check if the optional maximum run time has elapsed since the start time.
+fn is_run_time_elapsed(start_time: Instant, run_for: Option<Duration>) -> bool {
+    run_for.is_some_and(|run_for| start_time.elapsed() >= run_for)
+}
+
+/// Render a panel to an image file for every sensor value update.
+///
+/// Rendering errors are logged, and the panel is rendered again with the next update.
+fn run_render_on_change(
+    renderer: &mut PanelRenderer,
+    panel: &Panel,
+    values: &RwLock<HashMap<String, String>>,
+    activity: &SensorActivity,
+    output: &Path,
+) -> anyhow::Result<()> {
+    info!(
+        "Rendering panel '{}' to {output:?} on sensor value changes",
+        panel.friendly_name()
+    );
+    let mut seen = activity.update_count();
+    if let Err(e) = render_to_file(renderer, panel, values, output) {
+        error!("{e:#}");
+    }
+
+    loop {
+        if let Some(updates) =
+            render_on_next_change(renderer, panel, values, activity, output, seen, None)
+        {
+            seen = updates;
+        }
+    }
+}
+
+/// Wait for the next sensor value update and render the panel to an image file.
+///
+/// Multiple updates received until the panel is rendered are combined into a single rendering.
+/// Rendering errors are logged.
+///
+/// returns: the update count of the rendered sensor values, or None if there was no update within
+/// the timeout.
+fn render_on_next_change(
+    renderer: &mut PanelRenderer,
+    panel: &Panel,
+    values: &RwLock<HashMap<String, String>>,
+    activity: &SensorActivity,
+    output: &Path,
+    seen: u64,
+    timeout: Option<Duration>,
+) -> Option<u64> {
+    let updates = activity.wait_for_update(seen, timeout)?;
+    debug!("Sensor values updated, rendering panel");
+    if let Err(e) = render_to_file(renderer, panel, values, output) {
+        error!("{e:#}");
+    }
+
+    Some(updates)
+}
+
+/// Render a panel with the current sensor values and save it as image file.
+///
+/// The saved image includes the sensor debug boxes if enabled with
+/// [PanelRenderer::set_debug_boxes]. The alpha channel is removed for image formats without alpha
+/// channel support, e.g. JPEG.
+fn render_to_file(
+    renderer: &mut PanelRenderer,
+    panel: &Panel,
+    values: &RwLock<HashMap<String, String>>,
+    output: &Path,
+) -> anyhow::Result<()> {
+    let values = read_lock(values);
+    let image = renderer
+        .render(panel, &values)
+        .map_err(|e| anyhow!("Error rendering panel '{}': {e:?}", panel.friendly_name()))?
+        .clone();
+    let image = renderer.debug_image(&image, panel).unwrap_or(image);
+    match image.save(output) {
+        Err(image::ImageError::Unsupported(_)) => image::DynamicImage::ImageRgba8(image.clone())
+            .to_rgb8()
+            .save(output),
+        result => result,
+    }
+    .with_context(|| format!("Error saving rendered panel to {output:?}"))?;
+
+    Ok(())
+}
+
+/// Stalled sensor source detection with the action to take while sensor values are stale.
+struct StaleMonitor {
+    watchdog: StaleWatchdog,
+    /// Panel to show while stale, or a warning banner if not set.
+    panel: Option<Panel>,
+}
 
-    start_file_slurper(
-        sensor_path,
-        sensor_values.clone(),
-        cfg.sensor_filter.clone(),
+impl StaleMonitor {
+    /// Check if the sensor values are stale and select the panel to render.
+    ///
+    /// While stale, the stale panel is returned if set, otherwise the warning banner of the
+    /// renderer is shown on the given panel.
+    fn select_panel<'a>(
+        &'a mut self,
+        panel: &'a Panel,
+        renderer: &mut PanelRenderer,
+        last_update: Instant,
+        now: Instant,
+    ) -> &'a Panel {
+        let stale = self.watchdog.check(last_update, now);
+        match &self.panel {
+            Some(stale_panel) if stale => stale_panel,
+            Some(_) => panel,
+            None => {
+                renderer.set_banner(stale.then(|| STALE_BANNER.to_string()));
+                panel
+            }
+        }
+    }
+}
+
+const STALE_BANNER: &str = "SENSOR DATA STALE";
+
+/// Number of transition steps when switching panels, including the final frame.
+const TRANSITION_STEPS: u32 = 8;
+
+/// Transition effect when switching panels.
+#[derive(Debug, Default, Copy, Clone)]
+struct PanelTransition {
+    effect: Transition,
+    /// Duration of the transition, the intermediate frames are evenly spread.
+    duration: Duration,
+}
+
+/// Sensor value input of the sensor panel mode.
+#[derive(Default)]
+struct SensorInput {
+    /// Single sensor value input file or directory for multiple sensor input files.
+    path: PathBuf,
+    /// Maximum time to wait for the sensor path at startup if it doesn't exist yet.
+    wait: Duration,
+    /// Generate mock sensor values instead of reading the sensor path.
+    mock: bool,
+    /// Additional SQLite database sensor value source.
+    #[cfg(feature = "sqlite")]
+    sqlite: Option<SqliteSource>,
+}
+
+impl SensorInput {
+    /// Start the sensor value sources: the sensor file watcher or the mock sensor value generator,
+    /// and the optional SQLite database source.
+    ///
+    /// returns: the shared [SensorActivity] with the time of the last sensor value update.
+    fn start(
+        self,
+        cfg: &MonitorConfig,
+        values: Arc<RwLock<HashMap<String, String>>>,
+    ) -> anyhow::Result<Arc<SensorActivity>> {
+        let activity = if self.mock {
+            let mock = MockSensors::new(sensor_labels(cfg));
+            start_mock_sensors(mock, values.clone(), cfg.setup.refresh())
+        } else {
+            start_file_slurper(
+                self.path,
+                values.clone(),
+                cfg.sensor_filter.clone(),
+                self.wait,
+            )?
+        };
+
+        #[cfg(feature = "sqlite")]
+        if let Some(sqlite) = self.sqlite {
+            start_sqlite_poller(sqlite, values, cfg.sensor_filter.clone(), activity.clone());
+        }
+
+        Ok(activity)
+    }
+
+    /// Read the current sensor values once, without monitoring the sensor value sources.
+    fn read(&self, cfg: &MonitorConfig) -> anyhow::Result<HashMap<String, String>> {
+        let mut values = HashMap::new();
+        if self.mock {
+            MockSensors::new(sensor_labels(cfg)).tick(&mut values);
+        } else {
+            if !self.path.exists() {
+                warn!("Sensor path {:?} not found", self.path);
+            }
+            read_path(&self.path, &mut values, cfg.sensor_filter.as_deref())?;
+        }
+
+        #[cfg(feature = "sqlite")]
+        if let Some(sqlite) = &self.sqlite
+            && let Err(e) = read_sqlite(
+                &sqlite.path,
+                &sqlite.query,
+                &mut values,
+                cfg.sensor_filter.as_deref(),
+            )
+        {
+            warn!(
+                "Failed to query SQLite sensor source {:?}: {e:#}",
+                sqlite.path
+            );
+        }
+
+        Ok(values)
+    }
+}
+
+/// Sensor value source labels of all panel sensors.
+fn sensor_labels(cfg: &MonitorConfig) -> impl Iterator<Item = &str> {
+    cfg.panels
+        .iter()
+        .flat_map(|panel| &panel.sensor)
+        .map(|sensor| sensor.source_label())
+}
+
+/// Show only the panel of the `--panel` option instead of the active panels.
+fn select_panel(cfg: &mut MonitorConfig, panel: Option<&str>) -> anyhow::Result<()> {
+    if let Some(panel) = panel
+        && !cfg.set_single_panel(panel)
+    {
+        return Err(anyhow!("Panel not found: {panel}"));
+    }
+
+    Ok(())
+}
+
+/// Render the next active panel once with the current sensor values and send it to the display.
+///
+/// Unlike [run_sensor_panel], the sensor value sources are not monitored and the panel is not
+/// refreshed. The frame remains on the display.
+fn run_panel_once(
+    screen: &mut AooScreen,
+    mut cfg: MonitorConfig,
+    mut renderer: PanelRenderer,
+    sensor_input: SensorInput,
+    error_frame: bool,
+) -> anyhow::Result<()> {
+    let values = sensor_input.read(&cfg)?;
+    let panel = cfg.get_next_active_panel().ok_or(anyhow!(
+        "No active panel. Use --fallback-all-panels to show all panels"
+    ))?;
+
+    info!("Displaying panel once: {}", panel.friendly_name());
+    update_panel(
+        screen,
+        &mut renderer,
+        panel,
+        &values,
+        (0, 0),
+        error_frame,
+        None,
     )?;
 
-    let refresh = Duration::from_millis((cfg.setup.refresh * 1000f32) as u64);
+    Ok(())
+}
 
-    let switch_time = cfg
-        .setup
-        .switch_time
-        .as_deref()
-        .and_then(|v| f32::from_str(v).ok())
-        .map(|v| Duration::from_millis((v * 1000.0) as u64))
-        .unwrap_or(Duration::from_secs(5));
+/// Optional features of the sensor panel mode.
+#[derive(Default)]
+struct PanelOptions {
+    /// Stalled sensor source detection.
+    stale_monitor: Option<StaleMonitor>,
+    /// Burn-in protection frame shift.
+    pixel_shift: Option<PixelShift>,
+    /// Switch off the display while the system is idle.
+    idle_detector: Option<IdleDetector>,
+    /// Save rendered images with a refresh counter suffix.
+    save_images: bool,
+    /// Stop the sensor panel mode after the given time.
+    run_for: Option<Duration>,
+    /// Show an error frame if a panel fails to render.
+    error_frame: bool,
+    /// Transition effect when switching panels.
+    transition: PanelTransition,
+}
+
+fn run_sensor_panel(
+    screen: &mut AooScreen,
+    mut cfg: MonitorConfig,
+    mut renderer: PanelRenderer,
+    sensor_input: SensorInput,
+    options: PanelOptions,
+) -> anyhow::Result<()> {
+    let PanelOptions {
+        mut stale_monitor,
+        pixel_shift,
+        mut idle_detector,
+        save_images,
+        run_for,
+        error_frame,
+        transition,
+    } = options;
+    let sensor_values: Arc<RwLock<HashMap<String, String>>> = Arc::new(RwLock::new(HashMap::new()));
+
+    let activity = sensor_input.start(&cfg, sensor_values.clone())?;
+
+    let refresh = cfg.setup.refresh();
+    let switch_time = cfg.setup.switch_time();
+    let start_time = Instant::now();
+    // the last panel frame sent to the display, as start frame of the next panel transition
+    let mut last_sent_frame = None;
+    let mut last_panel_index = None;
 
     // panel switching loop
     loop {
-        let panel = cfg
-            .get_next_active_panel()
-            .ok_or(anyhow!("No active panel"))?;
+        let panel_index = cfg.next_active_panel_index().ok_or(anyhow!(
+            "No active panel. Use --fallback-all-panels to show all panels"
+        ))?;
+        let panel = &cfg.panels[panel_index];
 
         info!("Switching panel: {}", panel.friendly_name());
         let panel_switch_time = Instant::now();
+        // no transition if the same panel is shown again, e.g. with a single active panel
+        let panel_changed = last_panel_index.replace(panel_index) != Some(panel_index);
+        let mut transition_from = match transition.effect {
+            Transition::Cut => None,
+            _ if !panel_changed => None,
+            _ => last_sent_frame.take(),
+        };
 
         // active panel refresh loop
         let mut refresh_count = 1;
         loop {
             let upd_start_time = Instant::now();
 
-            if img_save_path.is_some() {
+            if save_images {
                 renderer.set_img_suffix(format!("-{refresh_count:02}"));
             }
 
+            let current_panel = match stale_monitor.as_mut() {
+                Some(monitor) => monitor.select_panel(
+                    panel,
+                    &mut renderer,
+                    activity.last_update(),
+                    Instant::now(),
+                ),
+                None => panel,
+            };
+
             // Keeping the read lock during panel rendering should be ok, otherwise we could always clone the HashMap
-            let values = sensor_values.read().expect("RwLock is poisoned");
-            update_panel(screen, &mut renderer, panel, &values)?;
+            let offset = pixel_shift
+                .map(|shift| shift.offset(start_time.elapsed()))
+                .unwrap_or_default();
+            let values = read_lock(&sensor_values);
+            let idle = match idle_detector.as_mut() {
+                Some(detector) => update_idle_display(screen, detector, &values, Instant::now())?,
+                None => false,
+            };
+            let frame_sent = !idle
+                && update_panel(
+                    screen,
+                    &mut renderer,
+                    current_panel,
+                    &values,
+                    offset,
+                    error_frame,
+                    transition_from
+                        .take()
+                        .as_ref()
+                        .map(|from| (from, transition)),
+                )?;
             drop(values);
 
             let elapsed = upd_start_time.elapsed();
@@ -298,7 +1261,18 @@ fn run_sensor_panel<B: Into<PathBuf>>(
                 sleep(refresh - elapsed);
             }
 
+            if is_run_time_elapsed(start_time, run_for) {
+                info!(
+                    "Stopping sensor panel mode after {:?}",
+                    start_time.elapsed()
+                );
+                return Ok(());
+            }
+
             if panel_switch_time.elapsed() >= switch_time {
+                if frame_sent {
+                    last_sent_frame = renderer.last_frame().cloned();
+                }
                 break;
             }
 
@@ -307,18 +1281,816 @@ fn run_sensor_panel<B: Into<PathBuf>>(
     }
 }
 
+/// Switch off the display while the system is idle and on again when activity resumes.
+///
+/// The frame cache is cleared after switching the display on again, so that the next panel update
+/// sends a full frame.
+///
+/// returns: true if the system is idle and the display is switched off.
+fn update_idle_display(
+    screen: &mut AooScreen,
+    detector: &mut IdleDetector,
+    values: &HashMap<String, String>,
+    now: Instant,
+) -> anyhow::Result<bool> {
+    let was_idle = detector.is_idle();
+    let idle = detector.check(values, now);
+
+    if idle && !was_idle {
+        info!("Switching off display while idle");
+        screen.off()?;
+    } else if !idle && was_idle {
+        info!("Switching on display");
+        screen.on()?;
+        screen.clear_cache();
+    }
+
+    Ok(idle)
+}
+
+/// Render a panel and send it to the display, shifted by the given pixel offset.
+///
+/// If rendering fails, an error frame is sent with the `error_frame` option. Otherwise the last
+/// frame remains on the display.
+///
+/// With a `transition` from a previous frame, the intermediate transition frames are sent before
+/// the rendered frame.
+///
+/// returns: true if the rendered panel frame has been sent to the display.
 fn update_panel(
     screen: &mut AooScreen,
     renderer: &mut PanelRenderer,
     panel: &Panel,
     values: &HashMap<String, String>,
-) -> anyhow::Result<()> {
+    offset: (i32, i32),
+    error_frame: bool,
+    transition: Option<(&RgbaImage, PanelTransition)>,
+) -> anyhow::Result<bool> {
     debug!("Displaying panel '{}'...", panel.friendly_name());
 
-    match renderer.render(panel, values) {
-        Ok(image) => screen.send_image(&image)?,
-        Err(e) => error!("Error rendering panel '{}': {e:?}", panel.friendly_name()),
+    let sent = match renderer.render(panel, values) {
+        Ok(image) => {
+            if let Some((from, transition)) = transition {
+                send_transition(screen, from, image, transition, offset)?;
+            }
+            send_shifted_frame(screen, image, offset)?;
+            true
+        }
+        Err(ImageProcessingError::ImageSaveError(e)) => {
+            error!(
+                "Error saving images of panel '{}': {e}",
+                panel.friendly_name()
+            );
+            match renderer.last_frame() {
+                Some(image) => {
+                    send_shifted_frame(screen, image, offset)?;
+                    true
+                }
+                None => false,
+            }
+        }
+        Err(e) => {
+            error!("Error rendering panel '{}': {e:?}", panel.friendly_name());
+            if error_frame {
+                let image = renderer.render_error_frame(&panel.friendly_name(), &format!("{e:?}"));
+                send_shifted_frame(screen, &image, offset)?;
+            }
+            false
+        }
+    };
+
+    if let Some(profile) = renderer.last_profile() {
+        info!(
+            "Render profile of panel '{}':\n{profile}",
+            panel.friendly_name()
+        );
+    }
+
+    Ok(sent)
+}
+
+/// Send the intermediate frames of a transition from the previous to the next frame.
+///
+/// The frames are evenly spread over the transition duration, the next frame itself is not sent.
+fn send_transition(
+    screen: &mut AooScreen,
+    from: &RgbaImage,
+    to: &RgbaImage,
+    transition: PanelTransition,
+    offset: (i32, i32),
+) -> anyhow::Result<()> {
+    if transition.effect == Transition::Cut || from.dimensions() != to.dimensions() {
+        return Ok(());
+    }
+
+    let step_time = transition.duration / TRANSITION_STEPS;
+    for step in 1..TRANSITION_STEPS {
+        let step_start = Instant::now();
+        let progress = step as f32 / TRANSITION_STEPS as f32;
+        let frame = transition.effect.frame(from, to, progress);
+        send_shifted_frame(screen, &frame, offset)?;
+
+        let elapsed = step_start.elapsed();
+        if step_time > elapsed {
+            sleep(step_time - elapsed);
+        }
     }
 
     Ok(())
 }
+
+/// Send a frame to the display, shifted by the given pixel offset.
+fn send_shifted_frame(
+    screen: &mut AooScreen,
+    image: &RgbaImage,
+    offset: (i32, i32),
+) -> anyhow::Result<()> {
+    if offset == (0, 0) {
+        screen.send_image(image)
+    } else {
+        let shifted = img::translate_image(image, offset.0, offset.1);
+        screen.send_image(&shifted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use asterctl_lcd::FakeSerialPort;
+    use clap::CommandFactory;
+    use rstest::rstest;
+    use tempfile::TempDir;
+
+    /// Create a temporary test directory with the given text files. The file names may include
+    /// subdirectories.
+    ///
+    /// The directory is removed when the returned [TempDir] is dropped, also if the test fails.
+    fn test_dir(files: &[(&str, &str)]) -> TempDir {
+        let dir = tempfile::tempdir().expect("failed to create test directory");
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).unwrap();
+            }
+            std::fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn render_on_change_renders_once_per_file_change() {
+        let dir = test_dir(&[("sensors/values.txt", "cpu: 1\n")]);
+        let sensor_dir = dir.path().join("sensors");
+        let output = dir.path().join("preview.png");
+
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [{"mode": 1, "label": "cpu", "value": "", "unit": "", "x": 10, "y": 20,
+            "integerDigits": -1, "decimalDigits": -1, "pic": ""}]}"#,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", dir.path());
+        let values = Arc::new(RwLock::new(HashMap::new()));
+        let activity =
+            start_file_slurper(&sensor_dir, values.clone(), None, Duration::ZERO).unwrap();
+        // give the watcher thread time to start
+        sleep(Duration::from_millis(200));
+        let seen = activity.update_count();
+        let render = |renderer: &mut PanelRenderer, seen, timeout| {
+            render_on_next_change(
+                renderer,
+                &panel,
+                &values,
+                &activity,
+                &output,
+                seen,
+                Some(timeout),
+            )
+        };
+
+        assert_eq!(
+            render(&mut renderer, seen, Duration::from_millis(200)),
+            None
+        );
+        assert!(!output.exists());
+
+        let tmp_file = sensor_dir.join("values.tmp");
+        std::fs::write(&tmp_file, "cpu: 2\n").unwrap();
+        std::fs::rename(&tmp_file, sensor_dir.join("values.txt")).unwrap();
+
+        let updates = render(&mut renderer, seen, Duration::from_secs(5)).expect("re-render");
+        assert!(output.exists());
+        assert_eq!(read_lock(&values).get("cpu").map(String::as_str), Some("2"));
+        assert_eq!(
+            render(&mut renderer, updates, Duration::from_millis(500)),
+            None
+        );
+    }
+
+    #[test]
+    fn render_to_file_saves_jpeg_without_alpha_channel() {
+        let dir = test_dir(&[]);
+        let output = dir.path().join("preview.jpg");
+        let panel: Panel = serde_json::from_str(r#"{"name": "Empty", "sensor": []}"#).unwrap();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", dir.path());
+        let values = RwLock::new(HashMap::new());
+
+        let result = render_to_file(&mut renderer, &panel, &values, &output);
+        let saved = image::open(&output).map(|image| (image.width(), image.height()));
+
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(saved.unwrap(), (100, 40));
+    }
+
+    /// Create a small display with a fake serial port recording all sent data.
+    fn recording_screen() -> (AooScreen, Arc<std::sync::Mutex<Vec<u8>>>) {
+        let recorded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut builder = AooScreenBuilder::new();
+        builder.model(DisplayModel::Custom {
+            width: 100,
+            height: 40,
+        });
+        let screen = builder.open_port(Box::new(FakeSerialPort::recording(recorded.clone())));
+        (screen, recorded)
+    }
+
+    /// Check if the recorded data ends with the display off command.
+    fn ends_with_display_off(recorded: &[u8]) -> bool {
+        let (mut screen, off_cmd) = recording_screen();
+        screen.off().unwrap();
+        let off_cmd = off_cmd.lock().unwrap();
+        recorded.len() > off_cmd.len() && recorded.ends_with(&off_cmd)
+    }
+
+    #[test]
+    fn slideshow_run_for_is_switched_off_after() {
+        let dir = test_dir(&[]);
+        for (name, color) in [("a.png", [255, 0, 0]), ("b.png", [0, 0, 255])] {
+            image::RgbImage::from_pixel(100, 40, Rgb(color))
+                .save(dir.path().join(name))
+                .unwrap();
+        }
+        let (mut screen, recorded) = recording_screen();
+        let off_after = Duration::from_millis(50);
+
+        let start = Instant::now();
+        run_slideshow(
+            &mut screen,
+            dir.path(),
+            Duration::from_millis(20),
+            Some(Duration::from_millis(100)),
+        )
+        .unwrap();
+        assert!(!ends_with_display_off(&recorded.lock().unwrap()));
+        switch_off_after(&mut screen, Some(off_after)).unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(100) + off_after);
+        assert!(ends_with_display_off(&recorded.lock().unwrap()));
+    }
+
+    #[test]
+    fn stale_monitor_shows_banner_while_stale() {
+        let panel: Panel = serde_json::from_str(r#"{"name": "Main", "sensor": []}"#).unwrap();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        let mut monitor = StaleMonitor {
+            watchdog: StaleWatchdog::new(Duration::from_secs(10)),
+            panel: None,
+        };
+        let last_update = Instant::now();
+        let mut select = |renderer: &mut PanelRenderer, secs| {
+            let now = last_update + Duration::from_secs(secs);
+            monitor
+                .select_panel(&panel, renderer, last_update, now)
+                .friendly_name()
+        };
+
+        assert_eq!(select(&mut renderer, 5), "Main");
+        assert_eq!(renderer.banner(), None);
+        assert_eq!(select(&mut renderer, 10), "Main");
+        assert_eq!(renderer.banner(), Some(STALE_BANNER));
+        assert_eq!(select(&mut renderer, 0), "Main");
+        assert_eq!(renderer.banner(), None);
+    }
+
+    #[test]
+    fn stale_monitor_selects_stale_panel_while_stale() {
+        let panel: Panel = serde_json::from_str(r#"{"name": "Main", "sensor": []}"#).unwrap();
+        let stale_panel: Panel =
+            serde_json::from_str(r#"{"name": "Stale", "sensor": []}"#).unwrap();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        let mut monitor = StaleMonitor {
+            watchdog: StaleWatchdog::new(Duration::from_secs(10)),
+            panel: Some(stale_panel),
+        };
+        let last_update = Instant::now();
+        let mut select = |secs| {
+            let now = last_update + Duration::from_secs(secs);
+            monitor
+                .select_panel(&panel, &mut renderer, last_update, now)
+                .friendly_name()
+        };
+
+        assert_eq!(select(5), "Main");
+        assert_eq!(select(11), "Stale");
+        assert_eq!(select(1), "Main");
+        assert_eq!(renderer.banner(), None);
+    }
+
+    #[test]
+    fn display_stays_on_without_off_after() {
+        let (mut screen, recorded) = recording_screen();
+        screen.send_image(&RgbaImage::new(100, 40)).unwrap();
+
+        switch_off_after(&mut screen, None).unwrap();
+
+        assert!(!ends_with_display_off(&recorded.lock().unwrap()));
+    }
+
+    #[test]
+    fn sensor_panel_run_for_is_switched_off_after() {
+        let dir = test_dir(&[("sensors/values.txt", "cpu: 1\n")]);
+        let sensor_dir = dir.path().join("sensors");
+        let cfg: MonitorConfig = serde_json::from_str(
+            r#"{"setup": {"refresh": 0.02}, "mianban": [1], "diy": [{"sensor": [
+            {"mode": 1, "label": "cpu", "value": "", "unit": "", "x": 10, "y": 20,
+            "integerDigits": -1, "decimalDigits": -1, "pic": ""}]}]}"#,
+        )
+        .unwrap();
+        let renderer = PanelRenderer::new((100, 40), "fonts", dir.path());
+        let (mut screen, recorded) = recording_screen();
+        let off_after = Duration::from_millis(50);
+
+        let start = Instant::now();
+        run_sensor_panel(
+            &mut screen,
+            cfg,
+            renderer,
+            SensorInput {
+                path: sensor_dir.clone(),
+                wait: Duration::ZERO,
+                ..Default::default()
+            },
+            PanelOptions {
+                run_for: Some(Duration::from_millis(200)),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(screen.last_frame().is_some());
+        assert!(!ends_with_display_off(&recorded.lock().unwrap()));
+        switch_off_after(&mut screen, Some(off_after)).unwrap();
+
+        assert!(start.elapsed() >= Duration::from_millis(200) + off_after);
+        assert!(ends_with_display_off(&recorded.lock().unwrap()));
+    }
+
+    #[test]
+    fn pixel_shift_offsets_frame_at_scheduled_tick() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [{"mode": 1, "label": "cpu", "value": "", "unit": "", "x": 10, "y": 20,
+            "fontSize": 20, "integerDigits": -1, "decimalDigits": -1, "pic": ""}]}"#,
+        )
+        .unwrap();
+        let values = HashMap::from([("cpu".to_string(), "88".to_string())]);
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        let (mut screen, _) = recording_screen();
+        let shift = PixelShift {
+            magnitude: 3,
+            interval: Duration::from_secs(60),
+        };
+
+        let offset = shift.offset(Duration::from_secs(30));
+        update_panel(
+            &mut screen,
+            &mut renderer,
+            &panel,
+            &values,
+            offset,
+            false,
+            None,
+        )
+        .unwrap();
+        let frame = screen.last_frame().unwrap();
+
+        let offset = shift.offset(Duration::from_secs(120));
+        assert_eq!(offset, (3, 3));
+        update_panel(
+            &mut screen,
+            &mut renderer,
+            &panel,
+            &values,
+            offset,
+            false,
+            None,
+        )
+        .unwrap();
+        let shifted = screen.last_frame().unwrap();
+
+        assert_ne!(frame, shifted);
+        for y in 0..37 {
+            for x in 0..97 {
+                assert_eq!(frame.get_pixel(x, y), shifted.get_pixel(x + 3, y + 3));
+            }
+        }
+        for i in 0..3 {
+            assert!((0..40).all(|y| shifted.get_pixel(i, y) == &Rgb([0, 0, 0])));
+            assert!((0..100).all(|x| shifted.get_pixel(x, i) == &Rgb([0, 0, 0])));
+        }
+    }
+
+    #[rstest]
+    #[case::error_frame(true)]
+    #[case::keep_last_frame(false)]
+    fn render_error_sends_error_frame(#[case] error_frame: bool) {
+        let panel: Panel = serde_json::from_str(
+            r#"{"name": "Fans", "sensor": [{"mode": 2, "label": "fan", "value": "", "unit": "",
+            "x": 10, "y": 20, "integerDigits": -1, "decimalDigits": -1, "pic": "missing.png"}]}"#,
+        )
+        .unwrap();
+        let values = HashMap::from([("fan".to_string(), "50".to_string())]);
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        let (mut screen, recorded) = recording_screen();
+
+        let sent = update_panel(
+            &mut screen,
+            &mut renderer,
+            &panel,
+            &values,
+            (0, 0),
+            error_frame,
+            None,
+        )
+        .unwrap();
+
+        assert!(!sent, "the panel frame must not be reported as sent");
+        assert_eq!(!recorded.lock().unwrap().is_empty(), error_frame);
+        match screen.last_frame() {
+            Some(frame) => {
+                assert!(error_frame);
+                // red error banner, quantized to the RGB 565 display format
+                let pixel = frame.get_pixel(0, 0);
+                assert!(
+                    pixel[0] > 180 && pixel[1] == 0 && pixel[2] == 0,
+                    "{pixel:?}"
+                );
+            }
+            None => assert!(!error_frame),
+        }
+    }
+
+    /// Trace writer sharing the written protocol trace with the test.
+    struct SharedWriter(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for SharedWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Screen writing the protocol trace into the returned buffer.
+    fn traced_screen() -> (AooScreen, Arc<std::sync::Mutex<Vec<u8>>>) {
+        let trace = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut builder = AooScreenBuilder::new();
+        builder
+            .model(DisplayModel::Custom {
+                width: 100,
+                height: 40,
+            })
+            .trace(Some(Box::new(SharedWriter(trace.clone()))));
+        let screen = builder.open_port(Box::new(FakeSerialPort::recording(Arc::new(
+            std::sync::Mutex::new(Vec::new()),
+        ))));
+        (screen, trace)
+    }
+
+    /// Number of sent frames in a protocol trace.
+    fn traced_frames(trace: &std::sync::Mutex<Vec<u8>>) -> usize {
+        let trace = String::from_utf8(trace.lock().unwrap().clone()).unwrap();
+        trace.lines().filter(|l| l.starts_with("frame ")).count()
+    }
+
+    #[test]
+    fn panel_once_sends_single_frame() {
+        let dir = test_dir(&[("values.txt", "cpu: 1\n")]);
+        let sensor_file = dir.path().join("values.txt");
+        let mut cfg: MonitorConfig = serde_json::from_str(
+            r#"{"setup": {"refresh": 0.01}, "mianban": [1], "diy": [
+            {"name": "first", "sensor": []},
+            {"name": "second", "sensor": [
+            {"mode": 1, "label": "cpu", "value": "", "unit": "", "x": 10, "y": 20,
+            "integerDigits": -1, "decimalDigits": -1, "pic": ""}]}]}"#,
+        )
+        .unwrap();
+        select_panel(&mut cfg, Some("second")).unwrap();
+        let renderer = PanelRenderer::new((100, 40), "fonts", dir.path());
+        let (mut screen, trace) = traced_screen();
+
+        run_panel_once(
+            &mut screen,
+            cfg,
+            renderer,
+            SensorInput {
+                path: sensor_file,
+                ..Default::default()
+            },
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(traced_frames(&trace), 1);
+        let empty = PanelRenderer::new((100, 40), "fonts", "cfg")
+            .render(
+                &serde_json::from_str(r#"{"name": "first", "sensor": []}"#).unwrap(),
+                &HashMap::new(),
+            )
+            .unwrap()
+            .clone();
+        assert_ne!(
+            screen.last_frame().unwrap(),
+            image::DynamicImage::ImageRgba8(empty).to_rgb8(),
+            "the selected panel with the sensor value must be shown"
+        );
+    }
+
+    #[test]
+    fn select_unknown_panel_fails() {
+        let mut cfg: MonitorConfig = serde_json::from_str(
+            r#"{"setup": {}, "mianban": [1], "diy": [{"name": "first", "sensor": []}]}"#,
+        )
+        .unwrap();
+
+        assert!(select_panel(&mut cfg, Some("missing")).is_err());
+        assert!(select_panel(&mut cfg, None).is_ok());
+    }
+
+    #[test]
+    fn idle_display_switches_off_and_on() {
+        let panel: Panel = serde_json::from_str(r#"{"name": "Empty", "sensor": []}"#).unwrap();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        let (mut screen, trace) = traced_screen();
+        let mut detector = IdleDetector::new("cpu", 5.0, Duration::from_secs(60));
+        let low = HashMap::from([("cpu".to_string(), "1".to_string())]);
+        let high = HashMap::from([("cpu".to_string(), "50".to_string())]);
+        let start = Instant::now();
+
+        // same order as the main loop: the panel is only updated while not idle
+        let mut check = |values, secs| {
+            let now = start + Duration::from_secs(secs);
+            let idle = update_idle_display(&mut screen, &mut detector, values, now).unwrap();
+            if !idle {
+                update_panel(
+                    &mut screen,
+                    &mut renderer,
+                    &panel,
+                    values,
+                    (0, 0),
+                    false,
+                    None,
+                )
+                .unwrap();
+            }
+            idle
+        };
+        assert!(!check(&low, 0));
+        assert!(check(&low, 60));
+        assert!(check(&low, 90));
+        assert!(!check(&high, 91));
+
+        let trace = String::from_utf8(trace.lock().unwrap().clone()).unwrap();
+        let messages: Vec<_> = trace
+            .lines()
+            .filter_map(|line| line.split(' ').next())
+            .filter(|kind| ["on", "off", "frame"].contains(kind))
+            .collect();
+        // a single frame is sent after switching the display on
+        assert_eq!(messages, ["frame", "off", "on", "frame"]);
+    }
+
+    #[rstest]
+    #[case::cut(Transition::Cut, 1)]
+    #[case::crossfade(Transition::Crossfade, TRANSITION_STEPS)]
+    #[case::slide(Transition::Slide, TRANSITION_STEPS)]
+    fn panel_transition_sends_intermediate_frames(
+        #[case] effect: Transition,
+        #[case] expected_frames: u32,
+    ) {
+        let panel: Panel = serde_json::from_str(r#"{"name": "Empty", "sensor": []}"#).unwrap();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        let (mut screen, trace) = traced_screen();
+        let from = RgbaImage::from_pixel(100, 40, image::Rgba([255, 255, 255, 255]));
+        let transition = PanelTransition {
+            effect,
+            duration: Duration::ZERO,
+        };
+
+        let sent = update_panel(
+            &mut screen,
+            &mut renderer,
+            &panel,
+            &HashMap::new(),
+            (0, 0),
+            false,
+            Some((&from, transition)),
+        )
+        .unwrap();
+
+        assert!(sent);
+        assert_eq!(traced_frames(&trace) as u32, expected_frames);
+    }
+
+    #[test]
+    fn single_panel_is_not_transitioned_to_itself() {
+        let dir = test_dir(&[]);
+        let cfg: MonitorConfig = serde_json::from_str(
+            r#"{"setup": {"refresh": 0.02, "switchTime": 0.02}, "mianban": [1], "diy": [
+            {"name": "single", "sensor": []}]}"#,
+        )
+        .unwrap();
+        let renderer = PanelRenderer::new((100, 40), "fonts", dir.path());
+        let (mut screen, trace) = traced_screen();
+
+        run_sensor_panel(
+            &mut screen,
+            cfg,
+            renderer,
+            SensorInput {
+                path: dir.path().to_path_buf(),
+                wait: Duration::ZERO,
+                ..Default::default()
+            },
+            PanelOptions {
+                run_for: Some(Duration::from_millis(200)),
+                transition: PanelTransition {
+                    effect: Transition::Crossfade,
+                    duration: Duration::ZERO,
+                },
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        // at most one frame per refresh, without intermediate transition frames
+        let frames = traced_frames(&trace);
+        assert!(frames <= 200 / 20 + 1, "{frames} frames");
+    }
+
+    #[test]
+    fn load_value_aliases_from_config_dir() {
+        let dir = test_dir(&[("aliases.cfg", "# states\nup: Online\ndown : Offline\n")]);
+
+        let aliases = load_value_aliases(dir.path(), "aliases.cfg").unwrap();
+        let missing = load_value_aliases(dir.path(), "missing.cfg").unwrap();
+
+        assert_eq!(aliases.len(), 2);
+        assert_eq!(aliases["up"], "Online");
+        assert_eq!(aliases["down"], "Offline");
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn parse_rgb_color_hex_notation() {
+        assert_eq!(parse_rgb_color("ff8000"), Ok(Rgb([255, 128, 0])));
+        assert_eq!(parse_rgb_color("#00FF10"), Ok(Rgb([0, 255, 16])));
+        assert!(parse_rgb_color("fff").is_err());
+        assert!(parse_rgb_color("gg0000").is_err());
+    }
+
+    fn mocked_ports() -> Vec<SerialPortEntry> {
+        vec![
+            SerialPortEntry {
+                name: "/dev/ttyS0".to_string(),
+                vid: None,
+                pid: None,
+                product: None,
+                serial_number: None,
+            },
+            SerialPortEntry {
+                name: "/dev/ttyACM0".to_string(),
+                vid: Some(0x416),
+                pid: Some(0x90a1),
+                product: Some("WTR MAX".to_string()),
+                serial_number: Some("LCD01".to_string()),
+            },
+        ]
+    }
+
+    #[test]
+    fn list_ports_json_contains_port_fields() {
+        let json = ports_json(&mocked_ports());
+
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {"name": "/dev/ttyS0", "vid": null, "pid": null, "product": null, "serial": null},
+                {"name": "/dev/ttyACM0", "vid": "0416", "pid": "90a1", "product": "WTR MAX",
+                 "serial": "LCD01"}
+            ])
+        );
+    }
+
+    #[test]
+    fn list_ports_table_aligns_columns() {
+        let table = ports_table(&mocked_ports());
+
+        assert_eq!(
+            table,
+            "PORT          VID:PID    PRODUCT  SERIAL\n\
+             /dev/ttyS0    -          -        -\n\
+             /dev/ttyACM0  0416:90a1  WTR MAX  LCD01\n"
+        );
+    }
+
+    #[test]
+    fn parse_interval_requires_positive_seconds() {
+        assert_eq!(parse_interval("0.5"), Ok(0.5));
+        assert_eq!(parse_interval("10"), Ok(10.0));
+        assert!(parse_interval("0").is_err());
+        assert!(parse_interval("-1").is_err());
+        assert!(parse_interval("NaN").is_err());
+        assert!(parse_interval("fast").is_err());
+    }
+
+    #[test]
+    fn parse_seconds_requires_non_negative_seconds() {
+        assert_eq!(parse_seconds("0"), Ok(0.0));
+        assert_eq!(parse_seconds("1.5"), Ok(1.5));
+        assert!(parse_seconds("-1").is_err());
+        assert!(parse_seconds("inf").is_err());
+        assert!(parse_seconds("NaN").is_err());
+        assert!(parse_seconds("slow").is_err());
+    }
+
+    #[test]
+    fn parse_display_size_notation() {
+        assert_eq!(parse_display_size("320x240"), Ok((320, 240)));
+        assert_eq!(parse_display_size("960X376"), Ok((960, 376)));
+        assert!(parse_display_size("320").is_err());
+        assert!(parse_display_size("0x240").is_err());
+        assert!(parse_display_size("320x-1").is_err());
+    }
+
+    #[test]
+    fn custom_display_size_renders_and_sends_frame() {
+        let panel: Panel = serde_json::from_str(r#"{"name": "Empty", "sensor": []}"#).unwrap();
+        let recorded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut builder = AooScreenBuilder::new();
+        builder.model(DisplayModel::Custom {
+            width: 320,
+            height: 240,
+        });
+        let mut screen = builder.open_port(Box::new(FakeSerialPort::recording(recorded.clone())));
+        let mut renderer = PanelRenderer::new(screen.size(), "fonts", "cfg");
+
+        update_panel(
+            &mut screen,
+            &mut renderer,
+            &panel,
+            &HashMap::new(),
+            (0, 0),
+            false,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(screen.size(), (320, 240));
+        assert_eq!(screen.last_frame().unwrap().dimensions(), (320, 240));
+        // RGB 565 frame data with 2 bytes per pixel, plus the protocol headers
+        assert!(recorded.lock().unwrap().len() > 320 * 240 * 2);
+    }
+
+    #[test]
+    fn directory_options_use_env_vars() {
+        let command = Args::command();
+        let env_of = |id: &str| {
+            command
+                .get_arguments()
+                .find(|arg| arg.get_id() == id)
+                .and_then(|arg| arg.get_env())
+                .and_then(|env| env.to_str())
+        };
+
+        assert_eq!(env_of("config_dir"), Some("ASTERCTL_CONFIG_DIR"));
+        assert_eq!(env_of("font_dir"), Some("ASTERCTL_FONT_DIR"));
+        assert_eq!(env_of("sensor_path"), Some("ASTERCTL_SENSOR_PATH"));
+    }
+
+    #[test]
+    fn directory_cli_options() {
+        let args = Args::try_parse_from([
+            "asterctl",
+            "--config-dir",
+            "cfg",
+            "--font-dir",
+            "fonts",
+            "--sensor-path",
+            "sensors",
+        ])
+        .unwrap();
+        assert_eq!(args.config_dir, "cfg");
+        assert_eq!(args.font_dir, "fonts");
+        assert_eq!(args.sensor_path, "sensors");
+    }
+}
@@ -3,21 +3,149 @@
 
 //! Sensor panel rendering logic. Create an RGBa image from a panel configuration and sensor values.
 
-use crate::cfg::{Panel, Sensor, SensorDirection, SensorMode, TextAlign};
+use crate::cfg::{
+    BlendMode, ColorStop, FillOrigin, Panel, Sensor, SensorDirection, SensorMode, TextAlign,
+    TextAnchor, TextFill, ValueNormalization,
+};
 use crate::font::FontHandler;
-use crate::format_value;
-use crate::img::{ImageCache, Size, rotate_image};
-use crate::sensors::get_date_time_value;
-use ab_glyph::Font;
+use crate::img::{ImageCache, RotationQuality, Size, rotate_image};
+use crate::sensors::{ValueType, get_date_time_value, parse_bool};
+use crate::{
+    FormattedValue, IntegerDigits, NumberFormat, ValuePart, format_template, format_value_parts,
+    parse_number,
+};
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
 use chrono::{DateTime, Local};
-use image::{ImageBuffer, Rgba, RgbaImage};
-use imageproc::drawing::{draw_text_mut, text_size};
-use log::{debug, error};
-use std::collections::HashMap;
+use image::{DynamicImage, ImageBuffer, ImageError, ImageFormat, Rgb, Rgba, RgbaImage};
+use imageproc::drawing::{
+    draw_filled_rect_mut, draw_hollow_rect_mut, draw_line_segment_mut, draw_text_mut, text_size,
+};
+use imageproc::rect::Rect;
+use log::{debug, warn};
+use std::collections::{HashMap, HashSet};
 use std::f32::consts::PI;
+use std::fmt::{Display, Formatter};
 use std::fs;
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use unicode_bidi::BidiInfo;
+
+/// Font, scale and pixel position of each text line of a text sensor.
+struct TextLayout {
+    font: FontArc,
+    scale: PxScale,
+    lines: Vec<TextLine>,
+}
+
+/// Single text line with its pixel position.
+struct TextLine {
+    text: String,
+    x: i32,
+    y: i32,
+}
+
+/// Part of a text drawn in its own color.
+#[derive(Clone)]
+struct TextSegment {
+    text: String,
+    color: Rgba<u8>,
+}
+
+impl TextSegment {
+    fn new(text: impl Into<String>, color: Rgba<u8>) -> Self {
+        Self {
+            text: text.into(),
+            color,
+        }
+    }
+}
+
+impl TextLayout {
+    /// Pixel position of the first text line.
+    fn position(&self) -> (i32, i32) {
+        self.lines
+            .first()
+            .map(|line| (line.x, line.y))
+            .unwrap_or_default()
+    }
+}
+
+/// Diagnostic information how a single sensor is resolved, formatted and positioned.
+///
+/// See [PanelRenderer::explain_sensor].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorExplanation {
+    /// Sensor label identifier in the panel configuration.
+    pub label: String,
+    pub mode: SensorMode,
+    /// Data source identifier after applying the sensor identifier mapping.
+    pub source: String,
+    /// Raw sensor value, `None` if no value is available.
+    pub value: Option<String>,
+    pub unit: String,
+    pub integer_digits: IntegerDigits,
+    pub decimal_digits: usize,
+    /// Final rendered text of a text sensor.
+    pub text: Option<String>,
+    /// Computed pixel position of a text sensor.
+    pub position: Option<(i32, i32)>,
+}
+
+impl Display for SensorExplanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Sensor:         {}", self.label)?;
+        writeln!(f, "Mode:           {:?}", self.mode)?;
+        writeln!(f, "Source:         {}", self.source)?;
+        match &self.value {
+            Some(value) => writeln!(f, "Raw value:      {value}")?,
+            None => writeln!(f, "Raw value:      <not available>")?,
+        }
+        writeln!(f, "Unit:           {}", self.unit)?;
+        writeln!(f, "Integer digits: {:?}", self.integer_digits)?;
+        writeln!(f, "Decimal digits: {}", self.decimal_digits)?;
+        if let Some(text) = &self.text {
+            writeln!(f, "Rendered text:  {text}")?;
+        }
+        if let Some((x, y)) = self.position {
+            writeln!(f, "Pixel position: ({x}, {y})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Render timing of a panel, see [PanelRenderer::set_profile].
+#[derive(Debug, Clone, Default)]
+pub struct RenderProfile {
+    /// Render duration of each sensor in the panel order, identified by the sensor label.
+    pub sensors: Vec<(String, Duration)>,
+    /// Duration of the final layer compositing.
+    pub compositing: Duration,
+    /// Total panel render duration.
+    pub total: Duration,
+}
+
+impl Display for RenderProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut sensors: Vec<_> = self.sensors.iter().collect();
+        sensors.sort_by_key(|(_, duration)| std::cmp::Reverse(*duration));
+
+        for (label, duration) in sensors {
+            writeln!(f, "{:>10.3}ms  {label}", duration.as_secs_f64() * 1000.0)?;
+        }
+        writeln!(
+            f,
+            "{:>10.3}ms  <compositing>",
+            self.compositing.as_secs_f64() * 1000.0
+        )?;
+        writeln!(f, "{:>10.3}ms  <total>", self.total.as_secs_f64() * 1000.0)
+    }
+}
+
+/// Height of the warning banner in pixels.
+const BANNER_HEIGHT: u32 = 40;
+
+/// Sweep angle in degrees of a fan sensor at the minimum value, drawn only with an `at_min_color`.
+const FAN_AT_MIN_SWEEP: f32 = 5.0;
 
 /// Error type for image processing operations
 #[derive(Debug)]
@@ -28,6 +156,7 @@ pub enum ImageProcessingError {
     InvalidDirection(SensorDirection),
     MathError(String),
     IoError(std::io::Error),
+    ImageSaveError(ImageError),
 }
 
 impl From<std::io::Error> for ImageProcessingError {
@@ -36,21 +165,487 @@ impl From<std::io::Error> for ImageProcessingError {
     }
 }
 
+/// Quiet zone around a QR code in modules.
+#[cfg(feature = "qrcode")]
+const QR_QUIET_ZONE: u32 = 2;
+
+/// Mode 5 - QR code
+///
+/// The value is encoded as QR code with dark modules in the sensor font color, or black if not
+/// specified, on a white background. The QR code is drawn at the top left `(x, y)` position with
+/// the largest integer module size fitting into `width` × `height`, or 4 pixels per module if no
+/// size is specified.
+///
+/// A value which cannot be encoded is logged once per sensor label, tracked in `warnings`.
+#[cfg(feature = "qrcode")]
+fn render_qr_code(
+    background: &mut RgbaImage,
+    sensor: &Sensor,
+    value: &str,
+    warnings: &mut HashSet<String>,
+) {
+    let dark = sensor
+        .font_color
+        .map_or(Rgba([0, 0, 0, 255]), |color| color.into());
+    let max_size = match (sensor.width, sensor.height) {
+        (Some(width), Some(height)) => Some(width.min(height)),
+        (Some(size), None) | (None, Some(size)) => Some(size),
+        (None, None) => None,
+    };
+
+    match qr_code_image(value, max_size, dark, Rgba([255, 255, 255, 255])) {
+        Some(image) => {
+            image::imageops::overlay(background, &image, sensor.x as i64, sensor.y as i64);
+        }
+        None if warnings.insert(sensor.label.clone()) => warn!(
+            "Cannot encode value of sensor {} as QR code in the available size",
+            sensor.label
+        ),
+        None => {}
+    }
+}
+
+/// Mode 5 - QR code, not supported without the `qrcode` feature.
+#[cfg(not(feature = "qrcode"))]
+fn render_qr_code(
+    _background: &mut RgbaImage,
+    sensor: &Sensor,
+    _value: &str,
+    _warnings: &mut HashSet<String>,
+) {
+    static WARNING: std::sync::Once = std::sync::Once::new();
+    WARNING.call_once(|| {
+        warn!(
+            "QR code sensors require the `qrcode` feature, skipping sensor {}",
+            sensor.label
+        )
+    });
+}
+
+/// Encode a text as QR code image.
+///
+/// # Arguments
+///
+/// * `text`: text to encode.
+/// * `max_size`: maximum width and height of the image including the quiet zone. The module size
+///   is the largest integer fitting into the size. 4 pixels per module if not specified.
+/// * `dark`, `light`: module colors.
+///
+/// returns: the QR code image, or None if the text cannot be encoded or doesn't fit into the size.
+#[cfg(feature = "qrcode")]
+fn qr_code_image(
+    text: &str,
+    max_size: Option<u32>,
+    dark: Rgba<u8>,
+    light: Rgba<u8>,
+) -> Option<RgbaImage> {
+    let code = qrcode::QrCode::new(text).ok()?;
+    let modules = code.width() as u32;
+    let total_modules = modules + 2 * QR_QUIET_ZONE;
+    let module_size = max_size.map_or(4, |size| size / total_modules);
+    if module_size == 0 {
+        return None;
+    }
+
+    let size = total_modules * module_size;
+    let mut image = RgbaImage::from_pixel(size, size, light);
+    for y in 0..modules {
+        for x in 0..modules {
+            if code[(x as usize, y as usize)] == qrcode::Color::Dark {
+                let rect = Rect::at(
+                    ((x + QR_QUIET_ZONE) * module_size) as i32,
+                    ((y + QR_QUIET_ZONE) * module_size) as i32,
+                )
+                .of_size(module_size, module_size);
+                draw_filled_rect_mut(&mut image, rect, dark);
+            }
+        }
+    }
+
+    Some(image)
+}
+
+/// Resolve the current value and unit of a sensor.
+///
+/// All lookups use the data source identifier of the sensor, which is the mapped label if a sensor
+/// mapping is applied (see [Sensor::source_label]). Value precedence:
+/// 1. sensor value from the provided `values`
+/// 2. internal date time sensor value
+///
+/// The unit is taken from the `<source>#unit` value if available, otherwise from the sensor
+/// configuration.
+///
+/// # Arguments
+///
+/// * `sensor`: the sensor configuration
+/// * `values`: current sensor values
+/// * `now`: current time for the internal date time sensors
+///
+/// returns: `(value, unit)` tuple, or None if the sensor has no value.
+pub fn resolve_sensor_value(
+    sensor: &Sensor,
+    values: &HashMap<String, String>,
+    now: &DateTime<Local>,
+) -> Option<(String, String)> {
+    let source = sensor.source_label();
+    let value = values
+        .get(source)
+        .cloned()
+        .or_else(|| get_date_time_value(source, now))?;
+
+    Some((value, resolve_sensor_unit(sensor, values)))
+}
+
+/// Get the value of a numeric sensor with the declared value type.
+///
+/// returns: the value, `1` or `0` for a boolean value, or None for a text or invalid boolean value.
+fn typed_numeric_value(value: &str, value_type: Option<ValueType>) -> Option<&str> {
+    match value_type {
+        None | Some(ValueType::Number) => Some(value),
+        Some(ValueType::String) => None,
+        Some(ValueType::Bool) => parse_bool(value).map(|on| if on { "1" } else { "0" }),
+    }
+}
+
+/// Resolve the unit of a sensor from the `<source>#unit` value, or the sensor configuration.
+fn resolve_sensor_unit(sensor: &Sensor, values: &HashMap<String, String>) -> String {
+    values
+        .get(&format!("{}#unit", sensor.source_label()))
+        .cloned()
+        .or_else(|| sensor.unit.clone())
+        .unwrap_or_default()
+}
+
+/// Expand a text sensor template with the given sensor values and internal date time sensors.
+///
+/// `\n` escape sequences in the template are replaced with line breaks, but not in the expanded
+/// sensor values. The optional value normalization of the text sensor is applied to the sensor
+/// values.
+fn expand_template(
+    template: &str,
+    normalize: Option<&ValueNormalization>,
+    values: &HashMap<String, String>,
+    now: &DateTime<Local>,
+) -> String {
+    format_template(&template.replace("\\n", "\n"), |label| {
+        values
+            .get(label)
+            .map(|value| match normalize {
+                Some(normalize) => normalize.apply(value),
+                None => value.clone(),
+            })
+            .or_else(|| get_date_time_value(label, now))
+    })
+}
+
+/// Blend a source pixel onto a target pixel with the given blend mode.
+///
+/// The source alpha channel is applied to all blend modes, the target alpha channel is always
+/// alpha composited.
+pub fn blend_pixel(target: &mut Rgba<u8>, source: &Rgba<u8>, blend: BlendMode) {
+    let alpha = source[3] as f32 / 255.0;
+    let inv_alpha = 1.0 - alpha;
+
+    for i in 0..3 {
+        let src = source[i] as f32;
+        let dst = target[i] as f32;
+        let blended = match blend {
+            BlendMode::Normal => src,
+            BlendMode::Add => (src + dst).min(255.0),
+            BlendMode::Multiply => src * dst / 255.0,
+        };
+        target[i] = (blended * alpha + dst * inv_alpha) as u8;
+    }
+    target[3] = (source[3] as f32 * alpha + target[3] as f32 * inv_alpha) as u8;
+}
+
+/// Expand an image filename template with the `{type}`, `{panel}`, `{label}`, `{suffix}` and
+/// `{timestamp}` placeholders.
+fn expand_img_name(
+    template: &str,
+    img_type: &str,
+    panel: &str,
+    label: &str,
+    suffix: &str,
+    timestamp: &str,
+) -> String {
+    format_template(template, |placeholder| {
+        let value = match placeholder {
+            "type" => img_type,
+            "panel" => panel,
+            "label" => label,
+            "suffix" => suffix,
+            "timestamp" => timestamp,
+            _ => return None,
+        };
+        Some(value.to_string())
+    })
+}
+
+/// Normalize the `min_angle` and `max_angle` range of a fan or pointer sensor.
+///
+/// Sensor angles are specified in degrees with 0° at the 12 o'clock position, increasing clockwise.
+/// Negative angles are counter-clockwise from 12 o'clock.
+/// The `direction` field of a sensor defines if the range is drawn clockwise or counter-clockwise.
+///
+/// - An inverted range with `min_angle > max_angle` is swapped if `swap_inverted` is set. This
+///   applies to fan sensors only, a pointer sensor with an inverted range rotates backwards.
+/// - A range larger than 360° is limited to 360°.
+/// - A `min_angle` outside -360..360° is wrapped to 0..360°. `max_angle` is moved by the same
+///   amount to keep the range.
+///
+/// returns: normalized `(min_angle, max_angle)` tuple
+pub fn normalize_angle_range(min_angle: i32, max_angle: i32, swap_inverted: bool) -> (i32, i32) {
+    let (min_angle, max_angle) = if swap_inverted && min_angle > max_angle {
+        (max_angle, min_angle)
+    } else {
+        (min_angle, max_angle)
+    };
+    let range = (max_angle - min_angle).clamp(-360, 360);
+    let min_angle = if (-360..360).contains(&min_angle) {
+        min_angle
+    } else {
+        min_angle.rem_euclid(360)
+    };
+
+    (min_angle, min_angle + range)
+}
+
+/// Parse the numeric sensor value of an indicator sensor with the configured value parser.
+fn parse_indicator_value(sensor: &Sensor, value: &str) -> Result<f32, ImageProcessingError> {
+    sensor
+        .value_parser
+        .parse(value)
+        .map(|number| number as f32)
+        .ok_or_else(|| ImageProcessingError::MathError(format!("Invalid value: {value}")))
+}
+
+/// Get the `at_min_color` or `at_max_color` of a sensor if the value reached the `min_value` or
+/// `max_value` bound.
+///
+/// returns: the clamp indicator color, or None if the value is within the range or no color is set.
+pub fn clamp_color(sensor: &Sensor, value: f32, min_value: f32, max_value: f32) -> Option<Rgb<u8>> {
+    let color = if value >= max_value {
+        sensor.at_max_color
+    } else if value <= min_value {
+        sensor.at_min_color
+    } else {
+        None
+    };
+    color.map(|color| *color)
+}
+
+/// Fill source of a text sensor, see [TextFill].
+enum TextFillSource<'a> {
+    /// Linear color gradient with stop positions in percent of the text extent.
+    Gradient {
+        stops: &'a [ColorStop],
+        direction: SensorDirection,
+    },
+    /// Texture image tiled from the top left corner of the text.
+    Texture(&'a RgbaImage),
+}
+
+/// Replace the colors of the text drawn on a transparent image with a text fill.
+///
+/// The alpha channel of the drawn glyphs is used as mask. The gradient or texture covers the
+/// bounding box of all visible pixels.
+fn fill_text(text_img: &mut RgbaImage, fill: &TextFillSource) {
+    let visible = text_img
+        .enumerate_pixels()
+        .filter(|(_, _, pixel)| pixel[3] > 0)
+        .map(|(x, y, _)| (x, y));
+    let Some((min_x, min_y, max_x, max_y)) = visible.fold(None, |bounds, (x, y)| match bounds {
+        None => Some((x, y, x, y)),
+        Some((min_x, min_y, max_x, max_y)) => {
+            Some((min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y)))
+        }
+    }) else {
+        return;
+    };
+
+    match fill {
+        TextFillSource::Gradient { stops, direction } => {
+            let horizontal = matches!(
+                direction,
+                SensorDirection::LeftToRight | SensorDirection::RightToLeft
+            );
+            let extent = match horizontal {
+                true => max_x - min_x + 1,
+                false => max_y - min_y + 1,
+            };
+            let colors: Vec<_> = (0..extent)
+                .map(|pos| {
+                    let percent = match extent {
+                        1 => 0.0,
+                        _ => pos as f32 * 100.0 / (extent - 1) as f32,
+                    };
+                    let percent = match direction {
+                        SensorDirection::RightToLeft | SensorDirection::BottomToTop => {
+                            100.0 - percent
+                        }
+                        _ => percent,
+                    };
+                    gradient_color(stops, percent).unwrap_or(Rgb([255, 255, 255]))
+                })
+                .collect();
+
+            for (x, y, pixel) in text_img.enumerate_pixels_mut() {
+                if pixel[3] > 0 {
+                    let pos = match horizontal {
+                        true => x - min_x,
+                        false => y - min_y,
+                    };
+                    let color = colors[pos as usize];
+                    *pixel = Rgba([color[0], color[1], color[2], pixel[3]]);
+                }
+            }
+        }
+        TextFillSource::Texture(texture) => {
+            let (width, height) = texture.dimensions();
+            if width == 0 || height == 0 {
+                return;
+            }
+            for (x, y, pixel) in text_img.enumerate_pixels_mut() {
+                if pixel[3] > 0 {
+                    let texel = texture.get_pixel((x - min_x) % width, (y - min_y) % height);
+                    let alpha = (pixel[3] as u16 * texel[3] as u16 / 255) as u8;
+                    *pixel = Rgba([texel[0], texel[1], texel[2], alpha]);
+                }
+            }
+        }
+    }
+}
+
+/// Get the linear interpolated color of a color gradient for the given value.
+///
+/// Values outside the gradient stops use the color of the first or last stop.
+///
+/// returns: the interpolated color, or None if the gradient is empty.
+pub fn gradient_color(stops: &[ColorStop], value: f32) -> Option<Rgb<u8>> {
+    let mut stops: Vec<_> = stops.iter().collect();
+    stops.sort_by(|a, b| a.value.total_cmp(&b.value));
+
+    let first = stops.first()?;
+    let last = stops.last()?;
+    if value <= first.value {
+        return Some(*first.color);
+    }
+    if value >= last.value {
+        return Some(*last.color);
+    }
+
+    let (lower, upper) = stops
+        .windows(2)
+        .map(|pair| (pair[0], pair[1]))
+        .find(|(lower, upper)| value >= lower.value && value <= upper.value)?;
+    let range = upper.value - lower.value;
+    let ratio = if range > 0.0 {
+        (value - lower.value) / range
+    } else {
+        1.0
+    };
+
+    let channel = |i: usize| {
+        let start = lower.color[i] as f32;
+        let end = upper.color[i] as f32;
+        (start + (end - start) * ratio).round() as u8
+    };
+    Some(Rgb([channel(0), channel(1), channel(2)]))
+}
+
+/// Reorder a text line from logical to visual order with the Unicode bidirectional algorithm.
+///
+/// Right-to-left runs, e.g. Arabic or Hebrew, are reversed for left-to-right glyph drawing. The
+/// paragraph direction is detected from the first strong directional character. Pure
+/// left-to-right text is unchanged. Arabic letters are not shaped into their joining forms.
+pub fn bidi_visual_order(text: &str) -> String {
+    let bidi_info = BidiInfo::new(text, None);
+    if !bidi_info.has_rtl() {
+        return text.to_string();
+    }
+
+    bidi_info
+        .paragraphs
+        .iter()
+        .map(|paragraph| bidi_info.reorder_line(paragraph, paragraph.range.clone()))
+        .collect()
+}
+
+/// Wrap a text line at the given maximum pixel width.
+///
+/// The line is split between characters, a character wider than the maximum width gets its own
+/// line.
+fn wrap_text(scale: PxScale, font: &FontArc, line: &str, max_width: u32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for c in line.chars() {
+        current.push(c);
+        if current.chars().count() > 1 && text_size(scale, font, &current).0 > max_width {
+            current.pop();
+            lines.push(std::mem::take(&mut current));
+            current.push(c);
+        }
+    }
+    lines.push(current);
+
+    lines
+}
+
 /// Sensor panel renderer.
 ///
 /// Renders a final display image from a sensor panel configuration and current sensor values.
 /// All defined fonts and images of a sensor panel are cached after first use.
 pub struct PanelRenderer {
     size: Size,
-    composite_layer_map: HashMap<SensorMode, RgbaImage>,
+    composite_layer_map: HashMap<(SensorMode, BlendMode), RgbaImage>,
     font_handler: FontHandler,
     image_cache: ImageCache,
+    number_format: NumberFormat,
+    default_integer_digits: Option<i32>,
+    default_decimal_digits: Option<i32>,
+    use_config_values: bool,
+    /// Last rendered panel image
+    last_frame: Option<RgbaImage>,
+    /// Frame buffer for the next panel rendering, swapped with `last_frame` after a successful
+    /// rendering.
+    spare_frame: Option<RgbaImage>,
+    profile: bool,
+    last_profile: Option<RenderProfile>,
     // for debugging: save images for inspection
     save_render_img: bool,
     save_processed_pic: bool,
     save_progress_layer: bool,
     img_save_path: PathBuf,
     img_suffix: Option<String>,
+    img_save_format: ImageFormat,
+    img_name_template: Option<String>,
+    panel_name: String,
+    /// Default font family of the rendered panel, see [Panel::font_family].
+    panel_font_family: Option<String>,
+    debug_boxes: bool,
+    /// Warning banner text drawn on top of the rendered panel
+    banner: Option<String>,
+    /// Global display texts of raw text sensor values
+    value_aliases: HashMap<String, String>,
+    /// Sensor labels with an already logged invalid angle range
+    angle_warnings: HashSet<String>,
+    /// Sensor labels with an already logged QR code encoding failure
+    qr_code_warnings: HashSet<String>,
+    /// Number of reached color thresholds by panel name and sensor index, used for the color
+    /// hysteresis
+    threshold_levels: HashMap<(String, usize), usize>,
+    /// Last resolved values of sensors with an update interval, by panel name and sensor index
+    value_cache: HashMap<(String, usize), CachedValue>,
+}
+
+/// Resolved sensor value of a sensor with an update interval.
+#[derive(Debug, Clone)]
+struct CachedValue {
+    updated: Instant,
+    value: String,
+    unit: String,
+    value_type: Option<ValueType>,
 }
 
 impl PanelRenderer {
@@ -69,37 +664,148 @@ impl PanelRenderer {
             composite_layer_map: HashMap::new(),
             font_handler: FontHandler::new(font_dir),
             image_cache: ImageCache::new(img_dir),
+            number_format: NumberFormat::default(),
+            default_integer_digits: None,
+            default_decimal_digits: None,
+            use_config_values: false,
+            last_frame: None,
+            spare_frame: None,
+            profile: false,
+            last_profile: None,
             save_render_img: false,
             save_processed_pic: false,
             save_progress_layer: false,
             img_save_path: PathBuf::from("out"),
             img_suffix: None,
+            img_save_format: ImageFormat::Png,
+            img_name_template: None,
+            panel_name: String::new(),
+            panel_font_family: None,
+            debug_boxes: false,
+            banner: None,
+            value_aliases: HashMap::new(),
+            angle_warnings: HashSet::new(),
+            qr_code_warnings: HashSet::new(),
+            threshold_levels: HashMap::new(),
+            value_cache: HashMap::new(),
+        }
+    }
+
+    /// Set the number format for rendering text sensor values.
+    pub fn set_number_format(&mut self, number_format: NumberFormat) {
+        self.number_format = number_format;
+    }
+
+    /// Set the default number of integer and decimal places for text sensors without their own
+    /// `integer_digits` and `decimal_digits` settings.
+    ///
+    /// Default: all integer places and no decimal places.
+    pub fn set_default_digits(&mut self, integer_digits: Option<i32>, decimal_digits: Option<i32>) {
+        self.default_integer_digits = integer_digits;
+        self.default_decimal_digits = decimal_digits;
+    }
+
+    /// Use the `value` field of the sensor configuration if no sensor value is available.
+    ///
+    /// Sensor values from the sensor data sources always take precedence.
+    pub fn set_use_config_values(&mut self, enable: bool) {
+        self.use_config_values = enable;
+    }
+
+    /// Measure the render duration of each sensor and the layer compositing.
+    ///
+    /// The measurements of the last rendered panel are available with [PanelRenderer::last_profile].
+    pub fn set_profile(&mut self, enable: bool) {
+        self.profile = enable;
+        if !enable {
+            self.last_profile = None;
         }
     }
 
-    /// For debugging: save rendered panel image as .PNG graphic for inspection.
+    /// Get the render timing of the last rendered panel if profiling is enabled.
+    pub fn last_profile(&self) -> Option<&RenderProfile> {
+        self.last_profile.as_ref()
+    }
+
+    /// For debugging: save rendered panel image as graphic for inspection.
     pub fn set_save_render_img(&mut self, save: bool) {
         self.save_render_img = save;
-        self.create_img_save_path();
     }
-    /// For debugging: save all processed sensor pic images as .PNG graphics for inspection.
+    /// For debugging: save all processed sensor pic images as graphics for inspection.
     pub fn set_save_processed_pic(&mut self, save: bool) {
         self.save_processed_pic = save;
-        self.create_img_save_path();
     }
-    /// For debugging: save all progress layer images as .PNG graphics for inspection.
+    /// For debugging: save all progress layer images as graphics for inspection.
     pub fn set_save_progress_layer(&mut self, save: bool) {
         self.save_progress_layer = save;
-        self.create_img_save_path();
     }
+    /// For debugging: draw the bounding box and anchor point of each sensor on the saved panel image.
+    ///
+    /// The debug boxes are not drawn on the panel image sent to the display, see
+    /// [Self::debug_image].
+    pub fn set_debug_boxes(&mut self, enable: bool) {
+        self.debug_boxes = enable;
+    }
+    /// Append a font directory to the font search path.
+    ///
+    /// Fonts are loaded from the first directory containing the font, starting with the font
+    /// directory of the constructor.
+    pub fn add_font_dir(&mut self, font_dir: impl Into<PathBuf>) {
+        self.font_handler.add_font_dir(font_dir);
+    }
+
+    /// Set the default font for text sensors without a font family or with a missing font.
+    ///
+    /// The embedded default font is used if not set. Status banners always use the embedded font.
+    pub fn set_default_font(&mut self, font: FontArc) {
+        self.font_handler.set_default_font(font);
+    }
+
+    /// Set the global display texts of raw text sensor values, e.g. `up` → `Online`.
+    ///
+    /// The aliases apply to all text sensors, the `value_map` of a sensor takes precedence.
+    pub fn set_value_aliases(&mut self, aliases: HashMap<String, String>) {
+        self.value_aliases = aliases;
+    }
+
+    /// Draw a warning banner with the given text on top of the rendered panel image.
+    ///
+    /// Use `None` to remove the banner.
+    pub fn set_banner(&mut self, text: Option<String>) {
+        self.banner = text;
+    }
+
+    /// Get the text of the current warning banner.
+    pub fn banner(&self) -> Option<&str> {
+        self.banner.as_deref()
+    }
+
     /// Set output directory path for saving images.
     ///
-    /// Default output directory is `./out` in the current working directory.
+    /// Default output directory is `./out` in the current working directory. The directory is
+    /// created when the first image is saved.
     pub fn set_img_save_path(&mut self, img_dir: impl Into<PathBuf>) {
         self.img_save_path = img_dir.into();
-        self.create_img_save_path();
     }
-    /// Set an optional image name suffix for saving a .PNG graphic file.
+    /// Set the image format for saving images. Default: PNG
+    pub fn set_img_save_format(&mut self, format: ImageFormat) {
+        self.img_save_format = format;
+    }
+    /// Set an optional image filename template for saving images, without file extension.
+    ///
+    /// Supported placeholders:
+    /// - `{type}`: image type: `render`, `processed_img` or `progress_layer`
+    /// - `{panel}`: friendly name of the rendered panel
+    /// - `{label}`: sensor label, empty for rendered panel images
+    /// - `{suffix}`: image name suffix, see [set_img_suffix()]
+    /// - `{timestamp}`: local time in `YYYYmmdd_HHMMSS_mmm` format
+    ///
+    /// Default filenames: `render_{panel}{suffix}`, `processed_img-{label}{suffix}` and
+    /// `progress_layer-{label}{suffix}`.
+    pub fn set_img_name_template(&mut self, template: impl Into<String>) {
+        self.img_name_template = Some(template.into());
+    }
+    /// Set an optional image name suffix for saving a graphic file.
     ///
     /// This function needs to be called before [render()] if a different suffix should be used for each rendered panel.
     pub fn set_img_suffix(&mut self, img_suffix: impl Into<String>) {
@@ -108,17 +814,24 @@ impl PanelRenderer {
 
     /// Render a sensor panel with the given values and return the final panel image.
     ///
+    /// The panel is rendered into an internal frame buffer, which is swapped with the last rendered
+    /// panel image on success to avoid allocating a new image for every frame. The last rendered
+    /// panel image is kept if rendering fails.
+    ///
     /// # Arguments
     ///
     /// * `panel`: the panel configuration
     /// * `values`: current values for the defined panel sensors in a shared HashMap
     ///
     /// returns: a rendered panel image in [RgbaImage] format, or an [ImageProcessingError] in case of an error.
+    /// Errors saving the rendered panel image are returned as [ImageProcessingError::ImageSaveError],
+    /// the image is still available with [PanelRenderer::last_frame]. Errors saving the intermediate
+    /// sensor debug images are logged and don't abort rendering.
     pub fn render(
         &mut self,
         panel: &Panel,
         values: &HashMap<String, String>,
-    ) -> Result<RgbaImage, ImageProcessingError> {
+    ) -> Result<&RgbaImage, ImageProcessingError> {
         debug!(
             "Rendering panel {}...",
             panel
@@ -128,34 +841,87 @@ impl PanelRenderer {
         );
 
         let now = Instant::now();
-        let background = if let Some(img) = &panel.img
-            && let Some(background) = self.image_cache.get(img, Some(self.size))
-        {
-            background.clone()
-        } else {
-            RgbaImage::new(self.size.0, self.size.1)
-        };
+        let background = self.reset_frame(panel);
         self.composite_layer_map.clear();
+        self.last_profile = None;
+
+        let mut final_image = self.render_all_sensors(panel, values, background)?;
 
-        let final_image = self.render_all_sensors(panel, values, background)?;
+        if let Some(banner) = &self.banner {
+            PanelRenderer::draw_banner(&mut final_image, banner);
+        }
 
         debug!("Rendered panel in {}ms", now.elapsed().as_millis());
+        if let Some(profile) = self.last_profile.as_mut() {
+            profile.total = now.elapsed();
+        }
 
-        if self.save_render_img {
-            let name = format!(
-                "render_{}{}.png",
-                panel.friendly_name(),
-                self.img_suffix.as_deref().unwrap_or_default()
-            );
-            if let Err(e) = final_image.save(self.img_save_path.join(name)) {
-                error!("Error saving rendered panel image: {e}");
+        let saved = if self.save_render_img {
+            match self.debug_image(&final_image, panel) {
+                Some(debug_image) => self.save_img(&debug_image, "render", ""),
+                None => self.save_img(&final_image, "render", ""),
             }
-        }
+        } else {
+            Ok(())
+        };
+        self.spare_frame = self.last_frame.take();
+        let final_image = self.last_frame.insert(final_image);
+        saved.map_err(ImageProcessingError::ImageSaveError)?;
 
         Ok(final_image)
     }
 
-    /// Render all panel sensors with the given values on a background image
+    /// Reset the frame buffer of the last rendered frame with the panel background image of the
+    /// current local time, see [Panel::background_at].
+    ///
+    /// A new frame buffer is only allocated for the first frame or if the display size doesn't match.
+    fn reset_frame(&mut self, panel: &Panel) -> RgbaImage {
+        let frame = self
+            .spare_frame
+            .take()
+            .filter(|frame| frame.dimensions() == self.size);
+        let background = panel
+            .background_at(Local::now().time())
+            .and_then(|img| self.image_cache.get(img, Some(self.size)));
+
+        match (frame, background) {
+            (Some(mut frame), Some(background)) if background.dimensions() == self.size => {
+                frame.copy_from_slice(background);
+                frame
+            }
+            (Some(mut frame), None) => {
+                frame.fill(0);
+                frame
+            }
+            (_, Some(background)) => background.clone(),
+            (None, None) => RgbaImage::new(self.size.0, self.size.1),
+        }
+    }
+
+    /// Get a copy of a rendered panel image with the debug boxes of the panel sensors, if enabled
+    /// with [Self::set_debug_boxes].
+    ///
+    /// returns: the debug image, or None if debug boxes are disabled.
+    pub fn debug_image(&self, image: &RgbaImage, panel: &Panel) -> Option<RgbaImage> {
+        self.debug_boxes.then(|| {
+            let mut debug_image = image.clone();
+            PanelRenderer::draw_debug_boxes(&mut debug_image, panel);
+            debug_image
+        })
+    }
+
+    /// Get the last rendered panel image of [PanelRenderer::render].
+    ///
+    /// returns: the last rendered image, or None if no panel has been rendered yet.
+    pub fn last_frame(&self) -> Option<&RgbaImage> {
+        self.last_frame.as_ref()
+    }
+
+    /// Render all panel sensors and tables with the given values on a background image
+    ///
+    /// Sensors and table cells hidden by the panel sensor filter are skipped, see
+    /// [Panel::is_sensor_filtered], as well as sensors hidden by their control sensor, see
+    /// [Sensor::is_visible].
     pub fn render_all_sensors(
         &mut self,
         panel: &Panel,
@@ -163,106 +929,663 @@ impl PanelRenderer {
         mut background: RgbaImage,
     ) -> Result<RgbaImage, ImageProcessingError> {
         let now: DateTime<Local> = Local::now();
+        self.panel_name = panel.friendly_name();
+        self.panel_font_family = panel.font_family.clone();
+        let mut profile = self.profile.then(RenderProfile::default);
 
-        for sensor in &panel.sensor {
-            let value = values.get(&sensor.label).cloned();
-            let unit = values
-                .get(&format!("{}#unit", sensor.label))
-                .cloned()
-                .or_else(|| sensor.unit.clone())
-                .unwrap_or_default();
+        for (index, sensor) in panel.sensor.iter().enumerate() {
+            if panel.is_sensor_filtered(sensor) || !sensor.is_visible(values) {
+                continue;
+            }
+            let sensor_start = Instant::now();
+            if sensor.mode == SensorMode::Text
+                && let Some(template) = &sensor.template
+            {
+                let text = expand_template(template, sensor.normalize.as_ref(), values, &now);
+                let font_color = sensor.font_color.unwrap_or_default().into();
+                self.draw_text(
+                    &mut background,
+                    sensor,
+                    &[TextSegment::new(text, font_color)],
+                    font_color,
+                )?;
+            } else if let Some(cached) = self.sensor_value(index, sensor, values, &now) {
+                self.render_sensor(
+                    &mut background,
+                    index,
+                    sensor,
+                    &cached.value,
+                    &cached.unit,
+                    cached.value_type,
+                )?;
+            }
+            if let Some(profile) = profile.as_mut() {
+                profile
+                    .sensors
+                    .push((sensor.label.clone(), sensor_start.elapsed()));
+            }
+        }
 
-            if let Some(value) = value {
-                self.render_sensor(&mut background, sensor, &value, &unit)?;
-            } else if let Some(value) = get_date_time_value(&sensor.label, &now) {
-                self.render_sensor(&mut background, sensor, &value, &unit)?;
+        // table cells are indexed after the panel sensors
+        let cells = panel.tables.iter().flat_map(|table| table.cell_sensors());
+        for (index, cell) in (panel.sensor.len()..).zip(cells) {
+            if panel.is_sensor_filtered(&cell) {
+                continue;
+            }
+            if let Some((value, unit)) = self.resolve_value(&cell, values, &now) {
+                let value_type = ValueType::of(cell.source_label(), values);
+                self.render_text(&mut background, index, &cell, &value, &unit, value_type)?;
             }
         }
 
         // Final compositing
+        let compositing_start = Instant::now();
         self.composite_layers(&mut background);
 
+        if let Some(mut profile) = profile {
+            profile.compositing = compositing_start.elapsed();
+            self.last_profile = Some(profile);
+        }
+
         Ok(background)
     }
 
+    /// Get the value, unit and value type of a sensor for rendering.
+    ///
+    /// The value of a sensor with an `update_interval` is only resolved again after the interval
+    /// elapsed, otherwise the last resolved value is returned. The value is cached by the `index`
+    /// of the sensor in the panel, since multiple sensors may show the same label.
+    fn sensor_value(
+        &mut self,
+        index: usize,
+        sensor: &Sensor,
+        values: &HashMap<String, String>,
+        now: &DateTime<Local>,
+    ) -> Option<CachedValue> {
+        let interval = sensor
+            .update_interval
+            .filter(|interval| *interval > 0.0)
+            .and_then(|interval| Duration::try_from_secs_f32(interval).ok());
+        let key = (self.panel_name.clone(), index);
+        if let Some(interval) = interval
+            && let Some(cached) = self.value_cache.get(&key)
+            && cached.updated.elapsed() < interval
+        {
+            return Some(cached.clone());
+        }
+
+        let (value, unit) = self.resolve_value(sensor, values, now)?;
+        let resolved = CachedValue {
+            updated: Instant::now(),
+            value,
+            unit,
+            value_type: ValueType::of(sensor.source_label(), values),
+        };
+        if interval.is_some() {
+            self.value_cache.insert(key, resolved.clone());
+        }
+
+        Some(resolved)
+    }
+
+    /// Resolve the current value and unit of a sensor with [resolve_sensor_value].
+    ///
+    /// Falls back to the `value` field of the sensor configuration if enabled with
+    /// [PanelRenderer::set_use_config_values]. The optional sensor value normalization is applied
+    /// to the resolved value.
+    fn resolve_value(
+        &self,
+        sensor: &Sensor,
+        values: &HashMap<String, String>,
+        now: &DateTime<Local>,
+    ) -> Option<(String, String)> {
+        let (value, unit) = resolve_sensor_value(sensor, values, now).or_else(|| {
+            if !self.use_config_values {
+                return None;
+            }
+            let value = sensor.value.clone()?;
+            Some((value, resolve_sensor_unit(sensor, values)))
+        })?;
+
+        match &sensor.normalize {
+            Some(normalize) => Some((normalize.apply(&value), unit)),
+            None => Some((value, unit)),
+        }
+    }
+
     /// Render a single sensor element based on its mode
+    ///
+    /// Fan, progress and pointer sensors skip values declared as text, and use boolean values as
+    /// 1 or 0.
     fn render_sensor(
         &mut self,
         background: &mut RgbaImage,
+        index: usize,
         sensor: &Sensor,
         value: &str,
         unit: &str,
+        value_type: Option<ValueType>,
     ) -> Result<(), ImageProcessingError> {
         let direction = sensor.direction.unwrap_or(SensorDirection::LeftToRight);
 
-        match sensor.mode {
-            SensorMode::Text => self.render_text(background, sensor, value, unit),
-            SensorMode::Fan => self.render_fan(sensor, value, direction),
-            SensorMode::Progress => self.render_progress(sensor, value, direction),
-            SensorMode::Pointer => self.render_pointer(sensor, value, direction),
+        match (sensor.mode, typed_numeric_value(value, value_type)) {
+            (SensorMode::Text, _) => {
+                self.render_text(background, index, sensor, value, unit, value_type)
+            }
+            (SensorMode::QrCode, _) => {
+                render_qr_code(background, sensor, value, &mut self.qr_code_warnings);
+                Ok(())
+            }
+            (SensorMode::Fan, Some(value)) => self.render_fan(sensor, value, direction),
+            (SensorMode::Progress, Some(value)) => self.render_progress(sensor, value, direction),
+            (SensorMode::Pointer, Some(value)) => self.render_pointer(sensor, value, direction),
+            (_, None) => {
+                debug!("Skipping non-numeric value of sensor {}", sensor.label);
+                Ok(())
+            }
         }
     }
 
     /// Mode 1 - Text
+    ///
+    /// The decimal places and the unit are drawn with the optional `decimal_color` and
+    /// `unit_color`, or the font color of the integer part.
     fn render_text(
         &mut self,
         background: &mut RgbaImage,
+        index: usize,
         sensor: &Sensor,
         value: &str,
         unit: &str,
+        value_type: Option<ValueType>,
     ) -> Result<(), ImageProcessingError> {
-        let font = if let Some(font_family) = &sensor.font_family {
-            self.font_handler.get_ttf_font_or_default(font_family)
-        } else {
-            FontHandler::default_font()
+        let formatted = self.format_text_parts(sensor, value, unit, value_type);
+        let font_color = match typed_numeric_value(value, value_type) {
+            Some(value) => self.text_color(index, sensor, value),
+            None => sensor.font_color.unwrap_or_default().into(),
         };
-        let font_size = sensor.font_size.unwrap_or(14) as f32;
-        // TODO verify pixel scaling! Is font_size point size or pixel size?
-        // TODO some font size calculation is missing, dpi scaling? internal padding?
-        //      The adjustment hack is required to get the correct size of the rendered text.
-        //      However, the y-position requires the regular value (see multiplication by 1.33 below)
-        let adjustment_hack = 0.75;
-        let scale = font.pt_to_px_scale(font_size * adjustment_hack).unwrap();
+        let decimal_color = sensor.decimal_color.map_or(font_color, Into::into);
+        let unit_color = sensor.unit_color.map_or(font_color, Into::into);
+
+        let segments: Vec<_> = formatted
+            .segments()
+            .into_iter()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(part, text)| {
+                let color = match part {
+                    ValuePart::Integer => font_color,
+                    ValuePart::Decimal => decimal_color,
+                    ValuePart::Unit => unit_color,
+                };
+                TextSegment::new(text, color)
+            })
+            .collect();
 
-        let text = format_value(
-            value,
-            sensor.integer_digits.into(),
-            sensor.decimal_digits.unwrap_or_default() as usize,
-            unit,
-        );
-        let size = text_size(scale, &font, &text);
-        let width = sensor.width.unwrap_or_default() as i32;
-        let height = sensor.height.unwrap_or_default() as i32;
-        let x = match sensor.text_align.unwrap_or_default() {
-            TextAlign::Left => sensor.x,
-            TextAlign::Center => sensor.x + width / 2 - (size.0 / 2) as i32,
-            TextAlign::Right => sensor.x + width - size.0 as i32,
-        };
-        // FIXME figure out font scaling factor / padding / dpi etc. See above for y-adjustment hack.
-        // This work quite ok for most panels, but not all!
-        // Some work better with `sensor.y + height / 2 - size.1 as i32;`
-        // The y parameter in `draw_text_mut` is still a mystery: drawing text at position (0,0)
-        // renders a huge gap at the top, about the size of half the font-height!?
-        let y = sensor.y + height / 2 - (size.1 as f32 * 1.3333 / 2f32) as i32;
+        self.draw_text(background, sensor, &segments, font_color)
+    }
 
-        debug!(
-            "Sensor({:03},{:03}), pixel({x:03},{y:03}), size{size:?}: {text}",
-            sensor.x, sensor.y
-        );
+    /// Get the font color of a text sensor for the given value.
+    ///
+    /// The color of the highest reached color threshold is used, or the sensor font color if no
+    /// threshold is reached. With a color hysteresis, a threshold is only reached if the value is
+    /// greater than or equal to the threshold plus the hysteresis margin, and only left if the value
+    /// is below the threshold minus the margin, to avoid flickering colors at a threshold.
+    ///
+    /// The threshold level is kept per panel and sensor `index`, since the same sensor label can be
+    /// used by multiple sensors with different thresholds.
+    fn text_color(&mut self, index: usize, sensor: &Sensor, value: &str) -> Rgba<u8> {
+        let default_color = sensor.font_color.unwrap_or_default().into();
+        if sensor.color_thresholds.is_empty() {
+            return default_color;
+        }
+        let Some(value) = parse_number(value).map(|value| value as f32) else {
+            return default_color;
+        };
 
-        let font_color = sensor.font_color.unwrap_or_default().into();
-        draw_text_mut(background, font_color, x, y, scale, &font, &text);
+        let mut thresholds: Vec<_> = sensor.color_thresholds.iter().collect();
+        thresholds.sort_by(|a, b| a.value.total_cmp(&b.value));
+        let reached = thresholds.iter().filter(|t| value >= t.value).count();
+
+        let hysteresis = sensor.color_hysteresis.max(0.0);
+        let key = (self.panel_name.clone(), index);
+        let level = match self.threshold_levels.get(&key) {
+            Some(&previous) => {
+                let mut level = previous.min(thresholds.len());
+                while level < thresholds.len() && value >= thresholds[level].value + hysteresis {
+                    level += 1;
+                }
+                while level > 0 && value < thresholds[level - 1].value - hysteresis {
+                    level -= 1;
+                }
+                level
+            }
+            None => reached,
+        };
+        self.threshold_levels.insert(key, level);
 
-        Ok(())
+        match level {
+            0 => default_color,
+            level => thresholds[level - 1].color.into(),
+        }
     }
 
-    /// Mode 2 - Circular/Arc progress indicator
-    fn render_fan(
-        &mut self,
+    /// Format the value of a text sensor with its digits and unit settings.
+    ///
+    /// Values declared as text or boolean are not formatted as number.
+    fn format_text(
+        &self,
         sensor: &Sensor,
         value: &str,
-        direction: SensorDirection,
-    ) -> Result<(), ImageProcessingError> {
+        unit: &str,
+        value_type: Option<ValueType>,
+    ) -> String {
+        self.format_text_parts(sensor, value, unit, value_type)
+            .to_string()
+    }
+
+    /// Format the value of a text sensor like [Self::format_text], keeping the value parts
+    /// separated.
+    ///
+    /// Values with a display text in the sensor `value_map` or the global value aliases are
+    /// replaced and not formatted as number.
+    fn format_text_parts(
+        &self,
+        sensor: &Sensor,
+        value: &str,
+        unit: &str,
+        value_type: Option<ValueType>,
+    ) -> FormattedValue {
+        let raw_value = value.trim();
+        if let Some(alias) = sensor
+            .value_map
+            .get(raw_value)
+            .or_else(|| self.value_aliases.get(raw_value))
+        {
+            return FormattedValue::text(alias, unit, sensor.unit_position);
+        }
+        if matches!(value_type, Some(ValueType::String | ValueType::Bool)) {
+            return FormattedValue::text(value, unit, sensor.unit_position);
+        }
+        let (integer_digits, decimal_digits) = self.text_digits(sensor);
+        let number_format = NumberFormat {
+            rounding: sensor.rounding.unwrap_or(self.number_format.rounding),
+            ..self.number_format.clone()
+        };
+        format_value_parts(
+            value,
+            integer_digits,
+            decimal_digits,
+            unit,
+            sensor.unit_position,
+            sensor.notation,
+            &number_format,
+        )
+    }
+
+    /// Get the number of integer and decimal places of a text sensor.
+    ///
+    /// The sensor settings take precedence over the default settings. An unset sensor setting
+    /// (`-1` or missing) uses the default setting, or all integer places and no decimal places
+    /// without a default. A sensor setting of `0` is never replaced by the default.
+    fn text_digits(&self, sensor: &Sensor) -> (IntegerDigits, usize) {
+        let integer_digits = sensor.integer_digits.or(self.default_integer_digits);
+        let decimal_digits = sensor
+            .decimal_digits
+            .or(self.default_decimal_digits)
+            .unwrap_or_default()
+            .max(0);
+
+        (integer_digits.into(), decimal_digits as usize)
+    }
+
+    /// Draw the final text segments of a text sensor
+    ///
+    /// The `font_color` is used for texts which can't keep the segment colors, see
+    /// [Self::line_segments].
+    fn draw_text(
+        &mut self,
+        background: &mut RgbaImage,
+        sensor: &Sensor,
+        segments: &[TextSegment],
+        font_color: Rgba<u8>,
+    ) -> Result<(), ImageProcessingError> {
+        let text: String = segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect();
+        let layout = self.text_layout(sensor, &text);
+        let line_segments = Self::line_segments(&layout, segments, sensor.rtl, font_color);
+        let fill = match &sensor.text_fill {
+            Some(fill) => self.text_fill_source(fill)?,
+            None => None,
+        };
+
+        if sensor.rotation.rem_euclid(360) != 0 {
+            Self::draw_rotated_text(
+                background,
+                &layout,
+                &line_segments,
+                sensor.rotation,
+                fill.as_ref(),
+            );
+            return Ok(());
+        }
+
+        match fill {
+            Some(fill) => {
+                let (text_img, x, y) = Self::draw_text_buffer(&layout, &line_segments, Some(&fill));
+                Self::paste_image(background, &text_img, x, y);
+            }
+            None => {
+                for (line, segments) in layout.lines.iter().zip(&line_segments) {
+                    Self::draw_segments(background, &layout, line.x, line.y, segments);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the fill source of a text fill configuration.
+    ///
+    /// returns: the texture image or the gradient, or None without texture image and gradient stops.
+    fn text_fill_source<'a>(
+        &'a mut self,
+        fill: &'a TextFill,
+    ) -> Result<Option<TextFillSource<'a>>, ImageProcessingError> {
+        if let Some(image) = fill.image.as_deref() {
+            let texture = self.image_cache.get(image, None).ok_or_else(|| {
+                ImageProcessingError::ImageLoadError(format!("Failed to load: {image:?}"))
+            })?;
+            return Ok(Some(TextFillSource::Texture(texture)));
+        }
+        if fill.gradient.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(TextFillSource::Gradient {
+            stops: &fill.gradient,
+            direction: fill.direction.unwrap_or(SensorDirection::LeftToRight),
+        }))
+    }
+
+    /// Get the text segments of each layout line.
+    ///
+    /// The segment colors are only kept for a single left-to-right line. Multiline and
+    /// right-to-left texts are drawn in the given font color of the integer part, also if the
+    /// unit is placed in front of the value.
+    fn line_segments(
+        layout: &TextLayout,
+        segments: &[TextSegment],
+        rtl: bool,
+        font_color: Rgba<u8>,
+    ) -> Vec<Vec<TextSegment>> {
+        if layout.lines.len() == 1 && !rtl {
+            return vec![segments.to_vec()];
+        }
+
+        layout
+            .lines
+            .iter()
+            .map(|line| vec![TextSegment::new(line.text.clone(), font_color)])
+            .collect()
+    }
+
+    /// Draw the text segments of a line one after another, starting at the given position.
+    fn draw_segments(
+        image: &mut RgbaImage,
+        layout: &TextLayout,
+        x: i32,
+        y: i32,
+        segments: &[TextSegment],
+    ) {
+        let mut x = x;
+        for segment in segments {
+            draw_text_mut(
+                image,
+                segment.color,
+                x,
+                y,
+                layout.scale,
+                &layout.font,
+                &segment.text,
+            );
+            x += text_size(layout.scale, &layout.font, &segment.text).0 as i32;
+        }
+    }
+
+    /// Draw the aligned text lines on a transparent buffer and paste it rotated clockwise around
+    /// the center of the text.
+    fn draw_rotated_text(
+        background: &mut RgbaImage,
+        layout: &TextLayout,
+        line_segments: &[Vec<TextSegment>],
+        rotation: i32,
+        fill: Option<&TextFillSource>,
+    ) {
+        let (text_img, min_x, min_y) = Self::draw_text_buffer(layout, line_segments, fill);
+
+        let rotated = rotate_image(&text_img, rotation, RotationQuality::Best, true);
+        let center_x = min_x + (text_img.width() / 2) as i32;
+        let center_y = min_y + (text_img.height() / 2) as i32;
+        PanelRenderer::paste_image(
+            background,
+            &rotated,
+            center_x - (rotated.width() / 2) as i32,
+            center_y - (rotated.height() / 2) as i32,
+        );
+    }
+
+    /// Draw the aligned text lines on a transparent buffer of the text bounds, and apply the
+    /// optional text fill.
+    ///
+    /// returns: the text buffer with the panel position of its top left corner.
+    fn draw_text_buffer(
+        layout: &TextLayout,
+        line_segments: &[Vec<TextSegment>],
+        fill: Option<&TextFillSource>,
+    ) -> (RgbaImage, i32, i32) {
+        let line_height = {
+            let scaled_font = layout.font.as_scaled(layout.scale);
+            (scaled_font.height() + scaled_font.line_gap()).ceil() as i32
+        };
+        // the glyphs are not always drawn within the text size, a padding avoids clipping them
+        let padding = line_height;
+        let min_x = layout
+            .lines
+            .iter()
+            .map(|line| line.x)
+            .min()
+            .unwrap_or_default()
+            - padding;
+        let min_y = layout
+            .lines
+            .iter()
+            .map(|line| line.y)
+            .min()
+            .unwrap_or_default()
+            - padding;
+        let max_x = layout
+            .lines
+            .iter()
+            .map(|line| line.x + text_size(layout.scale, &layout.font, &line.text).0 as i32)
+            .max()
+            .unwrap_or_default()
+            + padding;
+        let max_y = layout
+            .lines
+            .iter()
+            .map(|line| line.y + line_height)
+            .max()
+            .unwrap_or_default()
+            + padding;
+
+        let mut text_img = RgbaImage::new((max_x - min_x) as u32, (max_y - min_y) as u32);
+        for (line, segments) in layout.lines.iter().zip(line_segments) {
+            Self::draw_segments(
+                &mut text_img,
+                layout,
+                line.x - min_x,
+                line.y - min_y,
+                segments,
+            );
+        }
+        if let Some(fill) = fill {
+            fill_text(&mut text_img, fill);
+        }
+
+        (text_img, min_x, min_y)
+    }
+
+    /// Calculate the font, scale and pixel position of a text sensor.
+    ///
+    /// The font family of the sensor is used, or the default font family of the panel if the
+    /// sensor doesn't specify one.
+    ///
+    /// The text is split into multiple lines at newline characters. Each line is aligned
+    /// individually. With the default [TextAnchor::Center], the lines are vertically
+    /// centered around the single line position. Otherwise, the first line is anchored at the sensor
+    /// `y` position and the following lines are placed below.
+    fn text_layout(&mut self, sensor: &Sensor, text: &str) -> TextLayout {
+        let font_family = sensor
+            .font_family
+            .as_ref()
+            .filter(|font_family| !font_family.is_empty())
+            .or(self.panel_font_family.as_ref());
+        let font = if let Some(font_family) = font_family {
+            self.font_handler.get_ttf_font_or_default(font_family)
+        } else {
+            self.font_handler.fallback_font()
+        };
+        let font_size = sensor.font_size.unwrap_or(14) as f32;
+        // TODO verify pixel scaling! Is font_size point size or pixel size?
+        // TODO some font size calculation is missing, dpi scaling? internal padding?
+        //      The adjustment hack is required to get the correct size of the rendered text.
+        let adjustment_hack = 0.75;
+        let scale = font.pt_to_px_scale(font_size * adjustment_hack).unwrap();
+
+        let lines: Vec<String> = text
+            .split('\n')
+            .map(|line| line.trim_end_matches('\r'))
+            .map(|line| {
+                if sensor.rtl {
+                    bidi_visual_order(line)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        let sizes: Vec<(u32, u32)> = lines
+            .iter()
+            .map(|line| text_size(scale, &font, line))
+            .collect();
+        let line_height = {
+            let scaled_font = font.as_scaled(scale);
+            (scaled_font.height() + scaled_font.line_gap()).round() as i32
+        };
+        let max_height = sizes.iter().map(|size| size.1).max().unwrap_or_default();
+
+        let width = sensor.width.unwrap_or_default() as i32;
+        let height = sensor.height.unwrap_or_default() as i32;
+        // `draw_text_mut` places the glyph baseline at the font ascent below the y-position, and the
+        // text height is the height of the tallest glyph above the baseline.
+        let ascent = font.as_scaled(scale).ascent();
+        let y = match sensor.text_anchor {
+            TextAnchor::Center => {
+                // center the glyphs of a single line on the sensor position
+                let y = sensor.y + height / 2 - (ascent - max_height as f32 / 2.0).round() as i32;
+                y - (lines.len() as i32 - 1) * line_height / 2
+            }
+            TextAnchor::TopLeft => sensor.y,
+            TextAnchor::Baseline => sensor.y - ascent.round() as i32,
+        };
+
+        let lines = lines
+            .into_iter()
+            .zip(sizes)
+            .enumerate()
+            .map(|(idx, (line, size))| {
+                let x = match sensor.text_align.unwrap_or_default() {
+                    TextAlign::Left => sensor.x,
+                    TextAlign::Center => sensor.x + width / 2 - (size.0 / 2) as i32,
+                    TextAlign::Right => sensor.x + width - size.0 as i32,
+                };
+                let y = y + idx as i32 * line_height;
+
+                debug!(
+                    "Sensor({:03},{:03}), pixel({x:03},{y:03}), size{size:?}: {line}",
+                    sensor.x, sensor.y
+                );
+
+                TextLine { text: line, x, y }
+            })
+            .collect();
+
+        TextLayout { font, scale, lines }
+    }
+
+    /// Explain how a single sensor is resolved, formatted and positioned without rendering it.
+    ///
+    /// # Arguments
+    ///
+    /// * `panel`: the panel configuration
+    /// * `label`: the sensor label identifier in the panel configuration
+    /// * `values`: current sensor values
+    ///
+    /// returns: the [SensorExplanation], or `None` if the panel doesn't contain the sensor.
+    pub fn explain_sensor(
+        &mut self,
+        panel: &Panel,
+        label: &str,
+        values: &HashMap<String, String>,
+    ) -> Option<SensorExplanation> {
+        let sensor = panel.sensor.iter().find(|sensor| sensor.label == label)?;
+        let now: DateTime<Local> = Local::now();
+        self.panel_font_family = panel.font_family.clone();
+
+        let (integer_digits, decimal_digits) = self.text_digits(sensor);
+        let resolved = self.resolve_value(sensor, values, &now);
+        let text = match (sensor.mode, &sensor.template, &resolved) {
+            (SensorMode::Text, Some(template), _) => Some(expand_template(
+                template,
+                sensor.normalize.as_ref(),
+                values,
+                &now,
+            )),
+            (SensorMode::Text, None, Some((value, unit))) => {
+                let value_type = ValueType::of(sensor.source_label(), values);
+                Some(self.format_text(sensor, value, unit, value_type))
+            }
+            _ => None,
+        };
+        let position = text
+            .as_ref()
+            .map(|text| self.text_layout(sensor, text).position());
+        let (value, unit) = match resolved {
+            Some((value, unit)) => (Some(value), unit),
+            None => (None, sensor.unit.clone().unwrap_or_default()),
+        };
+
+        Some(SensorExplanation {
+            label: sensor.label.clone(),
+            mode: sensor.mode,
+            source: sensor.source_label().to_string(),
+            value,
+            unit,
+            integer_digits,
+            decimal_digits,
+            text,
+            position,
+        })
+    }
+
+    /// Mode 2 - Circular/Arc progress indicator
+    fn render_fan(
+        &mut self,
+        sensor: &Sensor,
+        value: &str,
+        direction: SensorDirection,
+    ) -> Result<(), ImageProcessingError> {
         if !matches!(
             direction,
             SensorDirection::LeftToRight | SensorDirection::RightToLeft
@@ -277,46 +1600,40 @@ impl PanelRenderer {
             ImageProcessingError::ImageLoadError("No picture specified".to_string())
         })?;
 
-        let target_image = self
-            .image_cache
-            .get(pic_path, None)
-            .ok_or_else(|| {
-                ImageProcessingError::ImageLoadError(format!("Failed to load: {:?}", pic_path))
-            })?
-            .clone();
-
-        let min_angle = sensor.min_angle.unwrap_or(0) as f32;
-        let max_angle = sensor.max_angle.unwrap_or(180) as f32;
         let min_value = sensor.min_value.unwrap_or(0.0);
         let max_value = sensor.max_value.unwrap_or(100.0);
+        let current_value = parse_indicator_value(sensor, value)?;
 
-        let current_value = value
-            .parse::<f32>()
-            .map_err(|_| ImageProcessingError::MathError("Invalid value".to_string()))?;
+        let color = clamp_color(sensor, current_value, min_value, max_value);
+        let target_image = self.get_sensor_pic(sensor, pic_path, None, color)?;
 
-        if current_value <= min_value {
-            return Ok(());
-        }
+        let (min_angle, max_angle) = self.sensor_angles(sensor, 180);
 
-        let progress = if current_value >= max_value {
-            1.0
+        let sweep = if current_value <= min_value {
+            // only show a small at-min cue if the at-min color is set
+            if color.is_none() {
+                return Ok(());
+            }
+            FAN_AT_MIN_SWEEP.min(max_angle - min_angle)
+        } else if current_value >= max_value {
+            max_angle - min_angle
         } else {
-            (current_value - min_value) / (max_value - min_value)
+            (max_angle - min_angle) * (current_value - min_value) / (max_value - min_value)
         };
 
         let (start_angle, end_angle) = if direction == SensorDirection::LeftToRight {
             // Clockwise
             let start = min_angle - 90.0;
-            let end = min_angle + (max_angle - min_angle) * progress - 90.0;
+            let end = min_angle + sweep - 90.0;
             (start, end)
         } else {
             // Counter-clockwise
-            let start = 360.0 - min_angle - (max_angle - min_angle) * progress - 90.0;
+            let start = 360.0 - min_angle - sweep - 90.0;
             let end = 360.0 - min_angle - 90.0;
             (start, end)
         };
 
-        if let Some(sector_layer) = self.get_layer(SensorMode::Fan) {
+        if let Some(sector_layer) = self.get_layer(SensorMode::Fan, sensor.blend) {
             PanelRenderer::draw_pie_slice(
                 sector_layer,
                 &target_image,
@@ -339,79 +1656,70 @@ impl PanelRenderer {
         value: &str,
         direction: SensorDirection,
     ) -> Result<(), ImageProcessingError> {
-        let pic_path = sensor.pic.as_ref().ok_or_else(|| {
-            ImageProcessingError::ImageLoadError("No picture specified".to_string())
-        })?;
-
-        let mut processed_img = self
-            .image_cache
-            .get(pic_path, None)
-            .ok_or_else(|| {
-                ImageProcessingError::ImageLoadError(format!("Failed to load: {:?}", pic_path))
-            })?
-            .clone();
-
+        let current_value = parse_indicator_value(sensor, value)?;
         let min_val = sensor.min_value.unwrap_or(0.0);
         let max_val = sensor.max_value.unwrap_or(100.0);
-
-        let current_value = value
-            .parse::<f32>()
-            .map_err(|_| ImageProcessingError::MathError("Invalid value".to_string()))?;
+        let color = clamp_color(sensor, current_value, min_val, max_val);
+
+        let pic_path = sensor.pic.as_deref().filter(|pic| !pic.is_empty());
+        let mut processed_img = match (pic_path, sensor.gradient.is_empty()) {
+            (Some(pic_path), _) => self.get_sensor_pic(sensor, pic_path, None, color)?,
+            (None, false) => RgbaImage::from_pixel(
+                sensor.width.unwrap_or_default(),
+                sensor.height.unwrap_or_default(),
+                Rgba([255, 255, 255, 255]),
+            ),
+            (None, true) => {
+                return Err(ImageProcessingError::ImageLoadError(
+                    "No picture specified".to_string(),
+                ));
+            }
+        };
+        let fill_color = match sensor.gradient.is_empty() {
+            true => None,
+            false => color.or_else(|| gradient_color(&sensor.gradient, current_value)),
+        };
+        if let Some(color) = fill_color {
+            for pixel in processed_img.pixels_mut() {
+                pixel.0[..3].copy_from_slice(&color.0);
+            }
+        }
 
         let clamped_value = current_value.clamp(min_val, max_val);
         let progress = ((clamped_value - min_val) / (max_val - min_val)).clamp(0.0, 1.0);
 
-        let (img_w, img_h) = processed_img.dimensions();
-
-        // Create progress mask based on direction
-        let crop_rect = match direction {
-            SensorDirection::LeftToRight => {
-                let crop_w = (img_w as f32 * progress).round() as u32;
-                (0, 0, crop_w, img_h)
-            }
-            SensorDirection::RightToLeft => {
-                let crop_w = (img_w as f32 * progress).round() as u32;
-                (img_w - crop_w, 0, img_w, img_h)
-            }
-            SensorDirection::TopToBottom => {
-                let crop_h = (img_h as f32 * progress).round() as u32;
-                (0, 0, img_w, crop_h)
-            }
-            SensorDirection::BottomToTop => {
-                let crop_h = (img_h as f32 * progress).round() as u32;
-                (0, img_h - crop_h, img_w, img_h)
-            }
-        };
-
-        // Apply crop mask to image
-        self.apply_progress_mask(&mut processed_img, crop_rect, direction);
+        // Apply progress mask to image
+        PanelRenderer::apply_progress_mask(
+            &mut processed_img,
+            progress,
+            direction,
+            sensor.fill_origin,
+            sensor.min_visible,
+            sensor.feather,
+        );
 
-        if self.save_processed_pic {
-            let name = format!(
-                "processed_img-{}{}.png",
-                sensor.label,
-                self.img_suffix.as_deref().unwrap_or_default()
+        if self.save_processed_pic
+            && let Err(e) = self.save_img(&processed_img, "processed_img", &sensor.label)
+        {
+            warn!(
+                "Failed to save processed image of sensor {}: {e}",
+                sensor.label
             );
-            if let Err(e) = processed_img.save(self.img_save_path.join(name)) {
-                error!("Error saving processed image: {e}");
-            }
         }
 
         let pos_x = sensor.x;
         let pos_y = sensor.y;
 
-        if let Some(progress_layer) = self.get_layer(SensorMode::Progress) {
+        if let Some(progress_layer) = self.get_layer(SensorMode::Progress, sensor.blend) {
             PanelRenderer::paste_image(progress_layer, &processed_img, pos_x, pos_y);
 
-            if self.save_progress_layer {
-                let name = format!(
-                    "progress_layer-{}{}.png",
-                    sensor.label,
-                    self.img_suffix.as_deref().unwrap_or_default()
+            if self.save_progress_layer
+                && let Err(e) = self.save_img(&processed_img, "progress_layer", &sensor.label)
+            {
+                warn!(
+                    "Failed to save progress layer of sensor {}: {e}",
+                    sensor.label
                 );
-                if let Err(e) = processed_img.save(self.img_save_path.join(name)) {
-                    error!("Error saving progress layer image: {e}");
-                }
             }
         }
         Ok(())
@@ -447,19 +1755,12 @@ impl PanelRenderer {
         } else {
             None
         };
-        let pic = self
-            .image_cache
-            .get(pic_path, size)
-            .ok_or_else(|| {
-                ImageProcessingError::ImageLoadError(format!("Failed to load: {:?}", pic_path))
-            })?
-            .clone();
-
         let min_val = sensor.min_value.unwrap_or(0.0);
         let max_val = sensor.max_value.unwrap_or(100.0);
-        let current_value = value
-            .parse::<f32>()
-            .map_err(|_| ImageProcessingError::MathError("Invalid value".to_string()))?;
+        let current_value = parse_indicator_value(sensor, value)?;
+
+        let color = clamp_color(sensor, current_value, min_val, max_val);
+        let pic = self.get_sensor_pic(sensor, pic_path, size, color)?;
 
         let clamped_value = current_value.clamp(min_val, max_val);
 
@@ -470,8 +1771,7 @@ impl PanelRenderer {
             (clamped_value - min_val) / (max_val - min_val)
         };
 
-        let mut min_angle = sensor.min_angle.unwrap_or(0) as f32;
-        let mut max_angle = sensor.max_angle.unwrap_or(360) as f32;
+        let (mut min_angle, mut max_angle) = self.sensor_angles(sensor, 360);
 
         // Adjust angles for counter-clockwise
         if direction == SensorDirection::RightToLeft {
@@ -486,20 +1786,63 @@ impl PanelRenderer {
         let offset_x = (xz_x as f32 * angle_rad.cos() - xz_y as f32 * angle_rad.sin()) as i32;
         let offset_y = (xz_x as f32 * angle_rad.sin() + xz_y as f32 * angle_rad.cos()) as i32;
 
-        // Rotate the image
+        // Rotate non-square images on an expanded canvas to avoid clipping the pointer corners.
+        // Square images keep their canvas, the pointer is usually centered in the image.
         let angle = angle.round() as i32;
-        let rotated_pic = rotate_image(&pic, -angle);
+        let expand = pic.width() != pic.height();
+        let rotated_pic = rotate_image(&pic, -angle, RotationQuality::Good, expand);
 
         // Calculate final position
         let final_x = x_center + offset_x - (rotated_pic.width() / 2) as i32;
         let final_y = y_center + offset_y - (rotated_pic.height() / 2) as i32;
 
-        if let Some(pointer_layer) = self.get_layer(SensorMode::Pointer) {
+        if let Some(pointer_layer) = self.get_layer(SensorMode::Pointer, sensor.blend) {
             PanelRenderer::paste_image(pointer_layer, &rotated_pic, final_x, final_y);
         }
         Ok(())
     }
 
+    /// Get the normalized `min_angle` and `max_angle` range of a fan or pointer sensor.
+    ///
+    /// See [normalize_angle_range]. A warning is logged once per sensor if the configured angles
+    /// are adjusted.
+    fn sensor_angles(&mut self, sensor: &Sensor, default_max_angle: i32) -> (f32, f32) {
+        let min_angle = sensor.min_angle.unwrap_or(0);
+        let max_angle = sensor.max_angle.unwrap_or(default_max_angle);
+        let swap_inverted = sensor.mode == SensorMode::Fan;
+        let (norm_min, norm_max) = normalize_angle_range(min_angle, max_angle, swap_inverted);
+
+        if (norm_min, norm_max) != (min_angle, max_angle)
+            && self.angle_warnings.insert(sensor.label.clone())
+        {
+            warn!(
+                "Sensor {}: adjusted invalid angle range {min_angle}..{max_angle} to {norm_min}..{norm_max}",
+                sensor.label
+            );
+        }
+
+        (norm_min as f32, norm_max as f32)
+    }
+
+    /// Get a copy of the sensor image from the image cache, tinted with the optional `color`, or
+    /// with the optional sensor tint color.
+    fn get_sensor_pic(
+        &mut self,
+        sensor: &Sensor,
+        pic_path: &str,
+        size: Option<Size>,
+        color: Option<Rgb<u8>>,
+    ) -> Result<RgbaImage, ImageProcessingError> {
+        let pic = match color.or(sensor.tint.map(|tint| *tint)) {
+            Some(color) => self.image_cache.get_tinted(pic_path, size, color),
+            None => self.image_cache.get(pic_path, size),
+        };
+
+        pic.cloned().ok_or_else(|| {
+            ImageProcessingError::ImageLoadError(format!("Failed to load: {:?}", pic_path))
+        })
+    }
+
     /// Draws a pie‐slice sector of the `source` image into the `layer` destination.
     ///
     /// Pixels in the sector are alpha-blended from source into the destination layer at the given
@@ -592,29 +1935,189 @@ impl PanelRenderer {
         }
     }
 
+    /// Draw a 1px bounding box at `(x, y, width, height)` and a crosshair at the anchor point `(x, y)`
+    /// of each sensor. The color depends on the sensor mode.
+    ///
+    /// The bounding box is only drawn if the sensor defines a width and height.
+    fn draw_debug_boxes(image: &mut RgbaImage, panel: &Panel) {
+        const CROSSHAIR_SIZE: f32 = 4.0;
+
+        for sensor in &panel.sensor {
+            if panel.is_sensor_filtered(sensor) {
+                continue;
+            }
+            let color = match sensor.mode {
+                SensorMode::Text => Rgba([255, 0, 255, 255]),
+                SensorMode::Fan => Rgba([0, 255, 255, 255]),
+                SensorMode::Progress => Rgba([255, 255, 0, 255]),
+                SensorMode::Pointer => Rgba([0, 255, 0, 255]),
+                SensorMode::QrCode => Rgba([255, 255, 255, 255]),
+            };
+
+            if let (Some(width), Some(height)) = (sensor.width, sensor.height)
+                && width > 0
+                && height > 0
+            {
+                let rect = Rect::at(sensor.x, sensor.y).of_size(width, height);
+                draw_hollow_rect_mut(image, rect, color);
+            }
+
+            let (x, y) = (sensor.x as f32, sensor.y as f32);
+            draw_line_segment_mut(
+                image,
+                (x - CROSSHAIR_SIZE, y),
+                (x + CROSSHAIR_SIZE, y),
+                color,
+            );
+            draw_line_segment_mut(
+                image,
+                (x, y - CROSSHAIR_SIZE),
+                (x, y + CROSSHAIR_SIZE),
+                color,
+            );
+        }
+    }
+
+    /// Render an error frame for a panel which failed to render.
+    ///
+    /// The panel name is shown in a red banner at the top, and the error text below on a black
+    /// background. Text lines wider than the display are wrapped.
+    pub fn render_error_frame(&self, panel_name: &str, error: &str) -> RgbaImage {
+        const FONT_HEIGHT: f32 = 18.0;
+        const MARGIN: u32 = 8;
+
+        let mut image = RgbaImage::from_pixel(self.size.0, self.size.1, Rgba([0, 0, 0, 255]));
+        PanelRenderer::draw_banner(&mut image, &format!("RENDER ERROR: {panel_name}"));
+
+        let font = FontHandler::default_font();
+        let scale = PxScale::from(FONT_HEIGHT);
+        let line_height = {
+            let scaled_font = font.as_scaled(scale);
+            (scaled_font.height() + scaled_font.line_gap()).ceil() as u32
+        };
+        let max_width = self.size.0.saturating_sub(2 * MARGIN);
+        let lines = error
+            .lines()
+            .flat_map(|line| wrap_text(scale, &font, line, max_width));
+        let mut y = BANNER_HEIGHT + MARGIN;
+        for line in lines {
+            if y >= self.size.1 {
+                break;
+            }
+            draw_text_mut(
+                &mut image,
+                Rgba([255, 255, 255, 255]),
+                MARGIN as i32,
+                y as i32,
+                scale,
+                &font,
+                &line,
+            );
+            y += line_height;
+        }
+
+        image
+    }
+
+    /// Draw a red warning banner with a centered text at the top of the image.
+    fn draw_banner(image: &mut RgbaImage, text: &str) {
+        const FONT_HEIGHT: f32 = 28.0;
+
+        let font = FontHandler::default_font();
+        let scale = PxScale::from(FONT_HEIGHT);
+        let height = BANNER_HEIGHT.min(image.height());
+        if height == 0 || image.width() == 0 {
+            return;
+        }
+
+        draw_filled_rect_mut(
+            image,
+            Rect::at(0, 0).of_size(image.width(), height),
+            Rgba([192, 0, 0, 255]),
+        );
+
+        let (text_width, _) = text_size(scale, &font, text);
+        let scaled_font = font.as_scaled(scale);
+        let text_height = scaled_font.ascent() - scaled_font.descent();
+        let x = (image.width() as i32 - text_width as i32) / 2;
+        let y = (height as f32 - text_height) as i32 / 2;
+        draw_text_mut(image, Rgba([255, 255, 255, 255]), x, y, scale, &font, text);
+    }
+
     /// Apply progress mask to image based on crop rectangle and direction
+    ///
+    /// With a `feather` width, the alpha channel is ramped up over the given number of pixels at
+    /// the inner fill edges instead of a hard cut. Fill edges at the image border are not feathered.
     fn apply_progress_mask(
-        &self,
         image: &mut RgbaImage,
-        crop_rect: (u32, u32, u32, u32),
+        progress: f32,
         direction: SensorDirection,
+        fill_origin: FillOrigin,
+        min_visible: u32,
+        feather: u32,
     ) {
-        let (crop_x, crop_y, crop_w, crop_h) = crop_rect;
         let (img_w, img_h) = image.dimensions();
+        let horizontal = matches!(
+            direction,
+            SensorDirection::LeftToRight | SensorDirection::RightToLeft
+        );
+        let reverse = matches!(
+            direction,
+            SensorDirection::RightToLeft | SensorDirection::BottomToTop
+        );
+        let length = if horizontal { img_w } else { img_h };
+        let fill = ((length as f32 * progress).round() as u32)
+            .max(min_visible)
+            .min(length);
+
+        // visible progress fill ranges along the direction axis, the second range is only used
+        // for filling from the edges
+        let ranges = match fill_origin {
+            _ if fill == length => [0..length, 0..0],
+            FillOrigin::Start if reverse => [length - fill..length, 0..0],
+            FillOrigin::Start => [0..fill, 0..0],
+            FillOrigin::Center => {
+                let start = (length - fill) / 2;
+                [start..start + fill, 0..0]
+            }
+            FillOrigin::Edges => {
+                let head = fill.div_ceil(2);
+                [0..head, length - (fill - head)..length]
+            }
+        };
 
-        // Create mask - set alpha to 0 outside crop area
-        for y in 0..img_h {
-            for x in 0..img_w {
-                let should_keep = match direction {
-                    SensorDirection::LeftToRight => x < crop_w,
-                    SensorDirection::RightToLeft => x >= crop_x,
-                    SensorDirection::TopToBottom => y < crop_h,
-                    SensorDirection::BottomToTop => y >= crop_y,
+        // alpha factor of each position along the direction axis
+        let coverage: Vec<f32> = (0..length)
+            .map(|pos| {
+                let Some(range) = ranges.iter().find(|range| range.contains(&pos)) else {
+                    return 0.0;
+                };
+                let from_start = if range.start > 0 {
+                    pos - range.start
+                } else {
+                    u32::MAX
                 };
+                let from_end = if range.end < length {
+                    range.end - 1 - pos
+                } else {
+                    u32::MAX
+                };
+                let distance = from_start.min(from_end);
+                if distance >= feather {
+                    1.0
+                } else {
+                    (distance + 1) as f32 / (feather + 1) as f32
+                }
+            })
+            .collect();
 
-                if !should_keep {
+        // Create mask - scale alpha with the coverage, 0 outside progress fill
+        for y in 0..img_h {
+            for x in 0..img_w {
+                let coverage = coverage[if horizontal { x } else { y } as usize];
+                if coverage < 1.0 {
                     let pixel = image.get_pixel_mut(x, y);
-                    pixel[3] = 0; // Set alpha to 0 (transparent)
+                    pixel[3] = (pixel[3] as f32 * coverage).round() as u8;
                 }
             }
         }
@@ -655,23 +2158,50 @@ impl PanelRenderer {
         }
     }
 
-    fn create_img_save_path(&mut self) {
-        if (self.save_render_img || self.save_processed_pic || self.save_progress_layer)
-            && let Err(e) = fs::create_dir_all(&self.img_save_path)
-        {
-            error!(
-                "Error creating image output path {:?}: {e}",
-                self.img_save_path
-            );
+    /// Save an image in the image output directory with the configured format and filename template.
+    fn save_img(&self, image: &RgbaImage, img_type: &str, label: &str) -> image::ImageResult<()> {
+        let default_template = match img_type {
+            "render" => "render_{panel}{suffix}",
+            _ => "{type}-{label}{suffix}",
+        };
+        let template = self
+            .img_name_template
+            .as_deref()
+            .unwrap_or(default_template);
+        let timestamp = Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+        let name = expand_img_name(
+            template,
+            img_type,
+            &self.panel_name,
+            label,
+            self.img_suffix.as_deref().unwrap_or_default(),
+            &timestamp,
+        );
+        let extension = self
+            .img_save_format
+            .extensions_str()
+            .first()
+            .copied()
+            .unwrap_or("img");
+        let path = self.img_save_path.join(format!("{name}.{extension}"));
+        fs::create_dir_all(&self.img_save_path)?;
+
+        match image.save_with_format(&path, self.img_save_format) {
+            // e.g. JPEG doesn't support an alpha channel
+            Err(ImageError::Unsupported(_)) => DynamicImage::ImageRgba8(image.clone())
+                .to_rgb8()
+                .save_with_format(path, self.img_save_format),
+            result => result,
         }
     }
 
-    fn get_layer(&mut self, mode: SensorMode) -> Option<&mut RgbaImage> {
-        if !self.composite_layer_map.contains_key(&mode) {
-            self.composite_layer_map.insert(mode, self.create_layer());
+    fn get_layer(&mut self, mode: SensorMode, blend: BlendMode) -> Option<&mut RgbaImage> {
+        let key = (mode, blend);
+        if !self.composite_layer_map.contains_key(&key) {
+            self.composite_layer_map.insert(key, self.create_layer());
         }
 
-        self.composite_layer_map.get_mut(&mode)
+        self.composite_layer_map.get_mut(&key)
     }
 
     /// Create an overlay image buffer with the same dimensions as the panel
@@ -680,11 +2210,19 @@ impl PanelRenderer {
     }
 
     /// Composite all layers into final image
+    ///
+    /// The regular layers are composited in sensor mode order: fan, progress and pointer. Layers of
+    /// sensors with a `blend` mode are composited afterwards in the same sensor mode order, so
+    /// the z-order of sensors without a `blend` mode is not changed.
     fn composite_layers(&mut self, background: &mut RgbaImage) {
         // quick and dirty, this should be an ordered enum variant list
         let modes = [SensorMode::Fan, SensorMode::Progress, SensorMode::Pointer];
-        for mode in modes {
-            if let Some(layer) = self.composite_layer_map.get(&mode) {
+        let blend_modes = [BlendMode::Normal, BlendMode::Add, BlendMode::Multiply];
+        for key in blend_modes
+            .into_iter()
+            .flat_map(|blend| modes.map(|mode| (mode, blend)))
+        {
+            if let Some(layer) = self.composite_layer_map.get(&key) {
                 // Find bounding box of non-transparent pixels
                 let bbox = PanelRenderer::get_bounding_box(layer);
 
@@ -696,16 +2234,7 @@ impl PanelRenderer {
                             if layer_pixel[3] > 0 {
                                 // If not fully transparent
                                 let final_pixel = background.get_pixel_mut(x, y);
-
-                                // Alpha compositing
-                                let alpha = layer_pixel[3] as f32 / 255.0;
-                                let inv_alpha = 1.0 - alpha;
-
-                                for i in 0..4 {
-                                    final_pixel[i] = ((layer_pixel[i] as f32 * alpha)
-                                        + (final_pixel[i] as f32 * inv_alpha))
-                                        as u8;
-                                }
+                                blend_pixel(final_pixel, &layer_pixel, key.1);
                             }
                         }
                     }
@@ -744,3 +2273,1780 @@ impl PanelRenderer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rounding;
+    use chrono::Datelike;
+    use rstest::rstest;
+    use std::ops::Range;
+    use tempfile::TempDir;
+
+    /// Create a temporary configuration directory with a white image file of the given size.
+    ///
+    /// The directory is removed when the returned [TempDir] is dropped, also if the test fails.
+    fn image_dir(name: &str, (width, height): (u32, u32)) -> TempDir {
+        let dir = tempfile::tempdir().expect("failed to create test directory");
+        RgbaImage::from_pixel(width, height, Rgba([255, 255, 255, 255]))
+            .save(dir.path().join(name))
+            .unwrap();
+        dir
+    }
+
+    #[test]
+    fn wrap_text_splits_long_lines() {
+        let font = FontHandler::default_font();
+        let scale = PxScale::from(18.0);
+        let text = "Failed to load: \"missing.png\"";
+        let width = text_size(scale, &font, text).0;
+
+        assert_eq!(wrap_text(scale, &font, text, width), [text]);
+        let lines = wrap_text(scale, &font, text, width / 2);
+        assert!(lines.len() > 1);
+        assert_eq!(lines.concat(), text);
+        assert!(
+            lines
+                .iter()
+                .all(|line| text_size(scale, &font, line).0 <= width / 2)
+        );
+    }
+
+    #[test]
+    fn render_error_frame_shows_banner_and_error_text() {
+        let renderer = PanelRenderer::new((300, 100), "fonts", "cfg");
+
+        let image = renderer.render_error_frame("CPU", "ImageLoadError");
+
+        assert_eq!(image.dimensions(), (300, 100));
+        assert_eq!(*image.get_pixel(0, 0), Rgba([192, 0, 0, 255]));
+        assert!(
+            image
+                .enumerate_pixels()
+                .any(|(_, y, pixel)| y >= BANNER_HEIGHT && pixel.0 == [255, 255, 255, 255])
+        );
+    }
+
+    #[test]
+    fn draw_banner_fills_top_of_image() {
+        let mut image = RgbaImage::from_pixel(200, 100, Rgba([0, 0, 0, 255]));
+
+        PanelRenderer::draw_banner(&mut image, "STALE");
+
+        assert_eq!(*image.get_pixel(0, 0), Rgba([192, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(199, 39), Rgba([192, 0, 0, 255]));
+        assert_eq!(*image.get_pixel(0, 40), Rgba([0, 0, 0, 255]));
+        let text_pixels = image
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| pixel.0[1] > 0)
+            .count();
+        assert!(text_pixels > 0);
+        assert!(
+            image
+                .enumerate_pixels()
+                .all(|(_, y, pixel)| y < 40 || pixel.0 == [0, 0, 0, 255])
+        );
+    }
+
+    #[test]
+    fn render_keeps_debug_boxes_out_of_the_panel_frame() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [{"mode": 3, "label": "progress", "value": "", "unit": "", "x": 10,
+            "y": 20, "width": 30, "height": 15, "integerDigits": -1, "decimalDigits": -1, "pic": ""}]}"#,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((60, 60), "fonts", "cfg");
+        renderer.set_debug_boxes(true);
+
+        let frame = renderer.render(&panel, &HashMap::new()).unwrap().clone();
+        let debug_image = renderer.debug_image(&frame, &panel).unwrap();
+
+        let color = Rgba([255, 255, 0, 255]);
+        assert!(frame.pixels().all(|pixel| *pixel != color));
+        assert_eq!(*debug_image.get_pixel(10, 20), color);
+    }
+
+    #[test]
+    fn draw_debug_boxes_draws_rectangle_and_crosshair() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [{"mode": 3, "label": "progress", "value": "", "unit": "", "x": 10,
+            "y": 20, "width": 30, "height": 15, "integerDigits": -1, "decimalDigits": -1, "pic": ""}]}"#,
+        )
+        .unwrap();
+        let mut image = RgbaImage::new(60, 60);
+
+        PanelRenderer::draw_debug_boxes(&mut image, &panel);
+
+        let color = Rgba([255, 255, 0, 255]);
+        let transparent = Rgba([0, 0, 0, 0]);
+        // rectangle corners and edges
+        for (x, y) in [(10, 20), (39, 20), (10, 34), (39, 34), (25, 20), (10, 27)] {
+            assert_eq!(*image.get_pixel(x, y), color, "pixel ({x},{y})");
+        }
+        // crosshair at anchor point
+        for (x, y) in [(6, 20), (14, 20), (10, 16), (10, 24)] {
+            assert_eq!(*image.get_pixel(x, y), color, "pixel ({x},{y})");
+        }
+        // inside and outside of the rectangle
+        for (x, y) in [(25, 27), (40, 20), (10, 35), (0, 0)] {
+            assert_eq!(*image.get_pixel(x, y), transparent, "pixel ({x},{y})");
+        }
+    }
+
+    #[rstest]
+    #[case(0, 180, (0, 180))]
+    #[case(45, 315, (45, 315))]
+    #[case(0, 360, (0, 360))]
+    #[case(-160, 30, (-160, 30))]
+    #[case(-360, -180, (-360, -180))]
+    fn normalize_angle_range_keeps_valid_range(
+        #[case] min_angle: i32,
+        #[case] max_angle: i32,
+        #[case] expected: (i32, i32),
+    ) {
+        assert_eq!(normalize_angle_range(min_angle, max_angle, true), expected);
+    }
+
+    #[rstest]
+    #[case(180, 0, (0, 180))]
+    #[case(315, 45, (45, 315))]
+    fn normalize_angle_range_swaps_inverted_range(
+        #[case] min_angle: i32,
+        #[case] max_angle: i32,
+        #[case] expected: (i32, i32),
+    ) {
+        assert_eq!(normalize_angle_range(min_angle, max_angle, true), expected);
+    }
+
+    #[rstest]
+    #[case(180, 0, (180, 0))]
+    #[case(110, -110, (110, -110))]
+    #[case(400, 0, (40, -320))]
+    #[case(0, -720, (0, -360))]
+    fn normalize_angle_range_keeps_reversed_pointer_range(
+        #[case] min_angle: i32,
+        #[case] max_angle: i32,
+        #[case] expected: (i32, i32),
+    ) {
+        assert_eq!(normalize_angle_range(min_angle, max_angle, false), expected);
+    }
+
+    #[rstest]
+    #[case(400, 500, (40, 140))]
+    #[case(-400, -300, (320, 420))]
+    #[case(0, 720, (0, 360))]
+    #[case(90, -400, (320, 680))]
+    fn normalize_angle_range_wraps_out_of_range_angles(
+        #[case] min_angle: i32,
+        #[case] max_angle: i32,
+        #[case] expected: (i32, i32),
+    ) {
+        assert_eq!(normalize_angle_range(min_angle, max_angle, true), expected);
+    }
+
+    #[test]
+    fn expand_img_name_with_default_templates() {
+        assert_eq!(
+            expand_img_name("render_{panel}{suffix}", "render", "CPU", "", "-01", "ts"),
+            "render_CPU-01"
+        );
+        assert_eq!(
+            expand_img_name(
+                "{type}-{label}{suffix}",
+                "progress_layer",
+                "CPU",
+                "cpu_usage",
+                "-02",
+                "ts"
+            ),
+            "progress_layer-cpu_usage-02"
+        );
+    }
+
+    #[test]
+    fn expand_img_name_with_custom_template() {
+        let name = expand_img_name(
+            "{timestamp}_{panel}_{type}_{label}{suffix}{unknown}",
+            "processed_img",
+            "CPU",
+            "cpu_usage",
+            "-03",
+            "20250901_120000_123",
+        );
+        assert_eq!(name, "20250901_120000_123_CPU_processed_img_cpu_usage-03");
+    }
+
+    fn digits_panel() -> Panel {
+        serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 1, "label": "unset", "value": "", "unit": "", "x": 0, "y": 0,
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""},
+            {"mode": 1, "label": "set", "value": "", "unit": "", "x": 0, "y": 0,
+             "integerDigits": 3, "decimalDigits": 0, "pic": ""},
+            {"mode": 1, "label": "zero", "value": "", "unit": "", "x": 0, "y": 0,
+             "integerDigits": 0, "decimalDigits": 0, "pic": ""},
+            {"mode": 1, "label": "absent", "value": "", "unit": "", "x": 0, "y": 0, "pic": ""}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case::unset(0, (IntegerDigits::Auto, 0), (IntegerDigits::Fixed(2), 1))]
+    #[case::zero(2, (IntegerDigits::Zero, 0), (IntegerDigits::Zero, 0))]
+    #[case::absent(3, (IntegerDigits::Auto, 0), (IntegerDigits::Fixed(2), 1))]
+    fn text_digits_distinguishes_zero_from_unset(
+        #[case] sensor_idx: usize,
+        #[case] without_defaults: (IntegerDigits, usize),
+        #[case] with_defaults: (IntegerDigits, usize),
+    ) {
+        let panel = digits_panel();
+        let mut renderer = PanelRenderer::new((10, 10), "fonts", "cfg");
+        let sensor = &panel.sensor[sensor_idx];
+
+        assert_eq!(renderer.text_digits(sensor), without_defaults);
+        renderer.set_default_digits(Some(2), Some(1));
+        assert_eq!(renderer.text_digits(sensor), with_defaults);
+    }
+
+    #[test]
+    fn text_digits_without_defaults() {
+        let panel = digits_panel();
+        let renderer = PanelRenderer::new((10, 10), "fonts", "cfg");
+
+        assert_eq!(
+            renderer.text_digits(&panel.sensor[0]),
+            (IntegerDigits::Auto, 0)
+        );
+        assert_eq!(
+            renderer.text_digits(&panel.sensor[1]),
+            (IntegerDigits::Fixed(3), 0)
+        );
+    }
+
+    #[test]
+    fn text_digits_sensor_without_digits_inherits_defaults() {
+        let panel = digits_panel();
+        let mut renderer = PanelRenderer::new((10, 10), "fonts", "cfg");
+        renderer.set_default_digits(Some(2), Some(1));
+
+        assert_eq!(
+            renderer.text_digits(&panel.sensor[0]),
+            (IntegerDigits::Fixed(2), 1)
+        );
+    }
+
+    #[test]
+    fn text_digits_sensor_digits_override_defaults() {
+        let panel = digits_panel();
+        let mut renderer = PanelRenderer::new((10, 10), "fonts", "cfg");
+        renderer.set_default_digits(Some(2), Some(1));
+
+        assert_eq!(
+            renderer.text_digits(&panel.sensor[1]),
+            (IntegerDigits::Fixed(3), 0)
+        );
+    }
+
+    #[rstest]
+    #[case::normal(BlendMode::Normal, [128, 0, 0, 255])]
+    #[case::add_brightens(BlendMode::Add, [255, 0, 0, 255])]
+    #[case::multiply_darkens(BlendMode::Multiply, [64, 0, 0, 255])]
+    fn blend_pixel_opaque_half_bright_reds(#[case] blend: BlendMode, #[case] expected: [u8; 4]) {
+        let mut target = Rgba([128, 0, 0, 255]);
+
+        blend_pixel(&mut target, &Rgba([128, 0, 0, 255]), blend);
+
+        assert_eq!(target, Rgba(expected));
+    }
+
+    #[test]
+    fn blend_pixel_add_brightens_toward_white() {
+        let mut target = Rgba([128, 128, 128, 255]);
+
+        blend_pixel(&mut target, &Rgba([128, 0, 0, 255]), BlendMode::Add);
+
+        assert_eq!(target, Rgba([255, 128, 128, 255]));
+    }
+
+    #[rstest]
+    #[case::normal(BlendMode::Normal)]
+    #[case::add(BlendMode::Add)]
+    #[case::multiply(BlendMode::Multiply)]
+    fn blend_pixel_transparent_source_keeps_target(#[case] blend: BlendMode) {
+        let mut target = Rgba([10, 20, 30, 255]);
+
+        blend_pixel(&mut target, &Rgba([200, 200, 200, 0]), blend);
+
+        assert_eq!(target, Rgba([10, 20, 30, 255]));
+    }
+
+    #[test]
+    fn composite_layers_keeps_sensor_mode_order() {
+        let mut renderer = PanelRenderer::new((2, 1), "fonts", "cfg");
+        renderer
+            .get_layer(SensorMode::Pointer, BlendMode::Normal)
+            .unwrap()[(0, 0)] = RED;
+        renderer
+            .get_layer(SensorMode::Fan, BlendMode::Normal)
+            .unwrap()[(0, 0)] = BLUE;
+        renderer
+            .get_layer(SensorMode::Progress, BlendMode::Normal)
+            .unwrap()[(1, 0)] = BLUE;
+        renderer
+            .get_layer(SensorMode::Fan, BlendMode::Normal)
+            .unwrap()[(1, 0)] = RED;
+        let mut background = RgbaImage::from_pixel(2, 1, Rgba([0, 0, 0, 255]));
+
+        renderer.composite_layers(&mut background);
+
+        assert_eq!(background[(0, 0)], RED, "pointer must be above fan");
+        assert_eq!(background[(1, 0)], BLUE, "progress must be above fan");
+    }
+
+    #[test]
+    fn composite_layers_blends_opted_in_layers_last() {
+        let mut renderer = PanelRenderer::new((1, 1), "fonts", "cfg");
+        renderer.get_layer(SensorMode::Fan, BlendMode::Add).unwrap()[(0, 0)] = BLUE;
+        renderer
+            .get_layer(SensorMode::Pointer, BlendMode::Normal)
+            .unwrap()[(0, 0)] = RED;
+        let mut background = RgbaImage::from_pixel(1, 1, Rgba([0, 0, 0, 255]));
+
+        renderer.composite_layers(&mut background);
+
+        assert_eq!(background[(0, 0)], Rgba([255, 0, 255, 255]));
+    }
+
+    /// Get the visible columns of the first row of a masked progress image.
+    fn visible_columns(progress: f32, direction: SensorDirection, origin: FillOrigin) -> Vec<u32> {
+        let mut image = RgbaImage::from_pixel(8, 2, Rgba([255, 255, 255, 255]));
+
+        PanelRenderer::apply_progress_mask(&mut image, progress, direction, origin, 0, 0);
+
+        (0..8).filter(|&x| image.get_pixel(x, 0)[3] > 0).collect()
+    }
+
+    #[rstest]
+    #[case::left_to_right(SensorDirection::LeftToRight, vec![0, 1, 2, 3])]
+    #[case::right_to_left(SensorDirection::RightToLeft, vec![4, 5, 6, 7])]
+    fn progress_mask_from_start(#[case] direction: SensorDirection, #[case] expected: Vec<u32>) {
+        assert_eq!(visible_columns(0.5, direction, FillOrigin::Start), expected);
+    }
+
+    #[test]
+    fn progress_mask_center_out_reveals_middle_half() {
+        assert_eq!(
+            visible_columns(0.5, SensorDirection::LeftToRight, FillOrigin::Center),
+            vec![2, 3, 4, 5]
+        );
+    }
+
+    #[test]
+    fn progress_mask_center_in_reveals_both_ends() {
+        assert_eq!(
+            visible_columns(0.5, SensorDirection::LeftToRight, FillOrigin::Edges),
+            vec![0, 1, 6, 7]
+        );
+    }
+
+    #[rstest]
+    #[case::start(FillOrigin::Start)]
+    #[case::center(FillOrigin::Center)]
+    #[case::edges(FillOrigin::Edges)]
+    fn progress_mask_full_and_empty(#[case] origin: FillOrigin) {
+        assert_eq!(
+            visible_columns(1.0, SensorDirection::LeftToRight, origin),
+            (0..8).collect::<Vec<_>>()
+        );
+        assert!(visible_columns(0.0, SensorDirection::LeftToRight, origin).is_empty());
+    }
+
+    #[rstest]
+    #[case::start(FillOrigin::Start, vec![0, 1])]
+    #[case::center(FillOrigin::Center, vec![3, 4])]
+    fn progress_mask_min_visible_at_min_value(
+        #[case] origin: FillOrigin,
+        #[case] expected: Vec<u32>,
+    ) {
+        let mut image = RgbaImage::from_pixel(8, 1, Rgba([255, 255, 255, 255]));
+
+        PanelRenderer::apply_progress_mask(
+            &mut image,
+            0.0,
+            SensorDirection::LeftToRight,
+            origin,
+            2,
+            0,
+        );
+
+        let columns: Vec<u32> = (0..8).filter(|&x| image.get_pixel(x, 0)[3] > 0).collect();
+        assert_eq!(columns, expected);
+    }
+
+    #[rstest]
+    #[case::start(FillOrigin::Start, vec![255, 255, 191, 128, 64, 0, 0, 0])]
+    #[case::center(FillOrigin::Center, vec![0, 64, 128, 191, 191, 128, 64, 0])]
+    fn progress_mask_feathers_fill_edge(#[case] origin: FillOrigin, #[case] expected: Vec<u8>) {
+        let mut image = RgbaImage::from_pixel(8, 1, Rgba([255, 255, 255, 255]));
+        let progress = if origin == FillOrigin::Start {
+            0.625
+        } else {
+            0.75
+        };
+
+        PanelRenderer::apply_progress_mask(
+            &mut image,
+            progress,
+            SensorDirection::LeftToRight,
+            origin,
+            0,
+            3,
+        );
+
+        let alpha: Vec<u8> = (0..8).map(|x| image.get_pixel(x, 0)[3]).collect();
+        assert_eq!(alpha, expected);
+        assert!(alpha.iter().any(|&a| a > 0 && a < 255));
+    }
+
+    #[test]
+    fn progress_mask_feather_keeps_full_fill_opaque() {
+        let mut image = RgbaImage::from_pixel(8, 1, Rgba([255, 255, 255, 255]));
+
+        PanelRenderer::apply_progress_mask(
+            &mut image,
+            1.0,
+            SensorDirection::LeftToRight,
+            FillOrigin::Edges,
+            0,
+            3,
+        );
+
+        assert!(image.pixels().all(|pixel| pixel[3] == 255));
+    }
+
+    fn gradient_panel(value: &str) -> Panel {
+        serde_json::from_str(&format!(
+            r##"{{"sensor": [
+            {{"mode": 3, "label": "cpu", "value": "{value}", "unit": "", "x": 0, "y": 0,
+             "width": 10, "height": 2, "direction": 1, "minValue": 0, "maxValue": 100,
+             "integerDigits": -1, "decimalDigits": -1, "pic": "",
+             "gradient": [{{"value": 100, "color": "#ff0000"}}, {{"value": 0, "color": "#00ff00"}}]}}
+            ]}}"##
+        ))
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case(-10.0, Rgb([0, 255, 0]))]
+    #[case(0.0, Rgb([0, 255, 0]))]
+    #[case(25.0, Rgb([64, 191, 0]))]
+    #[case(50.0, Rgb([128, 128, 0]))]
+    #[case(100.0, Rgb([255, 0, 0]))]
+    #[case(120.0, Rgb([255, 0, 0]))]
+    fn gradient_color_interpolates_stops(#[case] value: f32, #[case] expected: Rgb<u8>) {
+        let panel = gradient_panel("0");
+
+        assert_eq!(
+            gradient_color(&panel.sensor[0].gradient, value),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn gradient_color_without_stops() {
+        assert_eq!(gradient_color(&[], 50.0), None);
+    }
+
+    #[test]
+    fn progress_gradient_fills_visible_region_with_interpolated_color() {
+        let panel = gradient_panel("50");
+        let mut renderer = PanelRenderer::new((20, 4), "fonts", "cfg");
+        renderer.set_use_config_values(true);
+
+        let image = renderer.render(&panel, &HashMap::new()).unwrap();
+
+        let filled: Vec<u32> = (0..20).filter(|&x| image.get_pixel(x, 0)[3] > 0).collect();
+        assert_eq!(filled, (0..5).collect::<Vec<_>>());
+        assert_eq!(*image.get_pixel(0, 0), Rgba([128, 128, 0, 255]));
+        assert_eq!(*image.get_pixel(4, 1), Rgba([128, 128, 0, 255]));
+    }
+
+    #[rstest]
+    #[case::in_range("50", Rgba([128, 128, 0, 255]))]
+    #[case::at_max("100", Rgba([255, 0, 255, 255]))]
+    #[case::above_max("120", Rgba([255, 0, 255, 255]))]
+    #[case::at_min("0", Rgba([0, 0, 255, 255]))]
+    fn progress_clamp_colors_replace_gradient_at_bounds(
+        #[case] value: &str,
+        #[case] expected: Rgba<u8>,
+    ) {
+        let mut panel = gradient_panel(value);
+        let sensor = &mut panel.sensor[0];
+        sensor.at_max_color = Some(serde_json::from_str(r##""#ff00ff""##).unwrap());
+        sensor.at_min_color = Some(serde_json::from_str(r##""#0000ff""##).unwrap());
+        sensor.min_visible = 1;
+        let mut renderer = PanelRenderer::new((20, 4), "fonts", "cfg");
+        renderer.set_use_config_values(true);
+
+        let image = renderer.render(&panel, &HashMap::new()).unwrap();
+
+        assert_eq!(*image.get_pixel(0, 0), expected);
+    }
+
+    #[test]
+    fn clamp_color_only_at_bounds() {
+        let mut sensor = gradient_panel("0").sensor.remove(0);
+        assert_eq!(clamp_color(&sensor, 100.0, 0.0, 100.0), None);
+
+        sensor.at_max_color = Some(serde_json::from_str(r##""#ff0000""##).unwrap());
+        assert_eq!(
+            clamp_color(&sensor, 100.0, 0.0, 100.0),
+            Some(Rgb([255, 0, 0]))
+        );
+        assert_eq!(clamp_color(&sensor, 99.9, 0.0, 100.0), None);
+        // no at-min color
+        assert_eq!(clamp_color(&sensor, 0.0, 0.0, 100.0), None);
+    }
+
+    #[test]
+    fn progress_mask_min_visible_does_not_shrink_larger_fill() {
+        let mut image = RgbaImage::from_pixel(8, 1, Rgba([255, 255, 255, 255]));
+
+        PanelRenderer::apply_progress_mask(
+            &mut image,
+            0.75,
+            SensorDirection::LeftToRight,
+            FillOrigin::Start,
+            2,
+            0,
+        );
+
+        let columns: Vec<u32> = (0..8).filter(|&x| image.get_pixel(x, 0)[3] > 0).collect();
+        assert_eq!(columns, vec![0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn progress_mask_center_out_vertical() {
+        let mut image = RgbaImage::from_pixel(1, 8, Rgba([255, 255, 255, 255]));
+
+        PanelRenderer::apply_progress_mask(
+            &mut image,
+            0.25,
+            SensorDirection::BottomToTop,
+            FillOrigin::Center,
+            0,
+            0,
+        );
+
+        let rows: Vec<u32> = (0..8).filter(|&y| image.get_pixel(0, y)[3] > 0).collect();
+        assert_eq!(rows, vec![3, 4]);
+    }
+
+    #[test]
+    fn explain_sensor_resolves_value_and_position() {
+        let mut panel: Panel = serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 1, "label": "cpu_temperature", "value": "", "unit": "°C", "x": 10, "y": 20,
+             "integerDigits": -1, "decimalDigits": 1, "pic": ""}
+            ]}"#,
+        )
+        .unwrap();
+        panel.map_sensor_labels(&HashMap::from([(
+            "cpu_temperature".to_string(),
+            "temperature_cpu".to_string(),
+        )]));
+        let values = HashMap::from([("temperature_cpu".to_string(), "42.54".to_string())]);
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+
+        let explanation = renderer
+            .explain_sensor(&panel, "cpu_temperature", &values)
+            .expect("sensor not found");
+
+        assert_eq!(explanation.source, "temperature_cpu");
+        assert_eq!(explanation.value.as_deref(), Some("42.54"));
+        assert_eq!(explanation.text.as_deref(), Some("42.5°C"));
+        let (x, y) = explanation.position.expect("text position");
+        assert_eq!(x, 10);
+        assert!(y < 20);
+
+        let output = explanation.to_string();
+        assert!(output.contains("Raw value:      42.54"));
+        assert!(output.contains(&format!("Pixel position: (10, {y})")));
+    }
+
+    #[rstest]
+    #[case("42", None, "42.00 V")]
+    #[case("42", Some("number"), "42.00 V")]
+    #[case("42", Some("string"), "42 V")]
+    #[case("on", Some("bool"), "on V")]
+    #[case("42", Some("unknown"), "42.00 V")]
+    fn format_typed_sensor_value(
+        #[case] value: &str,
+        #[case] value_type: Option<&str>,
+        #[case] expected: &str,
+    ) {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 1, "label": "build", "value": "", "unit": " V", "x": 0, "y": 0,
+             "integerDigits": -1, "decimalDigits": 2, "pic": ""}
+            ]}"#,
+        )
+        .unwrap();
+        let mut values = HashMap::from([("build".to_string(), value.to_string())]);
+        if let Some(value_type) = value_type {
+            values.insert("build#type".to_string(), value_type.to_string());
+        }
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+
+        let explanation = renderer.explain_sensor(&panel, "build", &values);
+
+        assert_eq!(explanation.and_then(|e| e.text).as_deref(), Some(expected));
+    }
+
+    #[rstest]
+    #[case::global_alias("link1", "up", "Online")]
+    #[case::global_alias("link2", "down", "Offline")]
+    #[case::sensor_override("link3", "up", "Running")]
+    #[case::sensor_map_only("link3", "paused", "Idle")]
+    #[case::no_alias("link1", "unknown", "unknown")]
+    fn format_text_with_value_aliases(
+        #[case] label: &str,
+        #[case] value: &str,
+        #[case] expected: &str,
+    ) {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 1, "label": "link1", "value": "", "unit": "", "x": 0, "y": 0,
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""},
+            {"mode": 1, "label": "link2", "value": "", "unit": "", "x": 0, "y": 0,
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""},
+            {"mode": 1, "label": "link3", "value": "", "unit": "", "x": 0, "y": 0,
+             "valueMap": {"up": "Running", "paused": "Idle"},
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""}
+            ]}"#,
+        )
+        .unwrap();
+        let values = HashMap::from([(label.to_string(), value.to_string())]);
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+        renderer.set_value_aliases(HashMap::from([
+            ("up".to_string(), "Online".to_string()),
+            ("down".to_string(), "Offline".to_string()),
+        ]));
+
+        let explanation = renderer.explain_sensor(&panel, label, &values);
+
+        assert_eq!(explanation.and_then(|e| e.text).as_deref(), Some(expected));
+    }
+
+    #[rstest]
+    #[case::setup_default("", "3")]
+    #[case::half_up(r#""rounding": "half_up","#, "4")]
+    #[case::half_even(r#""rounding": "half_even","#, "4")]
+    fn format_text_with_sensor_rounding(#[case] rounding: &str, #[case] expected: &str) {
+        let panel: Panel = serde_json::from_str(&format!(
+            r#"{{"sensor": [
+            {{"mode": 1, "label": "value", "value": "", "unit": "", "x": 0, "y": 0, {rounding}
+             "integerDigits": -1, "decimalDigits": 0, "pic": ""}}
+            ]}}"#
+        ))
+        .unwrap();
+        let values = HashMap::from([("value".to_string(), "3.5".to_string())]);
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+        renderer.set_number_format(NumberFormat {
+            rounding: Rounding::Trunc,
+            ..Default::default()
+        });
+
+        let explanation = renderer.explain_sensor(&panel, "value", &values);
+
+        assert_eq!(explanation.and_then(|e| e.text).as_deref(), Some(expected));
+    }
+
+    #[rstest]
+    #[case("on", "bool", true)]
+    #[case("off", "bool", false)]
+    #[case("1", "string", false)]
+    #[case("1", "number", true)]
+    fn render_progress_with_typed_value(
+        #[case] value: &str,
+        #[case] value_type: &str,
+        #[case] visible: bool,
+    ) {
+        let panel: Panel = serde_json::from_str(
+            r##"{"sensor": [
+            {"mode": 3, "label": "fan_on", "value": "", "unit": "", "x": 0, "y": 0, "width": 10,
+             "height": 4, "direction": 1, "minValue": 0, "maxValue": 1,
+             "gradient": [{"value": 0, "color": "#ff0000"}],
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""}
+            ]}"##,
+        )
+        .unwrap();
+        let values = HashMap::from([
+            ("fan_on".to_string(), value.to_string()),
+            ("fan_on#type".to_string(), value_type.to_string()),
+        ]);
+        let mut renderer = PanelRenderer::new((20, 10), "fonts", "cfg");
+
+        let image = renderer.render(&panel, &values).unwrap();
+
+        assert_eq!(image.pixels().any(|pixel| pixel[3] > 0), visible);
+    }
+
+    #[test]
+    fn explain_sensor_places_dynamic_unit_as_prefix() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 1, "label": "price", "value": "", "unit": "€", "unitPosition": "prefix",
+             "x": 0, "y": 0, "integerDigits": -1, "decimalDigits": 2, "pic": ""}
+            ]}"#,
+        )
+        .unwrap();
+        let values = HashMap::from([
+            ("price".to_string(), "12.5".to_string()),
+            ("price#unit".to_string(), "$".to_string()),
+        ]);
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+
+        let explanation = renderer.explain_sensor(&panel, "price", &values);
+
+        assert_eq!(explanation.and_then(|e| e.text).as_deref(), Some("$12.50"));
+    }
+
+    fn config_value_panel() -> Panel {
+        serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 1, "label": "cpu", "value": "65", "unit": "%", "x": 0, "y": 0,
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""}
+            ]}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn sensor_without_live_value_ignores_config_value_by_default() {
+        let panel = config_value_panel();
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+
+        let explanation = renderer.explain_sensor(&panel, "cpu", &HashMap::new());
+
+        assert_eq!(explanation.and_then(|e| e.text), None);
+    }
+
+    #[test]
+    fn sensor_without_live_value_renders_config_value() {
+        let panel = config_value_panel();
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+        renderer.set_use_config_values(true);
+
+        let explanation = renderer.explain_sensor(&panel, "cpu", &HashMap::new());
+
+        assert_eq!(explanation.and_then(|e| e.text).as_deref(), Some("65%"));
+    }
+
+    #[test]
+    fn live_value_takes_precedence_over_config_value() {
+        let panel = config_value_panel();
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+        renderer.set_use_config_values(true);
+        let values = HashMap::from([("cpu".to_string(), "12".to_string())]);
+
+        let explanation = renderer.explain_sensor(&panel, "cpu", &values);
+
+        assert_eq!(explanation.and_then(|e| e.text).as_deref(), Some("12%"));
+    }
+
+    #[test]
+    fn last_frame_returns_rendered_image() {
+        let panel = config_value_panel();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        renderer.set_use_config_values(true);
+        assert!(renderer.last_frame().is_none());
+
+        let image = renderer.render(&panel, &HashMap::new()).unwrap().clone();
+
+        assert_eq!(renderer.last_frame(), Some(&image));
+        assert!(image.pixels().any(|pixel| pixel[3] > 0));
+    }
+
+    #[test]
+    fn render_reuses_frame_buffer() {
+        let panel = test_panel(&["cpu"]);
+        let values = HashMap::from([("cpu".to_string(), "12".to_string())]);
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+
+        let first_frame = renderer.render(&panel, &values).unwrap().as_ptr();
+        let second_frame = renderer.render(&panel, &values).unwrap().as_ptr();
+        for i in 0..10 {
+            let frame = renderer.render(&panel, &values).unwrap().as_ptr();
+            let expected = if i % 2 == 0 {
+                first_frame
+            } else {
+                second_frame
+            };
+            assert_eq!(frame, expected, "frame buffers must not be reallocated");
+        }
+    }
+
+    #[test]
+    fn render_keeps_last_frame_if_rendering_fails() {
+        let panel = config_value_panel();
+        let failing_panel: Panel = serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 2, "label": "fan", "value": "50", "unit": "", "x": 0, "y": 0,
+             "pic": "missing.png"}
+            ]}"#,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        renderer.set_use_config_values(true);
+
+        let image = renderer.render(&panel, &HashMap::new()).unwrap().clone();
+        assert!(renderer.render(&failing_panel, &HashMap::new()).is_err());
+
+        assert_eq!(renderer.last_frame(), Some(&image));
+        assert_eq!(renderer.render(&panel, &HashMap::new()).unwrap(), &image);
+    }
+
+    #[test]
+    fn render_resets_frame_buffer() {
+        let panel = test_panel(&["cpu"]);
+        let values = HashMap::from([("cpu".to_string(), "12".to_string())]);
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+
+        let frame = renderer.render(&panel, &values).unwrap();
+        assert!(frame.pixels().any(|pixel| pixel[3] > 0));
+
+        let frame = renderer.render(&panel, &HashMap::new()).unwrap();
+        assert!(frame.pixels().all(|pixel| pixel[3] == 0));
+    }
+
+    #[test]
+    fn profile_contains_each_sensor() {
+        let panel = test_panel(&["cpu", "gpu", "cpu"]);
+        let values = HashMap::from([
+            ("cpu".to_string(), "12".to_string()),
+            ("gpu".to_string(), "34".to_string()),
+        ]);
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        renderer.set_profile(true);
+
+        renderer.render(&panel, &values).unwrap();
+
+        let profile = renderer.last_profile().expect("profile");
+        let labels: Vec<_> = profile.sensors.iter().map(|(label, _)| label).collect();
+        assert_eq!(labels, ["cpu", "gpu", "cpu"]);
+        assert!(profile.total >= profile.compositing);
+        let output = profile.to_string();
+        assert_eq!(output.lines().count(), 5);
+        assert!(output.contains("<compositing>"));
+    }
+
+    #[test]
+    fn profile_disabled_by_default() {
+        let panel = test_panel(&["cpu"]);
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+
+        renderer.render(&panel, &HashMap::new()).unwrap();
+
+        assert!(renderer.last_profile().is_none());
+    }
+
+    #[test]
+    fn explain_sensor_unknown_label() {
+        let panel = test_panel(&["cpu"]);
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+
+        assert_eq!(
+            renderer.explain_sensor(&panel, "gpu", &HashMap::new()),
+            None
+        );
+    }
+
+    /// Get the rows containing visible pixels as list of (first row, last row) bands.
+    fn visible_row_bands(image: &RgbaImage) -> Vec<(u32, u32)> {
+        let mut bands: Vec<(u32, u32)> = Vec::new();
+        for y in 0..image.height() {
+            if !(0..image.width()).any(|x| image.get_pixel(x, y)[3] > 0) {
+                continue;
+            }
+            match bands.last_mut() {
+                Some(band) if band.1 + 1 == y => band.1 = y,
+                _ => bands.push((y, y)),
+            }
+        }
+        bands
+    }
+
+    #[test]
+    fn render_text_rotated_by_90_degrees_is_vertical() {
+        let render_ab = |rotation: i32| {
+            let panel: Panel = serde_json::from_str(&format!(
+                r#"{{"sensor": [
+                {{"mode": 1, "label": "text", "value": "", "unit": "", "x": 20, "y": 40, "width": 60,
+                 "height": 20, "fontSize": 30, "textAlign": "center", "rotation": {rotation},
+                 "integerDigits": -1, "decimalDigits": -1, "pic": ""}}
+                ]}}"#
+            ))
+            .unwrap();
+            let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+            let values = HashMap::from([("text".to_string(), "AB".to_string())]);
+            let image = renderer.render(&panel, &values).unwrap();
+
+            let visible: Vec<_> = image
+                .enumerate_pixels()
+                .filter(|(_, _, pixel)| pixel[3] > 0)
+                .map(|(x, y, _)| (x, y))
+                .collect();
+            assert!(!visible.is_empty(), "no text rendered at {rotation}°");
+            let min_x = visible.iter().map(|p| p.0).min().unwrap();
+            let max_x = visible.iter().map(|p| p.0).max().unwrap();
+            let min_y = visible.iter().map(|p| p.1).min().unwrap();
+            let max_y = visible.iter().map(|p| p.1).max().unwrap();
+            (max_x - min_x + 1, max_y - min_y + 1)
+        };
+
+        let (width, height) = render_ab(0);
+        assert!(width > height, "flat text {width}x{height}");
+
+        let (rotated_width, rotated_height) = render_ab(90);
+        assert!(
+            rotated_height > rotated_width,
+            "rotated text {rotated_width}x{rotated_height}"
+        );
+        assert!(rotated_width.abs_diff(height) <= 2);
+        assert!(rotated_height.abs_diff(width) <= 2);
+    }
+
+    #[test]
+    fn render_text_with_horizontal_gradient_fill() {
+        let panel: Panel = serde_json::from_str(
+            r##"{"sensor": [
+            {"mode": 1, "label": "text", "value": "", "unit": "", "x": 10, "y": 30, "width": 180,
+             "height": 40, "fontSize": 40, "integerDigits": -1, "decimalDigits": -1, "pic": "",
+             "textFill": {"gradient": [{"value": 0, "color": "#ff0000"}, {"value": 100, "color": "#0000ff"}]}}
+            ]}"##,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((200, 60), "fonts", "cfg");
+        let values = HashMap::from([("text".to_string(), "MMMMMM".to_string())]);
+
+        let image = renderer.render(&panel, &values).unwrap();
+
+        let mut opaque: Vec<_> = image
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| pixel[3] == 255)
+            .map(|(x, _, pixel)| (x, *pixel))
+            .collect();
+        opaque.sort_by_key(|(x, _)| *x);
+        let (_, first) = opaque.first().unwrap();
+        let (_, last) = opaque.last().unwrap();
+        assert!(first[0] > 200 && first[2] < 55, "left color {first:?}");
+        assert!(last[2] > 200 && last[0] < 55, "right color {last:?}");
+        assert!(opaque.iter().all(|(_, pixel)| pixel[1] == 0));
+
+        let colors: std::collections::HashSet<_> =
+            opaque.iter().map(|(_, pixel)| pixel[0]).collect();
+        assert!(colors.len() > 10, "{} colors", colors.len());
+    }
+
+    const WHITE: Rgba<u8> = Rgba([255, 255, 255, 255]);
+    const RED: Rgba<u8> = Rgba([255, 0, 0, 255]);
+    const BLUE: Rgba<u8> = Rgba([0, 0, 255, 255]);
+
+    #[rstest]
+    #[case::suffix("suffix", [WHITE, RED, BLUE])]
+    #[case::prefix("prefix", [BLUE, WHITE, RED])]
+    fn render_text_with_decimal_and_unit_colors(
+        #[case] unit_position: &str,
+        #[case] expected: [Rgba<u8>; 3],
+    ) {
+        let panel: Panel = serde_json::from_str(&format!(
+            r##"{{"sensor": [
+            {{"mode": 1, "label": "volt", "value": "", "unit": "VV", "x": 0, "y": 20,
+             "fontSize": 40, "fontColor": "#ffffff", "decimalColor": "#ff0000",
+             "unitColor": "#0000ff", "unitPosition": "{unit_position}",
+             "integerDigits": -1, "decimalDigits": 2, "pic": ""}}
+            ]}}"##
+        ))
+        .unwrap();
+        let mut renderer = PanelRenderer::new((200, 60), "fonts", "cfg");
+        let values = HashMap::from([("volt".to_string(), "88.88".to_string())]);
+        let image = renderer.render(&panel, &values).unwrap();
+
+        // horizontal pixel range of each fully covered segment color
+        let columns = |color: Rgba<u8>| {
+            let columns: Vec<_> = image
+                .enumerate_pixels()
+                .filter(|(_, _, pixel)| **pixel == color)
+                .map(|(x, _, _)| x)
+                .collect();
+            assert!(!columns.is_empty(), "no pixels with color {color:?}");
+            (
+                *columns.iter().min().unwrap(),
+                *columns.iter().max().unwrap(),
+            )
+        };
+        let ranges = expected.map(columns);
+
+        assert!(ranges[0].1 < ranges[1].0, "segments overlap: {ranges:?}");
+        assert!(ranges[1].1 < ranges[2].0, "segments overlap: {ranges:?}");
+    }
+
+    #[test]
+    fn render_rtl_text_with_prefix_unit_uses_font_color() {
+        let panel: Panel = serde_json::from_str(
+            r##"{"sensor": [
+            {"mode": 1, "label": "volt", "value": "", "unit": "VV", "x": 0, "y": 20,
+             "fontSize": 40, "fontColor": "#ffffff", "decimalColor": "#ff0000",
+             "unitColor": "#0000ff", "unitPosition": "prefix", "rtl": true,
+             "integerDigits": -1, "decimalDigits": 2, "pic": ""}
+            ]}"##,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((200, 60), "fonts", "cfg");
+        let values = HashMap::from([("volt".to_string(), "88.88".to_string())]);
+        let image = renderer.render(&panel, &values).unwrap();
+
+        assert!(image.pixels().any(|pixel| *pixel == WHITE));
+        assert!(!image.pixels().any(|pixel| *pixel == BLUE));
+    }
+
+    #[test]
+    fn render_text_with_newline_renders_stacked_lines() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 1, "label": "status", "value": "", "unit": "", "x": 10, "y": 50, "fontSize": 20,
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""}
+            ]}"#,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+        let single_line = renderer
+            .render(
+                &panel,
+                &HashMap::from([("status".to_string(), "88".to_string())]),
+            )
+            .unwrap()
+            .clone();
+
+        let values = HashMap::from([("status".to_string(), "88\n88".to_string())]);
+        let image = renderer.render(&panel, &values).unwrap();
+
+        let single_bands = visible_row_bands(&single_line);
+        let bands = visible_row_bands(image);
+        assert_eq!(single_bands.len(), 1);
+        assert_eq!(bands.len(), 2, "{bands:?}");
+        let line_height = single_bands[0].1 - single_bands[0].0;
+        assert_eq!(bands[0].1 - bands[0].0, line_height);
+        assert_eq!(bands[1].1 - bands[1].0, line_height);
+    }
+
+    #[test]
+    fn panel_sensor_filter_hides_sensors_only_on_filtered_panel() {
+        let summary_panel: Panel = serde_json::from_str(
+            r#"{"sensorFilter": ["^cpu_core_"], "sensor": [
+            {"mode": 1, "label": "cpu_core_1", "value": "", "unit": "", "x": 10, "y": 20,
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""}
+            ]}"#,
+        )
+        .unwrap();
+        let detail_panel = test_panel(&["cpu_core_1"]);
+        let values = HashMap::from([("cpu_core_1".to_string(), "88".to_string())]);
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+
+        let summary = renderer.render(&summary_panel, &values).unwrap();
+        assert!(summary.pixels().all(|pixel| pixel[3] == 0));
+
+        let detail = renderer.render(&detail_panel, &values).unwrap();
+        assert!(detail.pixels().any(|pixel| pixel[3] > 0));
+    }
+
+    #[test]
+    fn control_sensor_toggles_sensor_group() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 1, "label": "cpu_temp", "value": "", "unit": "", "x": 5, "y": 20,
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""},
+            {"mode": 1, "label": "cpu_core_1", "value": "", "unit": "", "x": 40, "y": 20,
+             "integerDigits": -1, "decimalDigits": -1, "pic": "",
+             "visibleWhenLabel": "detail_mode", "visibleWhenValue": "on"},
+            {"mode": 1, "label": "cpu_core_2", "value": "", "unit": "", "x": 70, "y": 20,
+             "integerDigits": -1, "decimalDigits": -1, "pic": "",
+             "visibleWhenLabel": "detail_mode", "visibleWhenValue": "on"}
+            ]}"#,
+        )
+        .unwrap();
+        let mut values = HashMap::from([
+            ("cpu_temp".to_string(), "8".to_string()),
+            ("cpu_core_1".to_string(), "8".to_string()),
+            ("cpu_core_2".to_string(), "8".to_string()),
+        ]);
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        let group_visible = |image: &RgbaImage| {
+            (
+                visible_column_range(image, 0..35).is_some(),
+                visible_column_range(image, 35..100).is_some(),
+            )
+        };
+
+        // missing control value
+        let image = renderer.render(&panel, &values).unwrap();
+        assert_eq!(group_visible(image), (true, false));
+
+        values.insert("detail_mode".to_string(), "on".to_string());
+        let image = renderer.render(&panel, &values).unwrap();
+        assert_eq!(group_visible(image), (true, true));
+
+        values.insert("detail_mode".to_string(), "off".to_string());
+        let image = renderer.render(&panel, &values).unwrap();
+        assert_eq!(group_visible(image), (true, false));
+    }
+
+    /// Get the first and last column containing visible pixels in the given column range.
+    fn visible_column_range(
+        image: &RgbaImage,
+        columns: std::ops::Range<u32>,
+    ) -> Option<(u32, u32)> {
+        let visible: Vec<u32> = columns
+            .filter(|&x| (0..image.height()).any(|y| image.get_pixel(x, y)[3] > 0))
+            .collect();
+        Some((*visible.first()?, *visible.last()?))
+    }
+
+    #[test]
+    fn render_table_aligns_columns() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [], "tables": [{"x": 10, "y": 0, "rowHeight": 30, "fontSize": 20,
+            "columns": [{"width": 100}, {"width": 100, "textAlign": "right"}],
+            "rows": [["a1", "b1"], ["a2", "b2"]]}]}"#,
+        )
+        .unwrap();
+        let values = HashMap::from([
+            ("a1".to_string(), "88".to_string()),
+            ("b1".to_string(), "8".to_string()),
+            ("a2".to_string(), "8".to_string()),
+            ("b2".to_string(), "888".to_string()),
+        ]);
+        let mut renderer = PanelRenderer::new((220, 60), "fonts", "cfg");
+
+        let image = renderer.render(&panel, &values).unwrap();
+
+        assert_eq!(visible_row_bands(image).len(), 2);
+        let left = visible_column_range(image, 0..110).unwrap();
+        let right = visible_column_range(image, 110..220).unwrap();
+        // left aligned column starts at the table x-position
+        assert!((10..14).contains(&left.0), "{left:?}");
+        // right aligned column ends at the right column border
+        assert!((200..210).contains(&right.1), "{right:?}");
+        assert!(right.0 > 150, "{right:?}");
+    }
+
+    #[rstest]
+    #[case::ltr("CPU 42%", "CPU 42%")]
+    #[case::hebrew("שלום", "םולש")]
+    #[case::arabic("مرحبا", "ابحرم")]
+    #[case::ltr_with_rtl_run("CPU: שלום 42", "CPU: 42 םולש")]
+    #[case::ltr_with_rtl_word("Hi שלום!", "Hi םולש!")]
+    #[case::rtl_with_number("שלום 42", "42 םולש")]
+    fn bidi_visual_order_reverses_rtl_runs(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(bidi_visual_order(text), expected);
+    }
+
+    #[test]
+    fn text_layout_uses_panel_default_font_family() {
+        let font_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fonts");
+        let font_size = |name: &str| fs::metadata(font_dir.join(name)).unwrap().len() as usize;
+        let panel: Panel = serde_json::from_str(
+            r#"{"fontFamily": "HarmonyOS_Sans_SC_Bold", "sensor": [
+            {"mode": 1, "label": "default", "value": "", "unit": "", "x": 0, "y": 0,
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""},
+            {"mode": 1, "label": "empty", "value": "", "unit": "", "x": 0, "y": 0,
+             "fontFamily": "", "integerDigits": -1, "decimalDigits": -1, "pic": ""},
+            {"mode": 1, "label": "own", "value": "", "unit": "", "x": 0, "y": 0,
+             "fontFamily": "DejaVuSans", "integerDigits": -1, "decimalDigits": -1, "pic": ""}
+            ]}"#,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((100, 40), &font_dir, "cfg");
+        renderer.render(&panel, &HashMap::new()).unwrap();
+
+        let mut layout_font_size =
+            |sensor| renderer.text_layout(sensor, "42").font.font_data().len();
+
+        let panel_font = font_size("HarmonyOS_Sans_SC_Bold.ttf");
+        assert_eq!(layout_font_size(&panel.sensor[0]), panel_font);
+        assert_eq!(layout_font_size(&panel.sensor[1]), panel_font);
+        assert_eq!(
+            layout_font_size(&panel.sensor[2]),
+            font_size("DejaVuSans.ttf")
+        );
+    }
+
+    #[rstest]
+    #[case(false, "שלום")]
+    #[case(true, "םולש")]
+    fn text_layout_reorders_rtl_text(#[case] rtl: bool, #[case] expected: &str) {
+        let panel: Panel = serde_json::from_str(&format!(
+            r#"{{"sensor": [{{"mode": 1, "label": "name", "value": "", "unit": "", "x": 0, "y": 0,
+            "rtl": {rtl}, "integerDigits": -1, "decimalDigits": -1, "pic": ""}}]}}"#
+        ))
+        .unwrap();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+
+        let layout = renderer.text_layout(&panel.sensor[0], "שלום");
+
+        assert_eq!(layout.lines[0].text, expected);
+    }
+
+    fn anchored_text_sensor(anchor: &str) -> Sensor {
+        let panel: Panel = serde_json::from_str(&format!(
+            r#"{{"sensor": [{{"mode": 1, "label": "temp", "value": "", "unit": "", "x": 10,
+            "y": 60, "fontSize": 40, "textAnchor": "{anchor}", "integerDigits": -1,
+            "decimalDigits": -1, "pic": ""}}]}}"#
+        ))
+        .unwrap();
+        panel.sensor.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn text_anchor_baseline_and_top_left_differ_by_ascent() {
+        let mut renderer = PanelRenderer::new((200, 100), "fonts", "cfg");
+
+        let top_left = renderer.text_layout(&anchored_text_sensor("top-left"), "88");
+        let baseline = renderer.text_layout(&anchored_text_sensor("baseline"), "88");
+
+        let ascent = baseline.font.as_scaled(baseline.scale).ascent().round() as i32;
+        assert!(ascent > 0);
+        assert_eq!(top_left.lines[0].y, 60);
+        assert_eq!(baseline.lines[0].y, 60 - ascent);
+        assert_eq!(top_left.lines[0].x, baseline.lines[0].x);
+    }
+
+    #[test]
+    fn text_anchor_baseline_draws_digits_on_baseline() {
+        let mut renderer = PanelRenderer::new((200, 100), "fonts", "cfg");
+        let mut image = RgbaImage::new(200, 100);
+        let sensor = anchored_text_sensor("baseline");
+
+        renderer
+            .draw_text(&mut image, &sensor, &[TextSegment::new("88", WHITE)], WHITE)
+            .unwrap();
+
+        let bands = visible_row_bands(&image);
+        assert_eq!(bands.len(), 1, "{bands:?}");
+        // digits sit on the baseline, anti-aliasing may add a row below
+        assert!((59..=61).contains(&bands[0].1), "{bands:?}");
+    }
+
+    #[test]
+    fn text_anchor_center_centers_digits_vertically() {
+        let mut renderer = PanelRenderer::new((200, 100), "fonts", "cfg");
+        let mut image = RgbaImage::new(200, 100);
+        let mut sensor = anchored_text_sensor("center");
+        sensor.y = 30;
+        sensor.height = Some(40);
+
+        renderer
+            .draw_text(&mut image, &sensor, &[TextSegment::new("88", WHITE)], WHITE)
+            .unwrap();
+
+        let bands = visible_row_bands(&image);
+        assert_eq!(bands.len(), 1, "{bands:?}");
+        let center = (bands[0].0 + bands[0].1) as i32 / 2;
+        assert!((49..=51).contains(&center), "{bands:?}");
+    }
+
+    #[test]
+    fn text_anchor_defaults_to_center() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [{"mode": 1, "label": "temp", "value": "", "unit": "", "x": 10,
+            "y": 60, "integerDigits": -1, "decimalDigits": -1, "pic": ""}]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(panel.sensor[0].text_anchor, TextAnchor::Center);
+    }
+
+    #[test]
+    fn sensor_with_update_interval_is_not_resolved_every_frame() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"name": "Slow", "sensor": [
+            {"mode": 1, "label": "slow", "value": "", "unit": "", "x": 0, "y": 20,
+             "updateInterval": 30, "integerDigits": -1, "decimalDigits": -1, "pic": ""},
+            {"mode": 1, "label": "fast", "value": "", "unit": "", "x": 0, "y": 20,
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""},
+            {"mode": 3, "label": "slow", "value": "", "unit": "", "x": 0, "y": 20,
+             "updateInterval": 30, "integerDigits": -1, "decimalDigits": -1, "pic": ""}
+            ]}"#,
+        )
+        .unwrap();
+        let (slow, fast, slow_bar) = (&panel.sensor[0], &panel.sensor[1], &panel.sensor[2]);
+        let now = Local::now();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+        renderer.panel_name = panel.friendly_name();
+        let values = |value: &str| {
+            HashMap::from([
+                ("slow".to_string(), value.to_string()),
+                ("fast".to_string(), value.to_string()),
+            ])
+        };
+        let value_of = |cached: Option<CachedValue>| cached.map(|cached| cached.value);
+
+        assert_eq!(
+            value_of(renderer.sensor_value(0, slow, &values("1"), &now)),
+            Some("1".into())
+        );
+        assert_eq!(
+            value_of(renderer.sensor_value(0, slow, &values("2"), &now)),
+            Some("1".into())
+        );
+        assert_eq!(
+            value_of(renderer.sensor_value(1, fast, &values("2"), &now)),
+            Some("2".into())
+        );
+        // another sensor of the same label has its own cached value
+        assert_eq!(
+            value_of(renderer.sensor_value(2, slow_bar, &values("2"), &now)),
+            Some("2".into())
+        );
+
+        // the interval elapsed
+        for cached in renderer.value_cache.values_mut() {
+            let Some(updated) = cached.updated.checked_sub(Duration::from_secs(30)) else {
+                // the monotonic clock started less than 30s ago
+                return;
+            };
+            cached.updated = updated;
+        }
+        assert_eq!(
+            value_of(renderer.sensor_value(0, slow, &values("3"), &now)),
+            Some("3".into())
+        );
+    }
+
+    #[test]
+    fn sensor_with_update_interval_renders_cached_value() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"name": "Slow", "sensor": [
+            {"mode": 1, "label": "slow", "value": "", "unit": "", "x": 0, "y": 20,
+             "updateInterval": 30, "integerDigits": -1, "decimalDigits": -1, "pic": ""}
+            ]}"#,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+
+        let first = renderer
+            .render(
+                &panel,
+                &HashMap::from([("slow".to_string(), "1".to_string())]),
+            )
+            .unwrap()
+            .clone();
+        let second = renderer
+            .render(
+                &panel,
+                &HashMap::from([("slow".to_string(), "888".to_string())]),
+            )
+            .unwrap();
+
+        assert_eq!(&first, second);
+    }
+
+    #[test]
+    fn resolve_value_applies_normalization() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [{"mode": 3, "label": "temp", "value": "", "unit": "", "x": 0, "y": 0,
+            "integerDigits": -1, "decimalDigits": -1, "pic": "",
+            "normalize": {"strip": "\" °C", "decimalComma": true}}]}"#,
+        )
+        .unwrap();
+        let sensor = &panel.sensor[0];
+        let values = HashMap::from([("temp".to_string(), "\"12,5 °C\"".to_string())]);
+        let renderer = PanelRenderer::new((100, 40), "fonts", "cfg");
+
+        let (value, _) = renderer
+            .resolve_value(sensor, &values, &Local::now())
+            .unwrap();
+
+        assert_eq!(value, "12.5");
+        assert_eq!(parse_indicator_value(sensor, &value).unwrap(), 12.5);
+    }
+
+    #[test]
+    fn expand_template_applies_normalization() {
+        let normalize = ValueNormalization {
+            strip: "\"".to_string(),
+            decimal_comma: true,
+        };
+        let values = HashMap::from([
+            ("cpu".to_string(), "\"12,5\"".to_string()),
+            ("gpu".to_string(), " 42 ".to_string()),
+        ]);
+        let template = "{cpu}% / {gpu}%";
+
+        assert_eq!(
+            expand_template(template, Some(&normalize), &values, &Local::now()),
+            "12.5% / 42%"
+        );
+        assert_eq!(
+            expand_template(template, None, &values, &Local::now()),
+            "\"12,5\"% /  42 %"
+        );
+    }
+
+    #[test]
+    fn expand_template_replaces_line_break_escapes_only_in_template() {
+        let values = HashMap::from([("path".to_string(), r"C:\new".to_string())]);
+
+        assert_eq!(
+            expand_template(r"CPU\n{path}", None, &values, &Local::now()),
+            "CPU\nC:\\new"
+        );
+    }
+
+    #[test]
+    fn text_layout_keeps_line_break_escapes_of_values() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [{"mode": 1, "label": "path", "value": "", "unit": "", "x": 0, "y": 20,
+            "integerDigits": -1, "decimalDigits": -1, "pic": ""}]}"#,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((200, 40), "fonts", "cfg");
+
+        let layout = renderer.text_layout(&panel.sensor[0], r"C:\new");
+
+        assert_eq!(layout.lines.len(), 1);
+        assert_eq!(layout.lines[0].text, r"C:\new");
+    }
+
+    fn threshold_panel() -> Panel {
+        serde_json::from_str(
+            r##"{"sensor": [
+            {"mode": 1, "label": "cpu", "value": "", "unit": "", "x": 0, "y": 0,
+             "integerDigits": -1, "decimalDigits": -1, "pic": "", "fontColor": "#ffffff",
+             "colorThresholds": [{"value": 90, "color": "#ff0000"}, {"value": 70, "color": "#ffff00"}],
+             "colorHysteresis": 2}
+            ]}"##,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn text_color_uses_highest_reached_threshold() {
+        let panel = threshold_panel();
+        let sensor = &panel.sensor[0];
+
+        for (value, expected) in [
+            ("50", [255, 255, 255]),
+            ("75", [255, 255, 0]),
+            ("95", [255, 0, 0]),
+        ] {
+            let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+            let color = renderer.text_color(0, sensor, value);
+            assert_eq!(
+                color,
+                Rgba([expected[0], expected[1], expected[2], 255]),
+                "{value}"
+            );
+        }
+    }
+
+    #[test]
+    fn text_color_keeps_stable_color_within_hysteresis() {
+        let panel = threshold_panel();
+        let sensor = &panel.sensor[0];
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+        let white = Rgba([255, 255, 255, 255]);
+        let yellow = Rgba([255, 255, 0, 255]);
+
+        assert_eq!(renderer.text_color(0, sensor, "69"), white);
+        for value in ["70", "71.5", "69", "70.5", "71"] {
+            assert_eq!(renderer.text_color(0, sensor, value), white, "{value}");
+        }
+        assert_eq!(renderer.text_color(0, sensor, "72"), yellow);
+        for value in ["69", "70.5", "68.5", "71"] {
+            assert_eq!(renderer.text_color(0, sensor, value), yellow, "{value}");
+        }
+        assert_eq!(renderer.text_color(0, sensor, "67.9"), white);
+    }
+
+    #[test]
+    fn text_color_keeps_hysteresis_per_sensor() {
+        let panel: Panel = serde_json::from_str(
+            r##"{"sensor": [
+            {"mode": 1, "label": "cpu", "value": "", "unit": "", "x": 0, "y": 0,
+             "pic": "", "fontColor": "#ffffff",
+             "colorThresholds": [{"value": 70, "color": "#ffff00"}], "colorHysteresis": 2},
+            {"mode": 1, "label": "cpu", "value": "", "unit": "", "x": 0, "y": 20,
+             "pic": "", "fontColor": "#ffffff",
+             "colorThresholds": [{"value": 50, "color": "#ff0000"}, {"value": 60, "color": "#00ff00"}],
+             "colorHysteresis": 2}
+            ]}"##,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((100, 100), "fonts", "cfg");
+        let white = Rgba([255, 255, 255, 255]);
+        let yellow = Rgba([255, 255, 0, 255]);
+        let green = Rgba([0, 255, 0, 255]);
+
+        assert_eq!(renderer.text_color(0, &panel.sensor[0], "65"), white);
+        assert_eq!(renderer.text_color(1, &panel.sensor[1], "65"), green);
+        assert_eq!(renderer.text_color(0, &panel.sensor[0], "72"), yellow);
+        assert_eq!(renderer.text_color(1, &panel.sensor[1], "72"), green);
+        // within the hysteresis of both sensors
+        assert_eq!(renderer.text_color(0, &panel.sensor[0], "69"), yellow);
+        assert_eq!(renderer.text_color(1, &panel.sensor[1], "59"), green);
+    }
+
+    #[rstest]
+    #[case::without_at_min_color(None, None)]
+    #[case::with_at_min_color(Some(r##""#0000ff""##), Some(Rgba([0, 0, 255, 255])))]
+    fn fan_at_min_value_draws_cue_with_at_min_color(
+        #[case] at_min_color: Option<&str>,
+        #[case] expected: Option<Rgba<u8>>,
+    ) {
+        let dir = image_dir("fan.png", (40, 40));
+        let mut panel: Panel = serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 2, "label": "fan", "value": "0", "unit": "", "x": 20, "y": 20,
+             "direction": 1, "minValue": 0, "maxValue": 100, "pic": "fan.png"}
+            ]}"#,
+        )
+        .unwrap();
+        panel.sensor[0].at_min_color =
+            at_min_color.map(|color| serde_json::from_str(color).unwrap());
+        let mut renderer = PanelRenderer::new((40, 40), "fonts", dir.path());
+        renderer.set_use_config_values(true);
+
+        let image = renderer.render(&panel, &HashMap::new()).unwrap();
+        let drawn: Vec<_> = image.pixels().filter(|pixel| pixel[3] > 0).collect();
+        assert_eq!(drawn.first().copied().copied(), expected);
+        assert!(drawn.len() < 40 * 40 / 8, "only a small cue is drawn");
+    }
+
+    #[rstest]
+    #[case::square_keeps_canvas((20, 20), 10..30, 1)]
+    #[case::non_square_expands_canvas((30, 10), 0..40, 20)]
+    fn pointer_expands_canvas_of_non_square_image(
+        #[case] pic_size: (u32, u32),
+        #[case] bounds: Range<u32>,
+        #[case] min_rows: u32,
+    ) {
+        let dir = image_dir("pointer.png", pic_size);
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 4, "label": "pointer", "value": "50", "unit": "", "x": 20, "y": 20,
+             "direction": 1, "minValue": 0, "maxValue": 100, "minAngle": 0, "maxAngle": 90,
+             "pic": "pointer.png"}
+            ]}"#,
+        )
+        .unwrap();
+        let mut renderer = PanelRenderer::new((40, 40), "fonts", dir.path());
+        renderer.set_use_config_values(true);
+
+        let image = renderer.render(&panel, &HashMap::new()).unwrap();
+        let drawn: Vec<_> = image
+            .enumerate_pixels()
+            .filter(|(_, _, pixel)| pixel[3] > 0)
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert!(
+            drawn
+                .iter()
+                .all(|(x, y)| bounds.contains(x) && bounds.contains(y))
+        );
+        let rows: HashSet<_> = drawn.iter().map(|(_, y)| y).collect();
+        assert!(rows.len() as u32 >= min_rows, "{} rows", rows.len());
+    }
+
+    #[test]
+    fn render_saves_image_in_created_output_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let out_dir = dir.path().join("out");
+        let panel = test_panel(&["cpu"]);
+        let mut renderer = PanelRenderer::new((20, 10), "fonts", "cfg");
+        renderer.set_img_save_path(&out_dir);
+        renderer.set_img_name_template("frame");
+        renderer.set_save_render_img(true);
+        assert!(!out_dir.exists());
+
+        let result = renderer.render(&panel, &HashMap::new()).map(|_| ());
+        let saved = out_dir.join("frame.png").is_file();
+
+        assert!(result.is_ok(), "{result:?}");
+        assert!(saved);
+    }
+
+    #[test]
+    fn render_reports_image_save_error() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let panel = test_panel(&["cpu"]);
+        let mut renderer = PanelRenderer::new((20, 10), "fonts", "cfg");
+        // a regular file in the path prevents creating the output directory
+        renderer.set_img_save_path(file.path().join("out"));
+        renderer.set_save_render_img(true);
+
+        let result = renderer.render(&panel, &HashMap::new()).map(|_| ());
+
+        assert!(
+            matches!(result, Err(ImageProcessingError::ImageSaveError(_))),
+            "{result:?}"
+        );
+        assert!(renderer.last_frame().is_some());
+    }
+
+    #[test]
+    fn render_continues_after_debug_image_save_error() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let panel: Panel = serde_json::from_str(
+            r##"{"sensor": [
+            {"mode": 3, "label": "load", "value": "", "unit": "", "x": 0, "y": 0, "width": 10,
+             "height": 4, "direction": 1, "minValue": 0, "maxValue": 1,
+             "gradient": [{"value": 0, "color": "#ff0000"}],
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""}
+            ]}"##,
+        )
+        .unwrap();
+        let values = HashMap::from([("load".to_string(), "1".to_string())]);
+        let mut renderer = PanelRenderer::new((20, 10), "fonts", "cfg");
+        renderer.set_img_save_path(file.path().join("out"));
+        renderer.set_save_processed_pic(true);
+        renderer.set_save_progress_layer(true);
+
+        let result = renderer.render(&panel, &values).map(|_| ());
+
+        assert!(result.is_ok(), "{result:?}");
+    }
+
+    fn test_panel(labels: &[&str]) -> Panel {
+        let sensors: Vec<String> = labels
+            .iter()
+            .map(|label| {
+                format!(
+                    r#"{{"mode": 1, "label": "{label}", "value": "", "unit": "°C", "x": 0, "y": 0,
+                    "integerDigits": -1, "decimalDigits": -1, "pic": ""}}"#
+                )
+            })
+            .collect();
+        serde_json::from_str(&format!(r#"{{"sensor": [{}]}}"#, sensors.join(",")))
+            .expect("invalid test panel")
+    }
+
+    #[test]
+    fn resolve_sensor_value_without_mapping() {
+        let panel = test_panel(&["cpu_temperature"]);
+        let values = HashMap::from([("cpu_temperature".to_string(), "42".to_string())]);
+
+        let result = resolve_sensor_value(&panel.sensor[0], &values, &Local::now());
+
+        assert_eq!(result, Some(("42".to_string(), "°C".to_string())));
+    }
+
+    #[test]
+    fn resolve_sensor_value_uses_mapped_label_for_value_and_unit() {
+        let mut panel = test_panel(&["cpu_temperature"]);
+        panel.map_sensor_labels(&HashMap::from([(
+            "cpu_temperature".to_string(),
+            "temperature_cpu".to_string(),
+        )]));
+        let values = HashMap::from([
+            ("cpu_temperature".to_string(), "1".to_string()),
+            ("temperature_cpu".to_string(), "42".to_string()),
+            ("temperature_cpu#unit".to_string(), "°F".to_string()),
+        ]);
+
+        let sensor = &panel.sensor[0];
+        let result = resolve_sensor_value(sensor, &values, &Local::now());
+
+        assert_eq!(sensor.label, "cpu_temperature");
+        assert_eq!(sensor.source_label(), "temperature_cpu");
+        assert_eq!(result, Some(("42".to_string(), "°F".to_string())));
+    }
+
+    #[test]
+    fn resolve_sensor_value_uses_mapped_label_for_date_time() {
+        let mut panel = test_panel(&["clock"]);
+        panel.map_sensor_labels(&HashMap::from([(
+            "clock".to_string(),
+            "DATE_year".to_string(),
+        )]));
+        let now = Local::now();
+
+        let result = resolve_sensor_value(&panel.sensor[0], &HashMap::new(), &now);
+
+        assert_eq!(result, Some((now.year().to_string(), "°C".to_string())));
+    }
+
+    #[test]
+    fn resolve_sensor_value_prefers_provider_value_over_date_time() {
+        let panel = test_panel(&["DATE_year"]);
+        let values = HashMap::from([("DATE_year".to_string(), "1999".to_string())]);
+
+        let result = resolve_sensor_value(&panel.sensor[0], &values, &Local::now());
+
+        assert_eq!(result, Some(("1999".to_string(), "°C".to_string())));
+    }
+
+    #[test]
+    fn resolve_sensor_value_without_value() {
+        let panel = test_panel(&["cpu_temperature"]);
+
+        let result = resolve_sensor_value(&panel.sensor[0], &HashMap::new(), &Local::now());
+
+        assert_eq!(result, None);
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn qr_code_image_of_known_string() {
+        let dark = Rgba([0, 0, 0, 255]);
+        let light = Rgba([255, 255, 255, 255]);
+
+        // version 2 QR code with 25 modules plus 2 quiet zone modules on each side
+        let image = qr_code_image("https://example.com", Some(66), dark, light).unwrap();
+
+        assert_eq!(image.dimensions(), (58, 58));
+        assert_eq!(*image.get_pixel(0, 0), light);
+        assert_eq!(*image.get_pixel(3, 3), light);
+        // top left finder pattern
+        assert_eq!(*image.get_pixel(4, 4), dark);
+        assert_eq!(*image.get_pixel(4 + 6 * 2, 4), dark);
+        assert_eq!(*image.get_pixel(4 + 7 * 2, 4), light);
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn qr_code_image_too_small() {
+        let image = qr_code_image(
+            "https://example.com",
+            Some(28),
+            Rgba([0, 0, 0, 255]),
+            Rgba([255, 255, 255, 255]),
+        );
+
+        assert!(image.is_none());
+    }
+
+    #[cfg(feature = "qrcode")]
+    #[test]
+    fn qr_code_encoding_failure_is_logged_once_per_sensor() {
+        let panel: Panel = serde_json::from_str(
+            r#"{"sensor": [
+            {"mode": 5, "label": "url", "value": "", "unit": "", "x": 0, "y": 0, "width": 28,
+             "integerDigits": -1, "decimalDigits": -1, "pic": ""}
+            ]}"#,
+        )
+        .unwrap();
+        let values = HashMap::from([("url".to_string(), "https://example.com".to_string())]);
+        let mut renderer = PanelRenderer::new((40, 40), "fonts", "cfg");
+
+        renderer.render(&panel, &values).unwrap();
+        renderer.render(&panel, &values).unwrap();
+
+        assert_eq!(
+            renderer.qr_code_warnings,
+            HashSet::from(["url".to_string()])
+        );
+    }
+}
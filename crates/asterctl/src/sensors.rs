@@ -6,7 +6,10 @@
 //! Implementations:
 //! - internal date time sensors
 //! - file-based value provider with simple key-value pairs.
+//! - mock sensor values for demos and testing without a sensor value provider.
+//! - SQLite database query, requires the `sqlite` feature.
 
+use anyhow::{Context, anyhow};
 use chrono::{DateTime, Datelike, Local, Timelike};
 use log::{debug, error, info, warn};
 use notify::event::{ModifyKind, RenameMode};
@@ -17,8 +20,10 @@ use std::fs;
 use std::io::{BufRead, BufReader};
 use std::ops::DerefMut;
 use std::path::{Path, PathBuf};
-use std::process::exit;
-use std::sync::{Arc, RwLock, mpsc};
+use std::sync::{
+    Arc, Condvar, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, mpsc,
+};
+use std::time::{Duration, Instant};
 
 pub fn get_date_time_value(label: &str, now: &DateTime<Local>) -> Option<String> {
     if !label.starts_with("DATE_") {
@@ -60,11 +65,377 @@ pub fn get_date_time_value(label: &str, now: &DateTime<Local>) -> Option<String>
     Some(value)
 }
 
+/// Acquire a read lock on the shared sensor values.
+///
+/// A poisoned lock, caused by a panic in another thread holding the write lock, is recovered and
+/// the poison flag is cleared. The sensor values may be incomplete, but they are still valid
+/// key-value pairs and will be updated with the next sensor file change.
+pub fn read_lock<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read().unwrap_or_else(|e| {
+        error!("Recovering poisoned sensor value lock");
+        lock.clear_poison();
+        e.into_inner()
+    })
+}
+
+/// Acquire a write lock on the shared sensor values.
+///
+/// A poisoned lock is recovered, see [read_lock].
+pub fn write_lock<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write().unwrap_or_else(|e| {
+        error!("Recovering poisoned sensor value lock");
+        lock.clear_poison();
+        e.into_inner()
+    })
+}
+
+/// Time and number of sensor value updates of the sensor sources.
+///
+/// Shared between the sensor file watcher and the panel loop to detect stalled sensor sources
+/// with a [StaleWatchdog], or to wait for the next sensor value update.
+#[derive(Debug)]
+pub struct SensorActivity {
+    state: Mutex<ActivityState>,
+    updated: Condvar,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ActivityState {
+    last_update: Instant,
+    updates: u64,
+}
+
+impl Default for SensorActivity {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(ActivityState {
+                last_update: Instant::now(),
+                updates: 0,
+            }),
+            updated: Condvar::new(),
+        }
+    }
+}
+
+impl SensorActivity {
+    /// Record a sensor value update at the current time and wake up all waiting threads.
+    pub fn touch(&self) {
+        let mut state = self.lock_state();
+        state.last_update = Instant::now();
+        state.updates += 1;
+        self.updated.notify_all();
+    }
+
+    /// Time of the last sensor value update, or the creation time if there was no update yet.
+    pub fn last_update(&self) -> Instant {
+        self.lock_state().last_update
+    }
+
+    /// Number of sensor value updates.
+    pub fn update_count(&self) -> u64 {
+        self.lock_state().updates
+    }
+
+    /// Wait for a sensor value update after the given update count.
+    ///
+    /// # Arguments
+    ///
+    /// * `seen`: last seen update count, see [SensorActivity::update_count].
+    /// * `timeout`: maximum time to wait, or `None` to wait forever.
+    ///
+    /// returns: the new update count, or None if there was no update within the timeout.
+    pub fn wait_for_update(&self, seen: u64, timeout: Option<Duration>) -> Option<u64> {
+        let state = self.lock_state();
+        let state = match timeout {
+            Some(timeout) => {
+                let (state, result) = self
+                    .updated
+                    .wait_timeout_while(state, timeout, |state| state.updates <= seen)
+                    .unwrap_or_else(PoisonError::into_inner);
+                if result.timed_out() {
+                    return None;
+                }
+                state
+            }
+            None => self
+                .updated
+                .wait_while(state, |state| state.updates <= seen)
+                .unwrap_or_else(PoisonError::into_inner),
+        };
+
+        Some(state.updates)
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, ActivityState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Watchdog to detect stalled sensor sources.
+///
+/// The sensor values are considered stale if no sensor source has been updated within the
+/// configured timeout. State changes are logged once.
+#[derive(Debug)]
+pub struct StaleWatchdog {
+    timeout: Duration,
+    stale: bool,
+}
+
+impl StaleWatchdog {
+    /// Create a new watchdog with the given maximum time between sensor value updates.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            stale: false,
+        }
+    }
+
+    /// Check if the sensor values are stale.
+    ///
+    /// # Arguments
+    ///
+    /// * `last_update`: time of the last sensor value update, see [SensorActivity::last_update].
+    /// * `now`: current time.
+    ///
+    /// returns: true if the last update is older than the timeout.
+    pub fn check(&mut self, last_update: Instant, now: Instant) -> bool {
+        let inactive = now.saturating_duration_since(last_update);
+        let stale = inactive >= self.timeout;
+
+        if stale && !self.stale {
+            warn!(
+                "Sensor values are stale: no sensor update since {}s",
+                inactive.as_secs()
+            );
+        } else if !stale && self.stale {
+            info!("Sensor values are updated again");
+        }
+        self.stale = stale;
+
+        stale
+    }
+}
+
+/// Idle detector to switch off the display while the system is idle.
+///
+/// The system is idle if the value of the configured sensor, for example the CPU usage, stays
+/// below the threshold for the given timeout. The idle state ends with the first value at or above
+/// the threshold. Missing or non-numeric sensor values keep the current state. State changes are
+/// logged once.
+#[derive(Debug)]
+pub struct IdleDetector {
+    label: String,
+    threshold: f64,
+    timeout: Duration,
+    below_since: Option<Instant>,
+    idle: bool,
+}
+
+impl IdleDetector {
+    /// Create a new idle detector.
+    ///
+    /// # Arguments
+    ///
+    /// * `label`: sensor label of the activity value.
+    /// * `threshold`: activity values below the threshold are considered idle.
+    /// * `timeout`: minimum time below the threshold before the system is idle.
+    pub fn new(label: impl Into<String>, threshold: f64, timeout: Duration) -> Self {
+        Self {
+            label: label.into(),
+            threshold,
+            timeout,
+            below_since: None,
+            idle: false,
+        }
+    }
+
+    /// Check if the system is idle.
+    ///
+    /// # Arguments
+    ///
+    /// * `values`: current sensor values.
+    /// * `now`: current time.
+    ///
+    /// returns: true if the activity value has been below the threshold for the timeout.
+    pub fn check(&mut self, values: &HashMap<String, String>, now: Instant) -> bool {
+        let Some(value) = values
+            .get(&self.label)
+            .and_then(|value| crate::parse_leading_number(value))
+        else {
+            return self.idle;
+        };
+
+        if value >= self.threshold {
+            self.below_since = None;
+            if self.idle {
+                info!("Activity resumed: {} = {value}", self.label);
+            }
+            self.idle = false;
+        } else {
+            let below_since = *self.below_since.get_or_insert(now);
+            if !self.idle && now.saturating_duration_since(below_since) >= self.timeout {
+                info!(
+                    "System is idle: {} below {} for {}s",
+                    self.label,
+                    self.threshold,
+                    self.timeout.as_secs()
+                );
+                self.idle = true;
+            }
+        }
+
+        self.idle
+    }
+
+    /// Idle state of the last [IdleDetector::check].
+    pub fn is_idle(&self) -> bool {
+        self.idle
+    }
+}
+
+/// Kind of generated mock sensor value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum MockGenerator {
+    /// Usage percentage oscillating between 5 and 95%.
+    Usage,
+    /// Temperature slowly drifting between 35 and 75 °C.
+    Temperature,
+    /// Storage temperature or used space, depending on the label.
+    Storage,
+    /// Network speed between 1 and 999.
+    Speed,
+    /// Current local time in `HH:MM:SS` format.
+    Clock,
+}
+
+/// Mock sensor value generators by label prefix. The first matching prefix is used.
+const MOCK_GENERATORS: &[(&str, MockGenerator)] = &[
+    ("cpu_temperature", MockGenerator::Temperature),
+    ("gpu_temperature", MockGenerator::Temperature),
+    ("memory_temperature", MockGenerator::Temperature),
+    ("motherboard_temperature", MockGenerator::Temperature),
+    ("storage_", MockGenerator::Storage),
+    ("cpu_", MockGenerator::Usage),
+    ("gpu_", MockGenerator::Usage),
+    ("memory_", MockGenerator::Usage),
+    ("net_upload", MockGenerator::Speed),
+    ("net_download", MockGenerator::Speed),
+    ("clock", MockGenerator::Clock),
+    ("time", MockGenerator::Clock),
+];
+
+/// Generator of plausible, changing sensor values for demos without real sensor values.
+///
+/// A generator is selected by the label name prefix, e.g. `cpu_` for a usage sine wave or
+/// `cpu_temperature` for a drifting temperature. Labels without a matching generator are not set.
+#[derive(Debug)]
+pub struct MockSensors {
+    sensors: Vec<(String, MockGenerator, f64)>,
+    tick: u64,
+}
+
+impl MockSensors {
+    /// Create a mock sensor value generator for the given sensor labels.
+    pub fn new<I, S>(labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut sensors: Vec<(String, MockGenerator, f64)> = Vec::new();
+        for label in labels {
+            let label = label.into();
+            if sensors.iter().any(|(existing, _, _)| *existing == label) {
+                continue;
+            }
+            let lowercase = label.to_ascii_lowercase();
+            if let Some((_, generator)) = MOCK_GENERATORS
+                .iter()
+                .find(|(prefix, _)| lowercase.starts_with(prefix))
+            {
+                // different phase per label, so that sensors of the same kind don't change in sync
+                let phase = label.bytes().map(f64::from).sum::<f64>() % 100.0 / 10.0;
+                sensors.push((label, *generator, phase));
+            }
+        }
+
+        Self { sensors, tick: 0 }
+    }
+
+    /// Number of sensor labels with a mock value generator.
+    pub fn len(&self) -> usize {
+        self.sensors.len()
+    }
+
+    /// Returns true if none of the sensor labels has a mock value generator.
+    pub fn is_empty(&self) -> bool {
+        self.sensors.is_empty()
+    }
+
+    /// Generate the next values of all mock sensors and store them in the given map.
+    pub fn tick(&mut self, values: &mut HashMap<String, String>) {
+        let t = self.tick as f64;
+        for (label, generator, phase) in &self.sensors {
+            let value = match generator {
+                MockGenerator::Usage => format!("{:.1}", 50.0 + 45.0 * (t * 0.2 + phase).sin()),
+                MockGenerator::Temperature => mock_temperature(t, *phase, 55.0, 20.0),
+                MockGenerator::Storage if label.to_ascii_lowercase().contains("temperature") => {
+                    mock_temperature(t, *phase, 40.0, 10.0)
+                }
+                MockGenerator::Storage => format!("{:.0}", 50.0 + 30.0 * (t * 0.01 + phase).sin()),
+                MockGenerator::Speed => format!("{:.0}", 500.0 + 499.0 * (t * 0.3 + phase).sin()),
+                MockGenerator::Clock => Local::now().format("%H:%M:%S").to_string(),
+            };
+            values.insert(label.clone(), value);
+        }
+        self.tick += 1;
+    }
+}
+
+/// Slowly drifting temperature with a small jitter within `center ± range`.
+fn mock_temperature(t: f64, phase: f64, center: f64, range: f64) -> String {
+    let drift = 0.8 * (t * 0.05 + phase).sin() + 0.2 * (t * 0.7 + phase).sin();
+    format!("{:.0}", center + range * drift)
+}
+
+/// Generate mock sensor values in a separate thread, see [MockSensors].
+///
+/// # Arguments
+///
+/// * `mock`: mock sensor value generator.
+/// * `values`: a shared, reader-writer lock protected HashMap
+/// * `interval`: time between two generated values.
+///
+/// returns: the shared [SensorActivity] with the time of the last sensor value update.
+pub fn start_mock_sensors(
+    mut mock: MockSensors,
+    values: Arc<RwLock<HashMap<String, String>>>,
+    interval: Duration,
+) -> Arc<SensorActivity> {
+    info!("Starting mock sensor values for {} sensors", mock.len());
+    mock.tick(write_lock(&values).deref_mut());
+
+    let activity = Arc::new(SensorActivity::default());
+    let mock_activity = activity.clone();
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(interval);
+            mock.tick(write_lock(&values).deref_mut());
+            mock_activity.touch();
+        }
+    });
+
+    activity
+}
+
 /// Read all sensor value source files from the given path and stort monitoring for changes.
 ///
 /// The source path is either a single sensor source file or a directory containing multiple sensor
 /// source files.
 ///
+/// If the source path doesn't exist yet, for example if the sensor value provider is started
+/// after `asterctl`, the function waits up to `wait_timeout` for the path to appear. The parent
+/// directory is monitored as well, so a source path created later is still detected and read.
+///
 /// The source path is monitored for changes in a separate thread.
 /// All updated files are automatically read and stored in the shared HashMap.
 ///
@@ -73,39 +444,51 @@ pub fn get_date_time_value(label: &str, now: &DateTime<Local>) -> Option<String>
 /// * `source_path`: Single source file path or a directory path.
 /// * `values`: a shared, reader-writer lock protected HashMap
 /// * `sensor_filter`: Optional list of regex filters to filter out matching sensor keys.
+/// * `wait_timeout`: maximum time to wait for a missing source path at startup.
 ///
-/// returns: Result<(), Error>
+/// returns: the shared [SensorActivity] with the time of the last sensor value update.
 pub fn start_file_slurper<P: Into<PathBuf>>(
     source_path: P,
     values: Arc<RwLock<HashMap<String, String>>>,
     sensor_filter: Option<Vec<Regex>>,
-) -> anyhow::Result<()> {
+    wait_timeout: Duration,
+) -> anyhow::Result<Arc<SensorActivity>> {
     let dir_path = source_path.into();
+
+    if !wait_for_path(&dir_path, wait_timeout) {
+        warn!("Sensor path {dir_path:?} not found, waiting for it to be created");
+    }
+
     // read existing file(s)
     {
-        let mut val = values.write().expect("Failed to lock values");
+        let mut val = write_lock(&values);
         read_path(&dir_path, val.deref_mut(), sensor_filter.as_deref())?;
     }
 
+    let activity = Arc::new(SensorActivity::default());
     let file_values = values.clone();
+    let file_activity = activity.clone();
 
-    std::thread::spawn(move || {
-        // watch sensor file/directory for changes
-        let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
-        let mut watcher = match notify::recommended_watcher(tx) {
-            Ok(w) => w,
-            Err(e) => {
-                error!("Failed to initialize watcher: {e}");
-                exit(1);
-            }
-        };
+    // watch sensor file/directory for changes, before returning to not miss any changes
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = notify::recommended_watcher(tx).context("Failed to initialize watcher")?;
 
-        info!("Starting sensor file watcher for {dir_path:?} with filter {sensor_filter:?}");
-        if let Err(e) = watcher.watch(&dir_path, RecursiveMode::NonRecursive) {
-            error!("Failed to start file watcher: {e}");
-            exit(1);
-        }
+    info!("Starting sensor file watcher for {dir_path:?} with filter {sensor_filter:?}");
+    // the parent directory is watched to detect a (re)created sensor file or directory
+    let parent_path = match dir_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    if let Err(e) = watcher.watch(&parent_path, RecursiveMode::NonRecursive) {
+        warn!("Failed to start file watcher for parent directory {parent_path:?}: {e}");
+    }
+    if dir_path.exists() {
+        watcher
+            .watch(&dir_path, RecursiveMode::NonRecursive)
+            .context("Failed to start file watcher")?;
+    }
 
+    std::thread::spawn(move || {
         // Block forever, printing out events as they come in
         for res in rx {
             let event = match res {
@@ -115,16 +498,48 @@ pub fn start_file_slurper<P: Into<PathBuf>>(
                     continue;
                 }
             };
+            // skip changes of other files in the watched parent directory
+            if !event
+                .paths
+                .iter()
+                .any(|path| path == &dir_path || path.parent() == Some(dir_path.as_path()))
+            {
+                continue;
+            }
+            let source_changed = matches!(
+                event.kind,
+                EventKind::Create(_)
+                    | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Name(RenameMode::To))
+            );
+
+            // the sensor file or directory itself has been created or replaced
+            if source_changed && event.paths.contains(&dir_path) {
+                info!("Sensor path {dir_path:?} created");
+                if dir_path.is_dir()
+                    && let Err(e) = watcher.watch(&dir_path, RecursiveMode::NonRecursive)
+                {
+                    warn!("Failed to start file watcher for {dir_path:?}: {e}");
+                }
+                let mut val = write_lock(&file_values);
+                match read_path(&dir_path, val.deref_mut(), sensor_filter.as_deref()) {
+                    Ok(_) => file_activity.touch(),
+                    Err(e) => warn!("Failed to read sensor path {dir_path:?}: {e}"),
+                }
+                continue;
+            }
+
             match event.kind {
                 EventKind::Modify(kind)
                     if matches!(kind, ModifyKind::Data(_) | ModifyKind::Name(RenameMode::To)) =>
                 {
                     for path in event.paths.iter() {
-                        if path.extension().unwrap_or_default() != "txt" {
+                        if path.extension().unwrap_or_default() != "txt"
+                            || path.parent() != Some(dir_path.as_path())
+                        {
                             continue;
                         }
                         debug!("Modified sensor file ({kind:?}): {path:?}");
-                        let mut val = file_values.write().expect("Poisoned sensor RwLock");
+                        let mut val = write_lock(&file_values);
 
                         if let Err(e) =
                             read_key_value_file(path, val.deref_mut(), sensor_filter.as_deref())
@@ -132,6 +547,7 @@ pub fn start_file_slurper<P: Into<PathBuf>>(
                             warn!("Failed to read sensor file {path:?}: {e}");
                             continue;
                         }
+                        file_activity.touch();
                     }
                 }
                 _ => {
@@ -142,11 +558,162 @@ pub fn start_file_slurper<P: Into<PathBuf>>(
         }
     });
 
-    Ok(())
+    Ok(activity)
+}
+
+/// SQLite database sensor value source, see [start_sqlite_poller].
+#[cfg(feature = "sqlite")]
+#[derive(Debug, Clone)]
+pub struct SqliteSource {
+    /// SQLite database file.
+    pub path: PathBuf,
+    /// Query returning the sensor label in the first and the sensor value in the second column.
+    pub query: String,
+    /// Time between two queries.
+    pub interval: Duration,
+}
+
+/// Default query of the [SqliteSource].
+#[cfg(feature = "sqlite")]
+pub const SQLITE_DEFAULT_QUERY: &str = "SELECT label, value FROM sensors";
+
+/// Periodically query sensor values from a SQLite database in a separate thread.
+///
+/// The query results are merged into the shared HashMap, see [read_sqlite]. A missing or locked
+/// database is logged and queried again in the next interval.
+///
+/// # Arguments
+///
+/// * `source`: database file, query and polling interval.
+/// * `values`: a shared, reader-writer lock protected HashMap
+/// * `sensor_filter`: Optional list of regex filters to filter out matching sensor keys.
+/// * `activity`: shared sensor activity, touched after each successful query.
+#[cfg(feature = "sqlite")]
+pub fn start_sqlite_poller(
+    source: SqliteSource,
+    values: Arc<RwLock<HashMap<String, String>>>,
+    sensor_filter: Option<Vec<Regex>>,
+    activity: Arc<SensorActivity>,
+) {
+    info!(
+        "Starting SQLite sensor source {:?} with query: {}",
+        source.path, source.query
+    );
+    std::thread::spawn(move || {
+        let mut last_error = None;
+        loop {
+            // query into a separate map to keep the write lock short
+            let mut result = HashMap::new();
+            match read_sqlite(
+                &source.path,
+                &source.query,
+                &mut result,
+                sensor_filter.as_deref(),
+            ) {
+                Ok(count) => {
+                    if last_error.take().is_some() {
+                        info!("SQLite sensor source {:?} is available again", source.path);
+                    }
+                    debug!("Read {count} sensor values from {:?}", source.path);
+                    write_lock(&values).extend(result);
+                    activity.touch();
+                }
+                Err(e) => {
+                    let error = format!("{e:#}");
+                    if last_error.as_ref() != Some(&error) {
+                        warn!(
+                            "Failed to query SQLite sensor source {:?}: {error}",
+                            source.path
+                        );
+                    }
+                    last_error = Some(error);
+                }
+            }
+            std::thread::sleep(source.interval);
+        }
+    });
+}
+
+/// Query sensor values from a SQLite database.
+///
+/// The database is opened read-only. The query must return the sensor label in the first and the
+/// sensor value in the second column. Text and numeric values are used, `NULL` and binary values
+/// are skipped.
+///
+/// # Arguments
+///
+/// * `path`: SQLite database file.
+/// * `query`: SQL query, e.g. [SQLITE_DEFAULT_QUERY].
+/// * `values`: HashMap to store the sensor values.
+/// * `sensor_filter`: Optional list of regex filters to filter out matching sensor keys.
+///
+/// returns: the number of stored sensor values.
+#[cfg(feature = "sqlite")]
+pub fn read_sqlite<P: AsRef<Path>>(
+    path: P,
+    query: &str,
+    values: &mut HashMap<String, String>,
+    sensor_filter: Option<&[Regex]>,
+) -> anyhow::Result<usize> {
+    use rusqlite::types::Value;
+    use rusqlite::{Connection, OpenFlags};
+
+    let path = path.as_ref();
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("Error opening database {path:?}"))?;
+    // wait for a short write lock of the sensor value collector
+    conn.busy_timeout(Duration::from_secs(1))?;
+
+    let mut stmt = conn.prepare(query)?;
+    let mut rows = stmt.query([])?;
+    let mut count = 0;
+    while let Some(row) = rows.next()? {
+        let key: String = row.get(0)?;
+        let value = match row.get::<_, Value>(1)? {
+            Value::Integer(value) => value.to_string(),
+            Value::Real(value) => value.to_string(),
+            Value::Text(value) => value,
+            Value::Null | Value::Blob(_) => continue,
+        };
+        if let Some(filter) = sensor_filter
+            && is_filtered(&key, filter)
+        {
+            continue;
+        }
+
+        values.insert(key, value);
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Wait for a path to exist.
+///
+/// returns: true if the path exists within the given timeout.
+fn wait_for_path(path: &Path, timeout: Duration) -> bool {
+    let start = Instant::now();
+    if !path.exists() && !timeout.is_zero() {
+        info!(
+            "Waiting up to {}s for sensor path {path:?}",
+            timeout.as_secs_f32()
+        );
+    }
+    while !path.exists() {
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    true
 }
 
 /// Read a single key-value-based source file or all source file for a given directory path.
 ///
+/// Source files of a directory are read in sorted path order. If a key is defined in multiple
+/// files, the value of the file that sorts last is used and a warning is logged.
+///
 /// # Arguments
 ///
 /// * `path`: Single source file path or a directory path.
@@ -154,7 +721,7 @@ pub fn start_file_slurper<P: Into<PathBuf>>(
 /// * `sensor_filter`: Optional list of regex filters to filter out matching sensor keys.
 ///
 /// returns: Result<(), Error>
-fn read_path<P: AsRef<Path>>(
+pub fn read_path<P: AsRef<Path>>(
     path: P,
     values: &mut HashMap<String, String>,
     sensor_filter: Option<&[Regex]>,
@@ -169,18 +736,72 @@ fn read_path<P: AsRef<Path>>(
         return read_key_value_file(path, values, sensor_filter);
     }
 
+    for duplicate in read_dir_files(path, values, sensor_filter)? {
+        warn!(
+            "Sensor key '{}' of {:?} is overwritten by {:?}",
+            duplicate.key, duplicate.previous, duplicate.path
+        );
+    }
+
+    Ok(())
+}
+
+/// Sensor key defined in multiple source files of a directory.
+#[derive(Debug, PartialEq)]
+struct DuplicateKey {
+    key: String,
+    /// Source file of the overwritten value.
+    previous: PathBuf,
+    /// Source file of the used value.
+    path: PathBuf,
+}
+
+/// Read all `.txt` source files of a directory in sorted path order.
+///
+/// A key defined in multiple files is taken from the file that sorts last.
+///
+/// returns: all keys defined in more than one file.
+fn read_dir_files(
+    path: &Path,
+    values: &mut HashMap<String, String>,
+    sensor_filter: Option<&[Regex]>,
+) -> anyhow::Result<Vec<DuplicateKey>> {
+    let mut paths = Vec::new();
     for entry in fs::read_dir(path)? {
         let path = entry?.path();
+        if path.is_file() && path.extension().unwrap_or_default() == "txt" {
+            paths.push(path);
+        }
+    }
+    paths.sort();
 
-        if path.is_file()
-            && path.extension().unwrap_or_default() == "txt"
-            && let Err(e) = read_key_value_file(&path, values, sensor_filter)
-        {
-            warn!("Failed to read sensor file {path:?}: {e}");
+    let mut key_sources: HashMap<String, PathBuf> = HashMap::new();
+    let mut duplicates = Vec::new();
+
+    for path in paths {
+        let file_values = match parse_sensor_file(&path, sensor_filter) {
+            Ok(file_values) => file_values,
+            Err(e) => {
+                warn!("Failed to read sensor file {path:?}: {e}");
+                continue;
+            }
+        };
+
+        for (key, value) in file_values {
+            if let Some(previous) = key_sources.insert(key.clone(), path.clone()) {
+                duplicates.push(DuplicateKey {
+                    key: key.clone(),
+                    previous,
+                    path: path.clone(),
+                });
+            }
+            values.insert(key, value);
         }
     }
 
-    Ok(())
+    duplicates.sort_by(|a, b| a.key.cmp(&b.key));
+
+    Ok(duplicates)
 }
 
 /// Read a key-value-based sensor source file and store content in the provided hashmap.
@@ -189,6 +810,8 @@ fn read_path<P: AsRef<Path>>(
 /// - Lines starting with # are skipped
 /// - Key-value pairs must be separated by `:`
 /// - All keys and values are trimmed
+/// - Invalid UTF-8 characters, e.g. a Latin-1 degree sign, are replaced with `�` and logged,
+///   without skipping the remaining lines of the file
 ///
 /// # Arguments
 ///
@@ -202,34 +825,205 @@ pub fn read_key_value_file<P: AsRef<Path>>(
     values: &mut HashMap<String, String>,
     sensor_filter: Option<&[Regex]>,
 ) -> anyhow::Result<()> {
-    debug!("Reading sensor file {:?}", path.as_ref());
+    let path = path.as_ref();
+    debug!("Reading sensor file {path:?}");
+
+    let file = fs::File::open(path)?;
+    let reader = BufReader::new(file);
+
+    for (idx, line) in reader.split(b'\n').enumerate() {
+        let line = match String::from_utf8(line?) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Invalid UTF-8 in sensor file {path:?} line {}", idx + 1);
+                String::from_utf8_lossy(e.as_bytes()).into_owned()
+            }
+        };
+        parse_key_value_line(&line, values, sensor_filter);
+    }
+
+    Ok(())
+}
+
+/// Read a sensor identifier mapping file and store the mappings in the provided hashmap.
+///
+/// The file format is the same as for [read_key_value_file], with an additional
+/// `@include <path>` directive to read the mappings of another file at this position.
+/// - Relative include paths are resolved against the directory of the including file.
+/// - Later entries overwrite earlier entries, e.g. a machine-specific file can include a base
+///   mapping file and override single mappings.
+/// - Include cycles are rejected with an error.
+///
+/// # Arguments
+///
+/// * `path`: mapping file path to read.
+/// * `values`: HashMap to insert the mappings.
+///
+/// returns: Result<(), Error>
+pub fn read_mapping_file<P: AsRef<Path>>(
+    path: P,
+    values: &mut HashMap<String, String>,
+) -> anyhow::Result<()> {
+    read_mapping_file_with_includes(path.as_ref(), values, &mut Vec::new())
+}
+
+fn read_mapping_file_with_includes(
+    path: &Path,
+    values: &mut HashMap<String, String>,
+    include_chain: &mut Vec<PathBuf>,
+) -> anyhow::Result<()> {
+    debug!("Reading sensor mapping file {path:?}");
+
+    let canonical_path = fs::canonicalize(path)
+        .with_context(|| format!("Error reading sensor mapping file {path:?}"))?;
+    if include_chain.contains(&canonical_path) {
+        return Err(anyhow!(
+            "Include cycle detected in sensor mapping file {path:?}"
+        ));
+    }
+    include_chain.push(canonical_path);
 
     let file = fs::File::open(path)?;
     let reader = BufReader::new(file);
 
     for line in reader.lines() {
         let line = line?;
-        let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
-            continue;
-        }
-        if let Some((key, value)) = line.split_once(':') {
-            if let Some(filter) = sensor_filter
-                && is_filtered(key, filter)
-            {
-                debug!("Filtered: {key}");
+        if let Some(include) = line.trim().strip_prefix("@include") {
+            let include = include.trim();
+            if include.is_empty() {
+                warn!("Skipping @include without a file path in {path:?}");
                 continue;
             }
-
-            values.insert(key.trim().to_string(), value.trim().to_string());
+            let include_path = path.parent().unwrap_or(Path::new("")).join(include);
+            read_mapping_file_with_includes(&include_path, values, include_chain)?;
         } else {
-            warn!("Skipping invalid entry in sensor value file: {line}");
+            parse_key_value_line(&line, values, None);
         }
     }
 
+    include_chain.pop();
+
     Ok(())
 }
 
+/// Parse a key-value-based sensor source file into a new HashMap.
+///
+/// See [parse_sensor_str] for the file format.
+///
+/// # Arguments
+///
+/// * `path`: file path to read.
+/// * `sensor_filter`: Optional list of regex filters to filter out matching sensor keys.
+///
+/// returns: all key-value pairs of the file, or an error if the file cannot be read.
+pub fn parse_sensor_file<P: AsRef<Path>>(
+    path: P,
+    sensor_filter: Option<&[Regex]>,
+) -> anyhow::Result<HashMap<String, String>> {
+    let mut values = HashMap::new();
+    read_key_value_file(path, &mut values, sensor_filter)?;
+    Ok(values)
+}
+
+/// Parse the content of a key-value-based sensor source file.
+///
+/// - Empty lines are skipped
+/// - Lines starting with # are skipped
+/// - Key-value pairs must be separated by `:`, the value may contain further `:` characters
+/// - All keys and values are trimmed
+/// - The unit of a sensor value is provided with a separate key with the `#unit` suffix,
+///   for example `temperature_cpu#unit: °C`
+/// - The optional type of a sensor value is provided with a separate key with the `#type` suffix,
+///   for example `hostname#type: string`, see [ValueType]
+/// - Keys matching a sensor filter are skipped, including `#unit` and `#type` keys
+/// - Invalid lines without a `:` separator are skipped
+///
+/// # Arguments
+///
+/// * `content`: sensor source file content.
+/// * `sensor_filter`: Optional list of regex filters to filter out matching sensor keys.
+///
+/// returns: all key-value pairs of the content.
+pub fn parse_sensor_str(content: &str, sensor_filter: Option<&[Regex]>) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    for line in content.lines() {
+        parse_key_value_line(line, &mut values, sensor_filter);
+    }
+    values
+}
+
+/// Declared type of a sensor value, provided with a separate key with the `#type` suffix.
+///
+/// Untyped sensor values are formatted as number if they can be parsed as number, otherwise they
+/// are handled as text.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValueType {
+    /// Numeric value, formatted with the number settings of a sensor.
+    Number,
+    /// Text value, never parsed or formatted as number.
+    String,
+    /// Boolean value, see [parse_bool]. Numeric sensors use 1 for `true` and 0 for `false`.
+    Bool,
+}
+
+impl ValueType {
+    /// Parse a value type name: `number`, `string` or `bool`. Case-insensitive.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "number" => Some(ValueType::Number),
+            "string" => Some(ValueType::String),
+            "bool" => Some(ValueType::Bool),
+            _ => None,
+        }
+    }
+
+    /// Get the declared type of a sensor value from its `<key>#type` value.
+    ///
+    /// returns: the value type, or None if the value is untyped or the type is unknown.
+    pub fn of(key: &str, values: &HashMap<String, String>) -> Option<Self> {
+        let value_type = values.get(&format!("{key}#type"))?;
+        let parsed = ValueType::parse(value_type);
+        if parsed.is_none() {
+            debug!("Ignoring unknown value type of {key}: {value_type}");
+        }
+        parsed
+    }
+}
+
+/// Parse a boolean sensor value: `true`, `on`, `yes`, `1` or `false`, `off`, `no`, `0`.
+/// Case-insensitive.
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "on" | "yes" | "1" => Some(true),
+        "false" | "off" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_key_value_line(
+    line: &str,
+    values: &mut HashMap<String, String>,
+    sensor_filter: Option<&[Regex]>,
+) {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return;
+    }
+    if let Some((key, value)) = line.split_once(':') {
+        let key = key.trim();
+        if let Some(filter) = sensor_filter
+            && is_filtered(key, filter)
+        {
+            debug!("Filtered: {key}");
+            return;
+        }
+
+        values.insert(key.to_string(), value.trim().to_string());
+    } else {
+        warn!("Skipping invalid entry in sensor value file: {line}");
+    }
+}
+
 fn is_filtered(key: &str, filters: &[Regex]) -> bool {
     filters.iter().any(|re| re.is_match(key))
 }
@@ -283,6 +1077,439 @@ mod tests {
     use super::*;
     use rstest::rstest;
 
+    fn poisoned_lock() -> Arc<RwLock<HashMap<String, String>>> {
+        let lock = Arc::new(RwLock::new(HashMap::from([(
+            "foo".to_string(),
+            "bar".to_string(),
+        )])));
+
+        let thread_lock = lock.clone();
+        let result = std::thread::spawn(move || {
+            let _guard = thread_lock.write().unwrap();
+            panic!("poisoning the lock");
+        })
+        .join();
+
+        assert!(result.is_err());
+        assert!(lock.is_poisoned());
+        lock
+    }
+
+    #[test]
+    fn read_lock_recovers_poisoned_lock() {
+        let lock = poisoned_lock();
+
+        let values = read_lock(&lock);
+
+        assert_eq!(values.get("foo").map(String::as_str), Some("bar"));
+        drop(values);
+        assert!(!lock.is_poisoned());
+    }
+
+    #[test]
+    fn write_lock_recovers_poisoned_lock() {
+        let lock = poisoned_lock();
+
+        write_lock(&lock).insert("foo".to_string(), "updated".to_string());
+
+        assert!(!lock.is_poisoned());
+        assert_eq!(
+            lock.read().unwrap().get("foo").map(String::as_str),
+            Some("updated")
+        );
+    }
+
+    const SENSOR_FILE: &str = "# sensor values
+cpu_temperature: 42.5
+cpu_temperature#unit: °C
+
+  # indented comment
+  memory_usage :  65%  
+invalid line
+uptime: 1d 02:03
+";
+
+    #[test]
+    fn parse_sensor_str_typed_entries() {
+        let values = parse_sensor_str(
+            "build: 42\nbuild#type: string\nfan_on: yes\nfan_on#type: Bool\nload: 1.5\n\
+             load#type: number\nuptime: 7\nuptime#type: duration\n",
+            None,
+        );
+
+        assert_eq!(ValueType::of("build", &values), Some(ValueType::String));
+        assert_eq!(ValueType::of("fan_on", &values), Some(ValueType::Bool));
+        assert_eq!(ValueType::of("load", &values), Some(ValueType::Number));
+        assert_eq!(ValueType::of("uptime", &values), None);
+        assert_eq!(ValueType::of("unknown", &values), None);
+        assert_eq!(parse_bool(&values["fan_on"]), Some(true));
+    }
+
+    #[rstest]
+    #[case("true", Some(true))]
+    #[case(" ON ", Some(true))]
+    #[case("1", Some(true))]
+    #[case("No", Some(false))]
+    #[case("0", Some(false))]
+    #[case("2", None)]
+    #[case("", None)]
+    fn parse_bool_values(#[case] value: &str, #[case] expected: Option<bool>) {
+        assert_eq!(parse_bool(value), expected);
+    }
+
+    #[test]
+    fn parse_sensor_str_skips_comments_and_invalid_lines() {
+        let values = parse_sensor_str(SENSOR_FILE, None);
+
+        assert_eq!(
+            values,
+            HashMap::from([
+                ("cpu_temperature".to_string(), "42.5".to_string()),
+                ("cpu_temperature#unit".to_string(), "°C".to_string()),
+                ("memory_usage".to_string(), "65%".to_string()),
+                ("uptime".to_string(), "1d 02:03".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn parse_sensor_str_filters_keys() {
+        let filter = [
+            Regex::new("#unit$").unwrap(),
+            Regex::new("^uptime$").unwrap(),
+        ];
+
+        let values = parse_sensor_str(SENSOR_FILE, Some(&filter));
+
+        let mut keys: Vec<_> = values.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, ["cpu_temperature", "memory_usage"]);
+    }
+
+    #[test]
+    fn parse_sensor_file_reads_same_as_str() {
+        let path =
+            std::env::temp_dir().join(format!("asterctl-sensor-test-{}.txt", std::process::id()));
+        fs::write(&path, SENSOR_FILE).unwrap();
+
+        let values = parse_sensor_file(&path, None).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(values, parse_sensor_str(SENSOR_FILE, None));
+    }
+
+    #[test]
+    fn parse_sensor_file_with_invalid_utf8_line() {
+        let path = std::env::temp_dir().join(format!(
+            "asterctl-sensor-latin1-test-{}.txt",
+            std::process::id()
+        ));
+        // Latin-1 degree sign in the second line
+        fs::write(
+            &path,
+            b"cpu_temperature: 42\r\ncpu_temperature#unit: \xb0C\r\ngpu_temperature: 50\n",
+        )
+        .unwrap();
+
+        let values = parse_sensor_file(&path, None).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(values.get("cpu_temperature"), Some(&"42".to_string()));
+        assert_eq!(
+            values.get("cpu_temperature#unit"),
+            Some(&"\u{FFFD}C".to_string())
+        );
+        assert_eq!(values.get("gpu_temperature"), Some(&"50".to_string()));
+    }
+
+    #[test]
+    fn parse_sensor_file_missing_file() {
+        assert!(parse_sensor_file("/does/not/exist.txt", None).is_err());
+    }
+
+    #[test]
+    fn read_mapping_file_with_include_chain() {
+        let dir =
+            std::env::temp_dir().join(format!("asterctl-mapping-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("base")).unwrap();
+        fs::write(
+            dir.join("base/base.cfg"),
+            "# base mapping\ncpu_temperature: temperature_cpu\ngpu_temperature: temperature_gpu\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("machine.cfg"),
+            "@include base/base.cfg\ngpu_temperature: temperature_gpu_edge\n",
+        )
+        .unwrap();
+
+        let mut mapping = HashMap::new();
+        let result = read_mapping_file(dir.join("machine.cfg"), &mut mapping);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.is_ok(), "{result:?}");
+        assert_eq!(
+            mapping,
+            HashMap::from([
+                ("cpu_temperature".to_string(), "temperature_cpu".to_string()),
+                (
+                    "gpu_temperature".to_string(),
+                    "temperature_gpu_edge".to_string()
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn read_mapping_file_rejects_self_include() {
+        let path = std::env::temp_dir().join(format!(
+            "asterctl-mapping-cycle-test-{}.cfg",
+            std::process::id()
+        ));
+        let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+        fs::write(
+            &path,
+            format!("cpu: temperature_cpu\n@include {file_name}\n"),
+        )
+        .unwrap();
+
+        let result = read_mapping_file(&path, &mut HashMap::new());
+        fs::remove_file(&path).unwrap();
+
+        let error = result.expect_err("include cycle must be rejected");
+        assert!(error.to_string().contains("Include cycle"), "{error}");
+    }
+
+    fn wait_for_value(values: &RwLock<HashMap<String, String>>, key: &str) -> Option<String> {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            if let Some(value) = read_lock(values).get(key) {
+                return Some(value.clone());
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        None
+    }
+
+    #[test]
+    fn file_slurper_reads_directory_created_after_start() {
+        let base_dir =
+            std::env::temp_dir().join(format!("asterctl-slurper-test-{}", std::process::id()));
+        let sensor_dir = base_dir.join("sensors");
+        let _ = fs::remove_dir_all(&base_dir);
+        fs::create_dir_all(&base_dir).unwrap();
+        let values = Arc::new(RwLock::new(HashMap::new()));
+
+        start_file_slurper(&sensor_dir, values.clone(), None, Duration::ZERO).unwrap();
+        assert!(read_lock(&values).is_empty());
+
+        fs::create_dir(&sensor_dir).unwrap();
+        let tmp_file = sensor_dir.join("values.tmp");
+        fs::write(&tmp_file, "cpu_temperature: 42\n").unwrap();
+        fs::rename(&tmp_file, sensor_dir.join("values.txt")).unwrap();
+
+        assert_eq!(
+            wait_for_value(&values, "cpu_temperature").as_deref(),
+            Some("42")
+        );
+
+        fs::remove_dir_all(&base_dir).unwrap();
+    }
+
+    #[test]
+    fn read_path_duplicate_key_uses_last_sorted_file() {
+        let dir =
+            std::env::temp_dir().join(format!("asterctl-duplicate-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("b.txt"), "cpu_temperature: 50\nb_only: 2\n").unwrap();
+        fs::write(dir.join("a.txt"), "cpu_temperature: 40\na_only: 1\n").unwrap();
+        fs::write(dir.join("c.dat"), "cpu_temperature: 60\n").unwrap();
+
+        let mut values = HashMap::new();
+        let duplicates = read_dir_files(&dir, &mut values, None).unwrap();
+
+        assert_eq!(
+            duplicates,
+            vec![DuplicateKey {
+                key: "cpu_temperature".to_string(),
+                previous: dir.join("a.txt"),
+                path: dir.join("b.txt"),
+            }]
+        );
+        assert_eq!(
+            values.get("cpu_temperature").map(String::as_str),
+            Some("50")
+        );
+        assert_eq!(values.get("a_only").map(String::as_str), Some("1"));
+        assert_eq!(values.get("b_only").map(String::as_str), Some("2"));
+
+        let mut values = HashMap::new();
+        read_path(&dir, &mut values, None).unwrap();
+        assert_eq!(
+            values.get("cpu_temperature").map(String::as_str),
+            Some("50")
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn mock_sensors_generate_values_within_ranges() {
+        let mut mock = MockSensors::new([
+            "cpu_percent",
+            "cpu_temperature",
+            "storage_ssd[0]['temperature']",
+            "storage_ssd[0]['used']",
+            "net_download_speed",
+            "net_ip_address",
+            "clock",
+        ]);
+        assert_eq!(mock.len(), 6);
+
+        let mut values = HashMap::new();
+        let mut cpu_values = Vec::new();
+        for _ in 0..50 {
+            mock.tick(&mut values);
+            let value = |label: &str| -> f64 { values[label].parse().unwrap() };
+
+            assert!((5.0..=95.0).contains(&value("cpu_percent")));
+            assert!((35.0..=75.0).contains(&value("cpu_temperature")));
+            assert!((30.0..=50.0).contains(&value("storage_ssd[0]['temperature']")));
+            assert!((20.0..=80.0).contains(&value("storage_ssd[0]['used']")));
+            assert!((1.0..=999.0).contains(&value("net_download_speed")));
+            assert_eq!(values["clock"].len(), 8);
+            cpu_values.push(values["cpu_percent"].clone());
+        }
+
+        assert!(!values.contains_key("net_ip_address"));
+        cpu_values.dedup();
+        assert!(cpu_values.len() > 1, "mock values must change");
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn read_sqlite_queries_label_value_rows() {
+        let path =
+            std::env::temp_dir().join(format!("asterctl-sqlite-test-{}.db", std::process::id()));
+        let _ = fs::remove_file(&path);
+        {
+            let conn = rusqlite::Connection::open(&path).unwrap();
+            conn.execute_batch(
+                "CREATE TABLE sensors (label TEXT, value);
+                 INSERT INTO sensors VALUES ('cpu_temperature', 65);
+                 INSERT INTO sensors VALUES ('cpu_percent', 47.5);
+                 INSERT INTO sensors VALUES ('net_ip_address', '192.168.1.2');
+                 INSERT INTO sensors VALUES ('gpu_core', NULL);",
+            )
+            .unwrap();
+        }
+
+        let mut values = HashMap::new();
+        let count = read_sqlite(&path, SQLITE_DEFAULT_QUERY, &mut values, None).unwrap();
+        let filter = [Regex::new("^net_").unwrap()];
+        let mut filtered = HashMap::new();
+        let filtered_count =
+            read_sqlite(&path, SQLITE_DEFAULT_QUERY, &mut filtered, Some(&filter)).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(count, 3);
+        assert_eq!(values.get("cpu_temperature"), Some(&"65".to_string()));
+        assert_eq!(values.get("cpu_percent"), Some(&"47.5".to_string()));
+        assert_eq!(
+            values.get("net_ip_address"),
+            Some(&"192.168.1.2".to_string())
+        );
+        assert!(!values.contains_key("gpu_core"));
+        assert_eq!(filtered_count, 2);
+        assert!(!filtered.contains_key("net_ip_address"));
+    }
+
+    #[cfg(feature = "sqlite")]
+    #[test]
+    fn read_sqlite_missing_database() {
+        let path = std::env::temp_dir().join("asterctl-sqlite-test-missing.db");
+        let mut values = HashMap::new();
+
+        assert!(read_sqlite(&path, SQLITE_DEFAULT_QUERY, &mut values, None).is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn stale_watchdog_detects_inactivity() {
+        let mut watchdog = StaleWatchdog::new(Duration::from_secs(10));
+        let last_update = Instant::now();
+
+        assert!(!watchdog.check(last_update, last_update + Duration::from_secs(9)));
+        assert!(watchdog.check(last_update, last_update + Duration::from_secs(11)));
+        assert!(watchdog.check(last_update, last_update + Duration::from_secs(12)));
+
+        // a new sensor update resets the stale state
+        let last_update = last_update + Duration::from_secs(12);
+        assert!(!watchdog.check(last_update, last_update + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn idle_detector_requires_sustained_low_values() {
+        let mut detector = IdleDetector::new("cpu", 5.0, Duration::from_secs(60));
+        let start = Instant::now();
+        let low = HashMap::from([("cpu".to_string(), "2.5 %".to_string())]);
+        let high = HashMap::from([("cpu".to_string(), "40".to_string())]);
+
+        assert!(!detector.check(&low, start));
+        assert!(!detector.check(&low, start + Duration::from_secs(59)));
+        // a short activity peak restarts the timeout
+        assert!(!detector.check(&high, start + Duration::from_secs(60)));
+        assert!(!detector.check(&low, start + Duration::from_secs(61)));
+        assert!(!detector.check(&low, start + Duration::from_secs(120)));
+        assert!(detector.check(&low, start + Duration::from_secs(121)));
+        assert!(detector.is_idle());
+
+        // missing values keep the idle state
+        assert!(detector.check(&HashMap::new(), start + Duration::from_secs(122)));
+
+        assert!(!detector.check(&high, start + Duration::from_secs(123)));
+        assert!(!detector.is_idle());
+    }
+
+    #[test]
+    fn sensor_activity_wait_for_update() {
+        let activity = Arc::new(SensorActivity::default());
+        assert_eq!(activity.update_count(), 0);
+        assert_eq!(
+            activity.wait_for_update(0, Some(Duration::from_millis(50))),
+            None
+        );
+
+        let thread_activity = activity.clone();
+        let waiter = std::thread::spawn(move || thread_activity.wait_for_update(0, None));
+        std::thread::sleep(Duration::from_millis(20));
+        activity.touch();
+
+        assert_eq!(waiter.join().unwrap(), Some(1));
+        assert_eq!(activity.wait_for_update(0, Some(Duration::ZERO)), Some(1));
+    }
+
+    #[test]
+    fn sensor_activity_touch_updates_last_update() {
+        let activity = SensorActivity::default();
+        let created = activity.last_update();
+
+        std::thread::sleep(Duration::from_millis(5));
+        activity.touch();
+
+        assert!(activity.last_update() > created);
+    }
+
+    #[test]
+    fn wait_for_path_times_out() {
+        let path = Path::new("/does/not/exist");
+
+        assert!(!wait_for_path(path, Duration::from_millis(150)));
+        assert!(wait_for_path(&std::env::temp_dir(), Duration::ZERO));
+    }
+
     #[test]
     fn is_filtered_does_not_filter_without_filters() {
         let key = "foobar";